@@ -111,12 +111,123 @@ impl InstanceConfig {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "fydia_utils::serde")]
+#[serde(default)]
+pub struct WebsocketConfig {
+    /// Seconds between two pings sent by server
+    pub heartbeat_interval: u64,
+    /// Seconds without any frame before a connection is closed
+    pub heartbeat_timeout: u64,
+}
+
+impl WebsocketConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            heartbeat_interval: 30,
+            heartbeat_timeout: 90,
+        }
+    }
+}
+
+impl Default for WebsocketConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "fydia_utils::serde")]
+#[serde(default)]
+pub struct TypingConfig {
+    /// Seconds before a typing user is considered as stopped
+    pub expiry: u64,
+}
+
+impl TypingConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self { expiry: 10 }
+    }
+}
+
+impl Default for TypingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "fydia_utils::serde")]
+#[serde(default)]
+pub struct PinsConfig {
+    /// Max pinned messages in a channel
+    pub max_per_channel: u64,
+}
+
+impl PinsConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            max_per_channel: 50,
+        }
+    }
+}
+
+impl Default for PinsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where attachments, icons and avatars are stored
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+#[serde(tag = "type")]
+pub enum StorageConfig {
+    /// Directory of the local filesystem
+    Local { path: String },
+    /// Bucket of a S3 compatible service
+    S3 {
+        /// Url of the service like `http://127.0.0.1:9000`
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key: String,
+        secret_key: String,
+    },
+}
+
+impl StorageConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::Local {
+            path: "./storage/".to_string(),
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(crate = "fydia_utils::serde")]
 pub struct Config {
     pub instance: InstanceConfig,
     pub server: ServerConfig,
     pub database: DatabaseConfig,
+    #[serde(default)]
+    pub websocket: WebsocketConfig,
+    #[serde(default)]
+    pub typing: TypingConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub pins: PinsConfig,
 }
 
 impl Default for Config {
@@ -125,6 +236,10 @@ impl Default for Config {
             instance: InstanceConfig::new(),
             server: ServerConfig::new(),
             database: DatabaseConfig::default(),
+            websocket: WebsocketConfig::new(),
+            typing: TypingConfig::new(),
+            storage: StorageConfig::new(),
+            pins: PinsConfig::new(),
         }
     }
 }
@@ -1,2 +1,3 @@
+pub mod threads;
 pub mod typing;
 pub mod websockets;
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::handlers::api::manager::websockets::manager::{
+    WbManagerChannelTrait, WebsocketManagerChannel,
+};
+use fydia_sql::impls::{channel::SqlChannel, thread::SqlThread};
+use fydia_sql::sqlpool::DbConnection;
+use fydia_struct::channel::Channel;
+use fydia_struct::event::{Event, EventContent};
+
+/// Time between two checks of inactive threads
+pub const ARCHIVE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Spawn a task that archives threads inactive since their `auto_archive`
+/// and tells members of the server
+pub fn spawn_archiver(database: DbConnection, wbsocket: Arc<WebsocketManagerChannel>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ARCHIVE_CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let threads = match Channel::archive_inactive(&database).await {
+                Ok(threads) => threads,
+                Err(error) => {
+                    error!("{error}");
+                    continue;
+                }
+            };
+
+            for thread in threads {
+                let members = match thread.users(&database).await {
+                    Ok(members) => members,
+                    Err(error) => {
+                        error!("{error}");
+                        continue;
+                    }
+                };
+
+                let event = Event::new(
+                    thread.parent_id.clone(),
+                    EventContent::ThreadArchive {
+                        channelid: thread.id.clone(),
+                    },
+                );
+
+                if let Err(error) = wbsocket.send(&event, &members).await {
+                    error!("{error}");
+                }
+            }
+        }
+    });
+}
@@ -1,35 +1,47 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use crate::handlers::api::manager::websockets::manager::{
     WbManagerChannelTrait, WebsocketManagerChannel,
 };
 use axum::async_trait;
-use flume::Sender;
 use fydia_sql::impls::channel::{SqlChannel, SqlChannelId};
+use fydia_sql::impls::direct_message::{DirectMessageMembers, SqlDirectMessage};
 use fydia_sql::sqlpool::DbConnection;
+use fydia_struct::directmessage::DirectMessage;
 use fydia_struct::event::{Event, EventContent};
 use fydia_struct::manager::{Manager, ManagerChannel, ManagerReceiverTrait};
 use fydia_struct::server::ServerId;
+use fydia_struct::utils::Id;
 use fydia_struct::{channel::ChannelId, user::UserId};
-use parking_lot::RwLock;
+use tokio_util::time::delay_queue::{DelayQueue, Key};
 
 pub type TypingManager = Manager<TypingStruct>;
 
+/// Default time before a typing user is considered as stopped
+pub const DEFAULT_TYPING_EXPIRY: Duration = Duration::from_secs(10);
+
+/// Where an user is typing
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypingTarget {
+    Channel(ServerId, ChannelId),
+    DirectMessage(Id<u32>),
+}
+
 #[derive(Debug)]
 pub enum TypingMessage {
     SetWebSocketManager(Arc<WebsocketManagerChannel>),
-    SetTypingManager(Arc<TypingManagerChannel>),
     SetDatabase(DbConnection),
-    StartTyping(UserId, ChannelId, ServerId),
-    StopTyping(UserId, ChannelId, ServerId),
+    SetExpiry(Duration),
+    StartTyping(UserId, TypingTarget),
+    StopTyping(UserId, TypingTarget),
+    Expired(UserId, TypingTarget),
 }
 
 #[derive(Debug, Default)]
 pub struct TypingStruct {
     wbsocketmanager: Option<Arc<WebsocketManagerChannel>>,
-    selfmanager: Option<Arc<TypingManagerChannel>>,
     database: Option<DbConnection>,
     inner: TypingInner,
 }
@@ -39,13 +51,15 @@ impl TypingStruct {
         self.wbsocketmanager = Some(websocket);
     }
 
-    pub fn set_selfmanager(&mut self, selfmanager: Arc<TypingManagerChannel>) {
-        self.selfmanager = Some(selfmanager);
-    }
-
     pub fn set_database(&mut self, database: DbConnection) {
         self.database = Some(database);
     }
+
+    fn send_event(&self, userid: UserId, target: TypingTarget, start: bool) {
+        if let (Some(wb), Some(database)) = (&self.wbsocketmanager, &self.database) {
+            send_websocket_message(userid, target, start, wb, database);
+        }
+    }
 }
 
 #[async_trait]
@@ -57,260 +71,176 @@ impl ManagerReceiverTrait for TypingStruct {
             TypingMessage::SetWebSocketManager(wbmessage) => {
                 self.set_websocketmanager(wbmessage);
             }
-            TypingMessage::SetTypingManager(typingmanager) => {
-                self.set_selfmanager(typingmanager);
-            }
             TypingMessage::SetDatabase(databasemanager) => {
                 self.set_database(databasemanager);
             }
-            TypingMessage::StartTyping(user, channelid, serverid) => {
-                if let (Some(wb), Some(typing), Some(database)) =
-                    (&self.wbsocketmanager, &self.selfmanager, &self.database)
-                {
-                    if let Err(error) = self
-                        .inner
-                        .start_typing(database, wb, typing, channelid, user, serverid)
-                        .await
-                    {
-                        error!("{error}");
-                    };
+            TypingMessage::SetExpiry(expiry) => self.inner.set_expiry(expiry),
+            TypingMessage::StartTyping(user, target) => {
+                if self.inner.start_typing(&user, &target) {
+                    self.send_event(user, target, true);
                 }
             }
-            TypingMessage::StopTyping(user, channelid, serverid) => {
-                if let (Some(wb), Some(database)) = (&self.wbsocketmanager, &self.database) {
-                    if let Err(error) = self
-                        .inner
-                        .stop_typing(database, wb, channelid, user, serverid)
-                        .await
-                    {
-                        warn!("{error}");
-                    };
-                };
+            TypingMessage::StopTyping(user, target) => {
+                if self.inner.stop_typing(&user, &target) {
+                    self.send_event(user, target, false);
+                }
+            }
+            TypingMessage::Expired(user, target) => {
+                if self.inner.expire(&user, &target) {
+                    self.send_event(user, target, false);
+                }
             }
         }
     }
+
+    async fn next_message(&mut self) -> Self::Message {
+        let (user, target) = self.inner.next_expired().await;
+
+        TypingMessage::Expired(user, target)
+    }
 }
 
+/// `TypingInner` contains all typing users and their deadline
 #[derive(Debug)]
-pub struct TypingInner(RwLock<HashMap<ChannelId, HashMap<UserId, Task>>>);
+pub struct TypingInner {
+    typing: HashMap<TypingTarget, HashMap<UserId, Key>>,
+    deadlines: DelayQueue<(UserId, TypingTarget)>,
+    expiry: Duration,
+}
 
 impl TypingInner {
-    /// Renew a typing task
-    ///
-    /// # Errors
-    /// Return an error if :
-    /// * `ChannelId` isn't in hashmap
-    pub async fn renew_typing(
-        &mut self,
-        typingmanager: &Arc<TypingManagerChannel>,
-        channelid: ChannelId,
-        userid: UserId,
-        serverid: ServerId,
-    ) -> Result<(), String> {
-        let mut inner = self.0.write();
-        let usertypings = inner.get_mut(&channelid).ok_or("No Channel in HashMap")?;
-        let mut task = Task::new(typingmanager, &userid, &channelid, &serverid);
-
-        task.spawn();
-        usertypings.insert(userid, task);
-
-        Ok(())
+    pub fn set_expiry(&mut self, expiry: Duration) {
+        self.expiry = expiry;
     }
-    /// Stop Typing
-    ///
-    /// # Errors
-    /// Return an error if :
-    /// * [`self::renew_typing`] return an error
-    pub async fn start_typing(
-        &mut self,
-        database: &DbConnection,
-        websocket: &Arc<WebsocketManagerChannel>,
-        typingmanager: &Arc<TypingManagerChannel>,
-        channelid: ChannelId,
-        userid: UserId,
-        serverid: ServerId,
-    ) -> Result<(), String> {
-        let is_exists = self.user_exists_in_channel(&channelid, &userid);
-        if is_exists {
-            return self
-                .renew_typing(typingmanager, channelid, userid, serverid)
-                .await;
-        }
 
-        let task = Task::new(typingmanager, &userid, &channelid, &serverid);
+    /// Start typing or push the deadline back if user is already typing.
+    ///
+    /// Return `true` if user wasn't typing
+    pub fn start_typing(&mut self, userid: &UserId, target: &TypingTarget) -> bool {
+        let users = self.typing.entry(target.clone()).or_default();
 
-        self.insert_user(&channelid, &userid, task);
+        if let Some(key) = users.get(userid) {
+            self.deadlines.reset(key, self.expiry);
+            return false;
+        }
 
-        send_websocket_message(
-            EventContent::StartTyping {
-                userid,
-                channelid: channelid.clone(),
-            },
-            serverid,
-            channelid,
-            websocket,
-            database,
-        );
+        let key = self
+            .deadlines
+            .insert((userid.clone(), target.clone()), self.expiry);
+        users.insert(userid.clone(), key);
 
-        Ok(())
+        true
     }
 
-    pub fn insert_channel(&mut self, channelid: &ChannelId) {
-        let mut inner = self.0.write();
-        if !inner.contains_key(channelid) {
-            inner.insert(channelid.clone(), HashMap::new());
+    /// Stop typing.
+    ///
+    /// Return `true` if user was typing
+    pub fn stop_typing(&mut self, userid: &UserId, target: &TypingTarget) -> bool {
+        match self.remove_user(userid, target) {
+            Some(key) => {
+                self.deadlines.remove(&key);
+                true
+            }
+            None => false,
         }
     }
 
-    pub fn insert_user(&mut self, channelid: &ChannelId, userid: &UserId, mut task: Task) {
-        self.insert_channel(channelid);
-        println!("Channel Insert");
-        let mut inner = self.0.write();
-        if let Some(channel) = inner.get_mut(channelid) {
-            if !channel.contains_key(userid) {
-                task.spawn();
-                channel.insert(userid.clone(), task);
-            }
-        }
+    /// Remove an user whose deadline is expired.
+    ///
+    /// Return `true` if user was typing
+    pub fn expire(&mut self, userid: &UserId, target: &TypingTarget) -> bool {
+        self.remove_user(userid, target).is_some()
     }
 
-    pub fn user_exists_in_channel(&self, channelid: &ChannelId, userid: &UserId) -> bool {
-        if let Some(channel) = self.0.read().get(channelid) {
-            if channel.contains_key(userid) {
-                return true;
-            }
+    fn remove_user(&mut self, userid: &UserId, target: &TypingTarget) -> Option<Key> {
+        let users = self.typing.get_mut(target)?;
+        let key = users.remove(userid)?;
+
+        if users.is_empty() {
+            self.typing.remove(target);
         }
 
-        false
+        Some(key)
     }
 
-    /// Stop Typing
-    ///
-    /// # Errors
-    /// Return an error if:
-    /// * `ChannelId` isn't in hashmap
-    /// * `UserId` isn't in hashmap
-    pub async fn stop_typing(
-        &mut self,
-        database: &DbConnection,
-        websocket: &Arc<WebsocketManagerChannel>,
-        channelid: ChannelId,
-        userid: UserId,
-        serverid: ServerId,
-    ) -> Result<(), String> {
-        let mut inner = self.0.write();
-
-        let users = inner
-            .get_mut(&channelid)
-            .ok_or_else(|| "No ChannelId in HashMap".to_string())?;
-
-        let user_task = users
-            .get_mut(&userid)
-            .ok_or_else(|| "No User in HashMap".to_string())?;
-
-        user_task.kill();
-
-        users.remove(&userid);
-
-        send_websocket_message(
-            EventContent::StopTyping {
-                userid,
-                channelid: channelid.clone(),
-            },
-            serverid,
-            channelid,
-            websocket,
-            database,
-        );
-
-        Ok(())
+    /// Wait for the next expired deadline
+    pub async fn next_expired(&mut self) -> (UserId, TypingTarget) {
+        if self.deadlines.is_empty() {
+            return futures::future::pending().await;
+        }
+
+        match futures::future::poll_fn(|cx| self.deadlines.poll_expired(cx)).await {
+            Some(expired) => expired.into_inner(),
+            None => futures::future::pending().await,
+        }
     }
 }
 
 impl Default for TypingInner {
     fn default() -> Self {
-        Self(RwLock::new(HashMap::new()))
+        Self {
+            typing: HashMap::new(),
+            deadlines: DelayQueue::new(),
+            expiry: DEFAULT_TYPING_EXPIRY,
+        }
     }
 }
 
 fn send_websocket_message(
-    event: EventContent,
-    serverid: ServerId,
-    channelid: ChannelId,
+    userid: UserId,
+    target: TypingTarget,
+    start: bool,
     websocket: &Arc<WebsocketManagerChannel>,
     database: &DbConnection,
 ) {
     let database = database.clone();
     let websocket = websocket.clone();
     tokio::task::spawn(async move {
-        let users = channelid
-            .channel(&database)
-            .await
-            .map_err(|e| e.to_string())?
-            .users(&database)
-            .await
-            .map_err(|e| e.to_string())?;
-
-        websocket
-            .send(&Event::new(serverid.clone(), event), &users)
-            .await
-    });
-}
-
-#[derive(Debug, Clone)]
-pub struct Task(Option<Arc<Sender<bool>>>, TaskValue); // Add Mutex
-#[derive(Clone, Debug)]
-pub struct TaskValue(Arc<TypingManagerChannel>, UserId, ChannelId, ServerId);
-
-impl Task {
-    pub fn new(
-        typingsocketmanager: &Arc<TypingManagerChannel>,
-        executor: &UserId,
-        channelid: &ChannelId,
-        serverid: &ServerId,
-    ) -> Self {
-        Self(
-            None,
-            TaskValue(
-                typingsocketmanager.clone(),
-                executor.clone(),
-                channelid.clone(),
-                serverid.clone(),
-            ),
-        )
-    }
+        let (serverid, users, event) = match target {
+            TypingTarget::Channel(serverid, channelid) => {
+                let users = channelid
+                    .channel(&database)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .users(&database)
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                let event = if start {
+                    EventContent::StartTyping { userid, channelid }
+                } else {
+                    EventContent::StopTyping { userid, channelid }
+                };
 
-    pub fn spawn(&mut self) {
-        let task = self.clone();
-        let (sender, receiver) = flume::bounded::<bool>(1);
-        let instant = Instant::now();
-        let value = task.1;
-        tokio::task::spawn(async move {
-            loop {
-                if instant.elapsed().as_secs() == 10 {
-                    if let Err(error) = value.0.stop_typing(value.1, value.2, value.3) {
-                        error!("{error}");
+                (serverid, users, event)
+            }
+            TypingTarget::DirectMessage(directmessage_id) => {
+                let users = DirectMessage::get(directmessage_id.clone(), &database)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .members(&database)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .members;
+
+                let event = if start {
+                    EventContent::DirectMessageStartTyping {
+                        userid,
+                        directmessage_id,
                     }
-
-                    return;
-                }
-                if let Ok(value) = receiver.recv_timeout(Duration::from_millis(10)) {
-                    if value {
-                        return;
+                } else {
+                    EventContent::DirectMessageStopTyping {
+                        userid,
+                        directmessage_id,
                     }
-                }
-            }
-        });
-        self.0 = Some(Arc::new(sender));
-    }
+                };
 
-    pub fn kill(&mut self) {
-        if let Some(sender) = &self.0 {
-            sender.send(true).unwrap_or(());
+                (ServerId::new(String::new()), users, event)
+            }
+        };
 
-            self.0 = None;
-        }
-    }
+        websocket.send(&Event::new(serverid, event), &users).await
+    });
 }
 
 pub type TypingManagerChannel = ManagerChannel<TypingMessage>;
@@ -323,43 +253,33 @@ pub trait TypingManagerChannelTrait {
     /// * typing manager is unreachable
     fn set_websocketmanager(&self, wbsocket: &Arc<WebsocketManagerChannel>) -> Result<(), String>;
 
-    /// Set a reference to self
+    /// Set database
     ///
     /// # Errors
     /// Return an error if:
     /// * typing manager is unreachable
-    fn set_selfmanager(&self, selfmanager: &Arc<TypingManagerChannel>) -> Result<(), String>;
+    fn set_database(&self, dbconnection: &DbConnection) -> Result<(), String>;
 
-    /// Set database
+    /// Set time before a typing user is considered as stopped
     ///
     /// # Errors
     /// Return an error if:
     /// * typing manager is unreachable
-    fn set_database(&self, dbconnection: &DbConnection) -> Result<(), String>;
+    fn set_expiry(&self, expiry: Duration) -> Result<(), String>;
 
-    /// Start typing task
+    /// Start typing or renew it
     ///
     /// # Errors
     /// Return an error if:
     /// * typing manager is unreachable
-    fn start_typing(
-        &self,
-        userid: UserId,
-        channelid: ChannelId,
-        serverid: ServerId,
-    ) -> Result<(), String>;
-
-    /// Stop typing task
+    fn start_typing(&self, userid: UserId, target: TypingTarget) -> Result<(), String>;
+
+    /// Stop typing
     ///
     /// # Errors
     /// Return an error if:
     /// * typing manager is unreachable
-    fn stop_typing(
-        &self,
-        userid: UserId,
-        channelid: ChannelId,
-        serverid: ServerId,
-    ) -> Result<(), String>;
+    fn stop_typing(&self, userid: UserId, target: TypingTarget) -> Result<(), String>;
 }
 
 impl TypingManagerChannelTrait for TypingManagerChannel {
@@ -370,40 +290,30 @@ impl TypingManagerChannelTrait for TypingManagerChannel {
             .map_err(|f| f.to_string())
     }
 
-    fn set_selfmanager(&self, selfmanager: &Arc<TypingManagerChannel>) -> Result<(), String> {
+    fn set_database(&self, dbconnection: &DbConnection) -> Result<(), String> {
         self.0
-            .send(TypingMessage::SetTypingManager(selfmanager.clone()))
+            .send(TypingMessage::SetDatabase(dbconnection.clone()))
             .map(|_| ())
             .map_err(|f| f.to_string())
     }
 
-    fn set_database(&self, dbconnection: &DbConnection) -> Result<(), String> {
+    fn set_expiry(&self, expiry: Duration) -> Result<(), String> {
         self.0
-            .send(TypingMessage::SetDatabase(dbconnection.clone()))
+            .send(TypingMessage::SetExpiry(expiry))
             .map(|_| ())
             .map_err(|f| f.to_string())
     }
 
-    fn start_typing(
-        &self,
-        userid: UserId,
-        channelid: ChannelId,
-        serverid: ServerId,
-    ) -> Result<(), String> {
+    fn start_typing(&self, userid: UserId, target: TypingTarget) -> Result<(), String> {
         self.0
-            .send(TypingMessage::StartTyping(userid, channelid, serverid))
+            .send(TypingMessage::StartTyping(userid, target))
             .map(|_| ())
             .map_err(|f| f.to_string())
     }
 
-    fn stop_typing(
-        &self,
-        userid: UserId,
-        channelid: ChannelId,
-        serverid: ServerId,
-    ) -> Result<(), String> {
+    fn stop_typing(&self, userid: UserId, target: TypingTarget) -> Result<(), String> {
         self.0
-            .send(TypingMessage::StopTyping(userid, channelid, serverid))
+            .send(TypingMessage::StopTyping(userid, target))
             .map(|_| ())
             .map_err(|f| f.to_string())
     }
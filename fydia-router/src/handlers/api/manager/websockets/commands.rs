@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use fydia_sql::{
+    impls::{
+        channel::SqlChannel,
+        direct_message::{DirectMessageMembers, SqlDirectMessage},
+        members::SqlMembers,
+        message::SqlMessage,
+        read_state::SqlReadState,
+        server::SqlServerId,
+    },
+    sqlpool::DbConnection,
+};
+use fydia_struct::{
+    channel::{Channel, ChannelId},
+    directmessage::DirectMessage,
+    messages::{Message, ReadState},
+    permission::PermissionValue,
+    server::{Members, ServerId},
+    user::User,
+    utils::Id,
+    websocket::{WebsocketCommand, WebsocketError, WebsocketFrame},
+};
+
+use super::{
+    manager::{WbManagerChannelTrait, WebsocketManagerChannel},
+    ChannelMessage, WbSender,
+};
+use crate::handlers::api::manager::typing::{
+    TypingManagerChannel, TypingManagerChannelTrait, TypingTarget,
+};
+use crate::handlers::api::server::check_channel_permission;
+
+/// Execute a command sent by a client and return the frame to reply with, if any
+///
+/// # Errors
+/// Return an error if:
+/// * user isn't a member of the server
+/// * channel or message doesn't exist
+/// * user doesn't have the permission needed in the channel
+/// * a manager or the database is unreachable
+pub async fn dispatch(
+    command: WebsocketCommand,
+    user: &User,
+    sender: &WbSender,
+    wbmanager: &Arc<WebsocketManagerChannel>,
+    database: &DbConnection,
+    typing: &Arc<TypingManagerChannel>,
+) -> Result<Option<WebsocketFrame>, WebsocketError> {
+    match command {
+        WebsocketCommand::Subscribe { server_ids } => {
+            for server_id in &server_ids {
+                check_member(user, server_id, database).await?;
+            }
+
+            sender
+                .send(ChannelMessage::Subscribe(server_ids))
+                .map_err(|error| WebsocketError::Internal(error.to_string()))?;
+
+            Ok(None)
+        }
+        WebsocketCommand::StartTyping {
+            server_id,
+            channel_id,
+        } => {
+            check_channel(
+                user,
+                &server_id,
+                &channel_id,
+                &PermissionValue::Write,
+                database,
+            )
+            .await?;
+
+            typing
+                .start_typing(
+                    user.id.clone(),
+                    TypingTarget::Channel(server_id, channel_id),
+                )
+                .map_err(WebsocketError::Internal)?;
+
+            Ok(None)
+        }
+        WebsocketCommand::StopTyping {
+            server_id,
+            channel_id,
+        } => {
+            check_channel(
+                user,
+                &server_id,
+                &channel_id,
+                &PermissionValue::Write,
+                database,
+            )
+            .await?;
+
+            typing
+                .stop_typing(
+                    user.id.clone(),
+                    TypingTarget::Channel(server_id, channel_id),
+                )
+                .map_err(WebsocketError::Internal)?;
+
+            Ok(None)
+        }
+        WebsocketCommand::StartDirectMessageTyping { directmessage_id } => {
+            check_direct_message(user, &directmessage_id, database).await?;
+
+            typing
+                .start_typing(
+                    user.id.clone(),
+                    TypingTarget::DirectMessage(directmessage_id),
+                )
+                .map_err(WebsocketError::Internal)?;
+
+            Ok(None)
+        }
+        WebsocketCommand::StopDirectMessageTyping { directmessage_id } => {
+            check_direct_message(user, &directmessage_id, database).await?;
+
+            typing
+                .stop_typing(
+                    user.id.clone(),
+                    TypingTarget::DirectMessage(directmessage_id),
+                )
+                .map_err(WebsocketError::Internal)?;
+
+            Ok(None)
+        }
+        WebsocketCommand::MarkRead {
+            server_id,
+            channel_id,
+            message_id,
+        } => {
+            check_channel(
+                user,
+                &server_id,
+                &channel_id,
+                &PermissionValue::Read,
+                database,
+            )
+            .await?;
+
+            let message = Message::by_id(&message_id, database)
+                .await
+                .map_err(|error| {
+                    error!("{error}");
+                    WebsocketError::UnknownMessage
+                })?;
+
+            if message.channel_id != channel_id {
+                return Err(WebsocketError::UnknownMessage);
+            }
+
+            ReadState::new(user.id.clone(), channel_id, message.id)
+                .upsert(database)
+                .await
+                .map_err(|error| WebsocketError::Internal(error.to_string()))?;
+
+            Ok(None)
+        }
+        WebsocketCommand::Ping { nonce } => Ok(Some(WebsocketFrame::Pong { nonce })),
+        WebsocketCommand::Resume {
+            session_id,
+            sequence,
+        } => {
+            let frame = match wbmanager
+                .resume(&user.id, sender, session_id, sequence)
+                .await
+                .map_err(WebsocketError::Internal)?
+            {
+                Some(sequence) => WebsocketFrame::Resumed { sequence },
+                None => WebsocketFrame::InvalidSession,
+            };
+
+            Ok(Some(frame))
+        }
+    }
+}
+
+async fn check_member(
+    user: &User,
+    server_id: &ServerId,
+    database: &DbConnection,
+) -> Result<(), WebsocketError> {
+    let servers = Members::servers_of(&user.id, database)
+        .await
+        .map_err(|error| WebsocketError::Internal(error.to_string()))?;
+
+    if servers.contains(server_id) {
+        Ok(())
+    } else {
+        Err(WebsocketError::NotMember)
+    }
+}
+
+async fn check_direct_message(
+    user: &User,
+    directmessage_id: &Id<u32>,
+    database: &DbConnection,
+) -> Result<(), WebsocketError> {
+    let directmessage = DirectMessage::get(directmessage_id.clone(), database)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            WebsocketError::NotDirectMessageMember
+        })?;
+
+    let is_member = directmessage
+        .is_member(&user.id, database)
+        .await
+        .map_err(|error| WebsocketError::Internal(error.to_string()))?;
+
+    if is_member {
+        Ok(())
+    } else {
+        Err(WebsocketError::NotDirectMessageMember)
+    }
+}
+
+async fn check_channel(
+    user: &User,
+    server_id: &ServerId,
+    channel_id: &ChannelId,
+    permission: &PermissionValue,
+    database: &DbConnection,
+) -> Result<(), WebsocketError> {
+    check_member(user, server_id, database).await?;
+
+    let channel = Channel::by_id(channel_id, database)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            WebsocketError::UnknownChannel
+        })?;
+
+    if &channel.parent_id != server_id {
+        return Err(WebsocketError::UnknownChannel);
+    }
+
+    let server = server_id
+        .get(database)
+        .await
+        .map_err(|error| WebsocketError::Internal(error.to_string()))?;
+
+    check_channel_permission(user, &server, &channel, permission, database)
+        .await
+        .map_err(|error| {
+            error!("{}", error.get_string());
+            WebsocketError::MissingPermission
+        })
+}
@@ -1,7 +1,7 @@
 use axum::async_trait;
 use tokio::sync::oneshot;
 
-use super::{ChannelMessage, WbChannel, WbManagerMessage, WbSender, WebsocketInner};
+use super::{WbChannel, WbManagerMessage, WbSender, WebsocketInner};
 use fydia_struct::{
     event::Event,
     instance::{Instance, RsaData},
@@ -17,7 +17,16 @@ pub type WebsocketManagerChannel = ManagerChannel<WbManagerMessage>;
 pub trait WbManagerChannelTrait {
     async fn get_channels_of_user(&self, user: &UserId) -> Result<Vec<WbSender>, String>;
     async fn get_new_channel(&self, user: &UserId) -> Option<WbChannel>;
+    async fn live_sessions(&self, user: &UserId) -> Result<usize, String>;
     async fn remove(&self, user: &UserId, wbsender: &WbSender) -> Result<(), String>;
+    async fn session(&self, user: &UserId) -> Result<Option<(String, u64)>, String>;
+    async fn resume(
+        &self,
+        user: &UserId,
+        wbsender: &WbSender,
+        session_id: String,
+        sequence: u64,
+    ) -> Result<Option<u64>, String>;
     async fn send(&self, msg: &Event, user: &[UserId]) -> Result<(), String>;
     async fn send_with_origin_and_key(
         &self,
@@ -48,6 +57,12 @@ impl WbManagerChannelTrait for WebsocketManagerChannel {
         receiver.await.ok()?.ok()
     }
 
+    async fn live_sessions(&self, user: &UserId) -> Result<usize, String> {
+        self.get_channels_of_user(user)
+            .await
+            .map(|channels| channels.len())
+    }
+
     async fn remove(&self, user: &UserId, wbsender: &WbSender) -> Result<(), String> {
         let (sender, receiver) = oneshot::channel::<Result<(), String>>();
         if let Err(error) = self.0.send(WbManagerMessage::Remove(
@@ -61,6 +76,36 @@ impl WbManagerChannelTrait for WebsocketManagerChannel {
         receiver.await.map_err(|error| error.to_string())?
     }
 
+    async fn session(&self, user: &UserId) -> Result<Option<(String, u64)>, String> {
+        let (sender, receiver) = oneshot::channel::<Option<(String, u64)>>();
+        if let Err(error) = self.0.send(WbManagerMessage::Session(user.clone(), sender)) {
+            error!("{}", error.to_string());
+        }
+
+        receiver.await.map_err(|error| error.to_string())
+    }
+
+    async fn resume(
+        &self,
+        user: &UserId,
+        wbsender: &WbSender,
+        session_id: String,
+        sequence: u64,
+    ) -> Result<Option<u64>, String> {
+        let (sender, receiver) = oneshot::channel::<Option<u64>>();
+        if let Err(error) = self.0.send(WbManagerMessage::Resume(
+            user.clone(),
+            wbsender.clone(),
+            session_id,
+            sequence,
+            sender,
+        )) {
+            error!("{}", error.to_string());
+        }
+
+        receiver.await.map_err(|error| error.to_string())
+    }
+
     async fn send(&self, msg: &Event, user: &[UserId]) -> Result<(), String> {
         self.send_with_origin_and_key(msg, user, None, None).await
     }
@@ -73,15 +118,9 @@ impl WbManagerChannelTrait for WebsocketManagerChannel {
         _origin: Option<Instance>,
     ) -> Result<(), String> {
         for i in user {
-            let channels = self.get_channels_of_user(i).await?;
-            for channel in channels {
-                let msg = msg.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = channel.send(ChannelMessage::Message(Box::new(msg))) {
-                        error!("{}", e.to_string());
-                    }
-                });
-            }
+            self.0
+                .send(WbManagerMessage::Dispatch(i.clone(), Box::new(msg.clone())))
+                .map_err(|error| error.to_string())?;
         }
 
         Ok(())
@@ -1,16 +1,25 @@
 #![allow(clippy::unwrap_used)]
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::handlers::api::manager::websockets::ChannelMessage;
-use crate::handlers::basic::{Database, WebsocketManager};
+use crate::handlers::api::manager::typing::TypingManagerChannel;
+use crate::handlers::api::manager::websockets::{commands, ChannelMessage};
+use crate::handlers::basic::{
+    Database, TypingManager, UserFromToken, WebsocketManager, WebsocketSettings,
+};
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::extract::Query;
 use axum::response::IntoResponse;
 use futures::prelude::*;
+use fydia_config::WebsocketConfig;
 use fydia_sql::impls::token::SqlToken;
+use fydia_sql::sqlpool::DbConnection;
 use fydia_struct::querystring::QsToken;
+use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
+use fydia_struct::server::ServerId;
 use fydia_struct::user::{Token, User, UserError};
+use fydia_struct::websocket::{WebsocketCommand, WebsocketError, WebsocketFrame};
 use fydia_utils::{serde::Serialize, serde_json};
 
 use super::manager::{WbManagerChannelTrait, WebsocketManagerChannel};
@@ -18,18 +27,23 @@ use super::manager::{WbManagerChannelTrait, WebsocketManagerChannel};
 pub async fn ws_handler(
     Database(database): Database,
     WebsocketManager(wbsocket): WebsocketManager,
+    TypingManager(typing): TypingManager,
+    WebsocketSettings(config): WebsocketSettings,
     Query(token): Query<QsToken>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
     let token = Token::new(token.token.unwrap_or_default());
     let user = token.get_user(&database).await;
 
-    ws.on_upgrade(move |e| connected(e, wbsocket, user))
+    ws.on_upgrade(move |e| connected(e, wbsocket, typing, database, config, user))
 }
 
 async fn connected(
     socket: WebSocket,
     wbmanager: Arc<WebsocketManagerChannel>,
+    typing: Arc<TypingManagerChannel>,
+    database: DbConnection,
+    config: Arc<WebsocketConfig>,
     user: Result<User, UserError>,
 ) {
     let Ok(user) = user else {
@@ -40,54 +54,160 @@ async fn connected(
         return;
     };
 
+    let hello = match wbmanager.session(&user.id).await {
+        Ok(Some((session_id, sequence))) => WebsocketFrame::Hello {
+            session_id,
+            sequence,
+        },
+        Ok(None) => return,
+        Err(error) => {
+            error!("{error}");
+            return;
+        }
+    };
+
+    match to_websocketmessage(&hello) {
+        Ok(message) => {
+            if let Err(error) = sender.send(ChannelMessage::WebsocketMessage(message)) {
+                error!("{error}");
+            }
+        }
+        Err(error) => error!("{error}"),
+    }
+
     let (mut sink, mut stream) = socket.split();
     let thread_sender = sender.clone();
+    let thread_user = user.clone();
+    let thread_wbmanager = wbmanager.clone();
 
-    tokio::spawn(async move {
+    let reader = tokio::spawn(async move {
         let sender = thread_sender;
+        let user = thread_user;
+        let wbmanager = thread_wbmanager;
         while let Some(Ok(e)) = stream.next().await {
-            if std::mem::discriminant(&e) == std::mem::discriminant(&Message::Close(None)) {
-                if let Err(e) = sender.send(ChannelMessage::Kill) {
-                    error!("{e}");
-                };
-            } else if let Err(e) = sender.send(ChannelMessage::WebsocketMessage(e)) {
+            if let Err(e) = sender.send(ChannelMessage::Heartbeat) {
                 error!("{e}");
+            }
+
+            let reply = match e {
+                Message::Close(_) => break,
+                Message::Ping(_) | Message::Pong(_) => continue,
+                Message::Binary(_) => Some(WebsocketFrame::error(
+                    &WebsocketError::UnsupportedFrame,
+                    None,
+                )),
+                Message::Text(text) => match WebsocketCommand::from_json(&text) {
+                    Ok(command) => {
+                        let name = command.name();
+                        commands::dispatch(command, &user, &sender, &wbmanager, &database, &typing)
+                            .await
+                            .unwrap_or_else(|error| Some(WebsocketFrame::error(&error, Some(name))))
+                    }
+                    Err(error) => Some(WebsocketFrame::error(&error, None)),
+                },
             };
+
+            let Some(reply) = reply else {
+                continue;
+            };
+
+            match to_websocketmessage(&reply) {
+                Ok(message) => {
+                    if let Err(e) = sender.send(ChannelMessage::WebsocketMessage(message)) {
+                        error!("{e}");
+                    };
+                }
+                Err(error) => error!("{error}"),
+            }
         }
+
+        if let Err(e) = sender.send(ChannelMessage::Kill) {
+            error!("{e}");
+        };
     });
     let sender = sender;
     tokio::spawn(async move {
-        while let Some(channelmessage) = receiver.recv().await {
-            match channelmessage {
-                ChannelMessage::WebsocketMessage(e) => {
-                    if let Err(error) = sink.send(e).await {
-                        error!("{error}");
-                    }
-                }
-                ChannelMessage::Message(e) => {
-                    let json = serde_json::to_string(&e);
+        let mut subscriptions: Vec<ServerId> = Vec::new();
+        let mut heartbeat =
+            tokio::time::interval(Duration::from_secs(config.heartbeat_interval.max(1)));
+        let timeout = Duration::from_secs(config.heartbeat_timeout);
+        let mut last_seen = Instant::now();
 
-                    if json.is_err() {
-                        error!("{:?}", json);
-                    }
+        loop {
+            tokio::select! {
+                channelmessage = receiver.recv() => {
+                    let Some(channelmessage) = channelmessage else {
+                        break;
+                    };
+
+                    match channelmessage {
+                        ChannelMessage::WebsocketMessage(e) => {
+                            if let Err(error) = sink.send(e).await {
+                                error!("{error}");
+                            }
+                        }
+                        ChannelMessage::Message(sequence, event) => {
+                            if !is_subscribed(&subscriptions, &event.server_id) {
+                                continue;
+                            }
 
-                    if let Ok(msg) = json {
-                        if let Err(error) = sink.send(Message::Text(msg)).await {
-                            error!("{error}");
+                            match to_websocketmessage(&WebsocketFrame::Dispatch { sequence, event }) {
+                                Ok(message) => {
+                                    if let Err(error) = sink.send(message).await {
+                                        error!("{error}");
+                                    }
+                                }
+                                Err(error) => error!("{error}"),
+                            }
                         }
+                        ChannelMessage::Subscribe(server_ids) => subscriptions = server_ids,
+                        ChannelMessage::Heartbeat => last_seen = Instant::now(),
+                        ChannelMessage::Kill => break,
                     }
                 }
-                ChannelMessage::Kill => {
-                    if wbmanager.remove(&user.id, &sender).await.is_err() {
-                        error!("Can't remove");
-                    };
-                    break;
+                _ = heartbeat.tick() => {
+                    if last_seen.elapsed() > timeout {
+                        info!("Websocket of {} timed out", user.name);
+                        break;
+                    }
+
+                    if let Err(error) = sink.send(Message::Ping(Vec::new())).await {
+                        error!("{error}");
+                    }
                 }
             }
         }
+
+        reader.abort();
+        if let Err(error) = sink.close().await {
+            error!("{error}");
+        }
+
+        if wbmanager.remove(&user.id, &sender).await.is_err() {
+            error!("Can't remove");
+        };
     });
 }
 
+/// Events without server (direct messages) are always sent,
+/// an empty subscription list means all servers
+fn is_subscribed(subscriptions: &[ServerId], server_id: &ServerId) -> bool {
+    subscriptions.is_empty() || server_id.id.is_empty() || subscriptions.contains(server_id)
+}
+
+/// Return the number of live websocket connections of user
+pub async fn get_websocket_sessions(
+    UserFromToken(user): UserFromToken,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    let sessions = wbsocket.live_sessions(&user.id).await.map_err(|error| {
+        error!("{error}");
+        "Cannot get websocket sessions".into_server_error()
+    })?;
+
+    FydiaResponse::from_serialize(sessions).into()
+}
+
 /// Convert a json to Websocket Message
 ///
 /// # Errors
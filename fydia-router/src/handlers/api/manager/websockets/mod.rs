@@ -1,13 +1,17 @@
 use std::collections::HashMap;
+use std::time::Instant;
 
 use axum::async_trait;
 use fydia_struct::event::Event;
 use fydia_struct::manager::ManagerReceiverTrait;
+use fydia_struct::server::ServerId;
 use fydia_struct::user::UserId;
+use fydia_struct::websocket::{WebsocketSession, REPLAY_BUFFER_SIZE, RESUME_WINDOW};
 use parking_lot::RwLock;
 use tokio::sync::mpsc::{UnboundedReceiver as Receiver, UnboundedSender as Sender};
 use tokio::sync::oneshot::Sender as OSSender;
 
+pub mod commands;
 pub mod manager;
 pub mod messages;
 
@@ -18,12 +22,14 @@ pub type WbSender = Sender<ChannelMessage>;
 #[derive(Debug)]
 pub struct WebsocketInner {
     wb_channel: RwLock<HashMap<UserId, Vec<WbSender>>>,
+    sessions: RwLock<HashMap<UserId, WebsocketSession>>,
 }
 
 impl Default for WebsocketInner {
     fn default() -> Self {
         Self {
             wb_channel: RwLock::new(HashMap::new()),
+            sessions: RwLock::new(HashMap::new()),
         }
     }
 }
@@ -56,11 +62,73 @@ impl WebsocketInner {
         self.wb_channel.read().get(user)?.get(index).cloned()
     }
 
+    /// Insert user with its session, sessions that cannot be resumed anymore are removed
     pub fn insert_user(&mut self, user: &UserId) {
         let mut wbchannel = self.wb_channel.write();
         if !wbchannel.contains_key(user) {
             wbchannel.insert(user.clone(), Vec::new());
         }
+
+        let now = Instant::now();
+        let mut sessions = self.sessions.write();
+        sessions.retain(|_, session| !session.is_expired(now, RESUME_WINDOW));
+        sessions
+            .entry(user.clone())
+            .or_insert_with(|| WebsocketSession::new(REPLAY_BUFFER_SIZE))
+            .set_connected(true, now);
+    }
+
+    pub fn get_session(&self, user: &UserId) -> Option<(String, u64)> {
+        self.sessions
+            .read()
+            .get(user)
+            .map(|session| (session.id().to_string(), session.sequence()))
+    }
+
+    /// Add event in the session of user and send it to all websocket channels
+    pub fn dispatch(&mut self, user: &UserId, event: &Event) {
+        let Some(sequence) = self
+            .sessions
+            .write()
+            .get_mut(user)
+            .map(|session| session.push(event.clone()))
+        else {
+            return;
+        };
+
+        for channel in self.get_channels(user) {
+            if let Err(error) =
+                channel.send(ChannelMessage::Message(sequence, Box::new(event.clone())))
+            {
+                error!("{error}");
+            }
+        }
+    }
+
+    /// Send missed events to a websocket channel and return the sequence of session
+    ///
+    /// Return `None` if session doesn't exist or if events aren't in buffer anymore
+    pub fn resume(
+        &self,
+        user: &UserId,
+        websocket_channel: &WbSender,
+        session_id: &str,
+        sequence: u64,
+    ) -> Option<u64> {
+        let sessions = self.sessions.read();
+        let session = sessions
+            .get(user)
+            .filter(|session| session.id() == session_id)?;
+
+        for (sequence, event) in session.replay_after(sequence)? {
+            if let Err(error) =
+                websocket_channel.send(ChannelMessage::Message(sequence, Box::new(event)))
+            {
+                error!("{error}");
+            }
+        }
+
+        Some(session.sequence())
     }
 
     /// Inser a websocket channel
@@ -102,6 +170,10 @@ impl WebsocketInner {
 
         if channel.is_empty() {
             wbchannel.remove(user);
+
+            if let Some(session) = self.sessions.write().get_mut(user) {
+                session.set_connected(false, Instant::now());
+            }
         }
 
         Ok(())
@@ -111,7 +183,10 @@ impl WebsocketInner {
 #[derive(Debug, Clone)]
 pub enum ChannelMessage {
     WebsocketMessage(axum::extract::ws::Message),
-    Message(Box<Event>),
+    Message(u64, Box<Event>),
+    Subscribe(Vec<ServerId>),
+    /// A frame was received from client
+    Heartbeat,
     Kill,
 }
 
@@ -121,6 +196,9 @@ pub enum WbManagerMessage {
     GetWithIndex(UserId, usize, OSSender<Option<WbSender>>),
     Insert(UserId, OSSender<Result<WbChannel, String>>),
     Remove(UserId, WbSender, OSSender<Result<(), String>>),
+    Session(UserId, OSSender<Option<(String, u64)>>),
+    Dispatch(UserId, Box<Event>),
+    Resume(UserId, WbSender, String, u64, OSSender<Option<u64>>),
 }
 
 #[async_trait]
@@ -149,6 +227,20 @@ impl ManagerReceiverTrait for WebsocketInner {
                     error!("Can't send");
                 };
             }
+            WbManagerMessage::Session(user, callback) => {
+                if callback.send(self.get_session(&user)).is_err() {
+                    error!("Can't send");
+                };
+            }
+            WbManagerMessage::Dispatch(user, event) => self.dispatch(&user, &event),
+            WbManagerMessage::Resume(user, wbsender, session_id, sequence, callback) => {
+                if callback
+                    .send(self.resume(&user, &wbsender, &session_id, sequence))
+                    .is_err()
+                {
+                    error!("Can't send");
+                };
+            }
         }
     }
 }
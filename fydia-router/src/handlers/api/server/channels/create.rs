@@ -1,8 +1,11 @@
 use fydia_sql::impls::server::SqlServer;
 use fydia_struct::channel::{Channel, ChannelType};
-use fydia_struct::response::FydiaResult;
+use fydia_struct::permission::PermissionValue;
+use fydia_struct::response::{FydiaResult, IntoFydia};
+use fydia_utils::http::StatusCode;
 
-use crate::handlers::basic::{Database, ServerJoinedFromId};
+use crate::handlers::api::server::check_server_permission;
+use crate::handlers::basic::{Database, ServerJoinedFromId, UserFromToken};
 use crate::handlers::{get_json, get_json_value_from_body};
 
 /// Create a new channel in a server
@@ -10,18 +13,28 @@ use crate::handlers::{get_json, get_json_value_from_body};
 /// # Errors
 /// Return an error if:
 /// * serverid, token isn't valid
+/// * user cannot manage channels
 /// * body isn't valid
 /// * database is unreachable
 pub async fn create_channel(
+    UserFromToken(user): UserFromToken,
     ServerJoinedFromId(mut server): ServerJoinedFromId,
     Database(database): Database,
     body: String,
 ) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageChannels, &database).await?;
+
     let json = get_json_value_from_body(&body)?;
 
     let name = get_json("name", &json)?.to_string();
     let channeltype = ChannelType::from_string(get_json("type", &json)?.to_string());
 
+    if channeltype.is_thread() {
+        return "Threads are started from a message"
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            .into();
+    }
+
     let channel = Channel::new_with_serverid(name, String::new(), server.id.clone(), channeltype)?;
 
     server
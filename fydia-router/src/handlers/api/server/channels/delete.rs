@@ -1,6 +1,8 @@
-use crate::handlers::basic::{ChannelFromId, Database};
+use crate::handlers::api::server::check_channel_permission;
+use crate::handlers::basic::{ChannelFromId, Database, ServerJoinedFromId, UserFromToken};
 
 use fydia_sql::impls::channel::SqlChannel;
+use fydia_struct::permission::PermissionValue;
 use fydia_struct::response::FydiaResult;
 
 /// Delete a channel in a server
@@ -8,11 +10,23 @@ use fydia_struct::response::FydiaResult;
 /// # Errors
 /// Return an error if:
 /// * serverid, channelid, token isn't valid
+/// * user cannot manage channels
 /// * database is unreachable
 pub async fn delete_channel(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
     Database(database): Database,
     ChannelFromId(channel): ChannelFromId,
 ) -> FydiaResult {
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::ManageChannels,
+        &database,
+    )
+    .await?;
+
     channel.delete(&database).await?;
 
     "Channel deleted".into()
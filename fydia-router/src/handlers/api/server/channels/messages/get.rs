@@ -1,28 +1,41 @@
-use crate::handlers::basic::{ChannelFromId, Database, UserFromToken};
-use fydia_sql::impls::{channel::SqlChannel, user::SqlUser};
-use fydia_struct::response::{FydiaResponse, FydiaResult};
+use crate::handlers::api::server::check_channel_permission;
+use crate::handlers::basic::{ChannelFromId, Database, ServerJoinedFromId, UserFromToken};
+use axum::extract::Query;
+use fydia_sql::impls::{channel::SqlChannel, reaction::SqlReaction};
+use fydia_struct::{
+    messages::MessageQuery,
+    permission::PermissionValue,
+    querystring::QsMessages,
+    reaction::Reaction,
+    response::{FydiaResponse, FydiaResult},
+};
 
-/// Return all message of channel
+/// Return a page of messages of channel
+///
+/// Page is selected with one of `before`, `after` or `around` cursors
+/// and `limit` in query string. Latest messages are returned without cursor.
 ///
 /// # Errors
 /// Return an error if:
 /// * serverid, channelid, token isn't valid
+/// * user cannot read this channel
+/// * more than one cursor is given or limit is 0
+/// * cursor isn't a message of this channel
 /// * database is unreachable
 pub async fn get_messages(
     UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
     ChannelFromId(channel): ChannelFromId,
     Database(database): Database,
+    Query(qs): Query<QsMessages>,
 ) -> FydiaResult {
-    if !user
-        .permission_of_channel(&channel.id, &database)
-        .await?
-        .calculate(Some(channel.id.clone()))?
-        .can_read()
-    {
-        return "Unknow channel".into();
-    }
+    check_channel_permission(&user, &server, &channel, &PermissionValue::Read, &database).await?;
+
+    let query = MessageQuery::new(qs.before, qs.after, qs.around, qs.limit)?;
+
+    let mut page = channel.messages(&query, &database).await?;
 
-    let messages = channel.messages(&database).await?;
+    Reaction::mark_own(&mut page.messages, &user.id, &database).await?;
 
-    FydiaResponse::from_serialize(messages).into()
+    FydiaResponse::from_serialize(page).into()
 }
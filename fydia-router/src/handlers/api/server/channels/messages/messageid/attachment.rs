@@ -0,0 +1,126 @@
+use axum::{
+    body::{boxed, StreamBody},
+    response::Response,
+};
+use fydia_storage::StorageError;
+use fydia_struct::{
+    file::{ByteRange, ByteRangeError, File},
+    messages::MessageType,
+    response::{FydiaResponse, IntoFydia},
+};
+use fydia_utils::http::{
+    header::{
+        ACCEPT_RANGES, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE,
+    },
+    HeaderMap, StatusCode,
+};
+
+use crate::handlers::{
+    api::server::channels::messages::post::{get_descriptor, get_mime_of_file},
+    basic::{MessageFromId, Storage},
+};
+
+/// Return the file of a file message.
+///
+/// A single range can be requested with a `Range` header.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, messageid, token isn't valid
+/// * user cannot read the channel
+/// * message isn't a file or file doesn't exist
+/// * range isn't satisfiable
+pub async fn get_attachment(
+    MessageFromId(message): MessageFromId,
+    Storage(storage): Storage,
+    headers: HeaderMap,
+) -> Result<Response, FydiaResponse> {
+    if message.message_type != MessageType::FILE {
+        return Err("Message has no attachment".into_error_with_statuscode(StatusCode::NOT_FOUND));
+    }
+
+    let file = File::get(&message.content);
+
+    let size = storage
+        .stat(&file.get_name())
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            match error {
+                StorageError::NotFound => {
+                    "Cannot get file".into_error_with_statuscode(StatusCode::NOT_FOUND)
+                }
+                _ => "Cannot get file".into_server_error(),
+            }
+        })?
+        .size;
+
+    let name = get_descriptor(&file, storage.as_ref())
+        .await
+        .map_or_else(|| file.get_name(), |descriptor| descriptor.name);
+
+    let range = match headers.get(RANGE).and_then(|range| range.to_str().ok()) {
+        Some(range) => match ByteRange::parse(range, size) {
+            Ok(range) => Some(range),
+            Err(ByteRangeError::Invalid) => None,
+            Err(ByteRangeError::Unsatisfiable) => {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(CONTENT_RANGE, format!("bytes */{size}"))
+                    .body(boxed(axum::body::Empty::new()))
+                    .map_err(|error| {
+                        error!("{error}");
+                        "Cannot build response".into_server_error()
+                    });
+            }
+        },
+        None => None,
+    };
+
+    let mut response = Response::builder()
+        .header(
+            CONTENT_TYPE,
+            get_mime_of_file(&message.content, storage.as_ref())
+                .await
+                .to_string(),
+        )
+        .header(ACCEPT_RANGES, "bytes")
+        .header(
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", sanitize_filename(&name)),
+        );
+
+    let length = match range {
+        Some(range) => {
+            response = response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, range.content_range(size));
+
+            range.length()
+        }
+        None => size,
+    };
+
+    let body = storage
+        .get(&file.get_name(), range)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot read file".into_server_error()
+        })?;
+
+    response
+        .header(CONTENT_LENGTH, length)
+        .body(boxed(StreamBody::new(body)))
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot build response".into_server_error()
+        })
+}
+
+/// Remove characters that cannot be in a quoted filename
+fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect()
+}
@@ -1,11 +1,12 @@
 use fydia_sql::impls::{channel::SqlChannel, message::SqlMessage};
 use fydia_struct::{
     event::EventContent,
+    permission::PermissionValue,
     response::{FydiaResult, IntoFydia},
 };
 
 use crate::handlers::{
-    api::manager::websockets::manager::WbManagerChannelTrait,
+    api::{manager::websockets::manager::WbManagerChannelTrait, server::check_channel_permission},
     basic::{
         ChannelFromId, Database, MessageFromId, ServerJoinedFromId, UserFromToken, WebsocketManager,
     },
@@ -16,7 +17,7 @@ use crate::handlers::{
 /// # Errors
 /// Return an error if:
 /// * serverid, channelid, messageid, token isn't valid
-/// * The owner user and token user is different
+/// * The owner user and token user is different and token user cannot manage messages
 pub async fn delete_message(
     Database(database): Database,
     UserFromToken(user): UserFromToken,
@@ -25,8 +26,20 @@ pub async fn delete_message(
     MessageFromId(message): MessageFromId,
     WebsocketManager(wbsocket): WebsocketManager,
 ) -> FydiaResult {
-    if message.author_id.id != user.id {
-        return "You can't delete this message".into();
+    if message.author_id.id != user.id
+        && check_channel_permission(
+            &user,
+            &server,
+            &channel,
+            &PermissionValue::ManageMessages,
+            &database,
+        )
+        .await
+        .is_err()
+    {
+        return "You can't delete this message"
+            .into_forbidden_error()
+            .into();
     }
 
     wbsocket
@@ -1,6 +1,10 @@
-use fydia_struct::response::{FydiaResponse, FydiaResult};
+use fydia_sql::impls::reaction::SqlReaction;
+use fydia_struct::{
+    reaction::Reaction,
+    response::{FydiaResponse, FydiaResult},
+};
 
-use crate::handlers::basic::{ChannelFromId, MessageFromId};
+use crate::handlers::basic::{ChannelFromId, Database, MessageFromId, UserFromToken};
 
 /// Return requested message
 ///
@@ -8,8 +12,14 @@ use crate::handlers::basic::{ChannelFromId, MessageFromId};
 /// Return error if:
 /// * serverid, channelid, messageid, token isn't valid
 pub async fn get_message(
+    UserFromToken(user): UserFromToken,
     ChannelFromId(_): ChannelFromId,
     MessageFromId(message): MessageFromId,
+    Database(database): Database,
 ) -> FydiaResult {
+    let mut messages = [message];
+    Reaction::mark_own(&mut messages, &user.id, &database).await?;
+    let [message] = messages;
+
     FydiaResponse::from_serialize(message).into()
 }
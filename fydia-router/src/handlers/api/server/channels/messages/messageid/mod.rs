@@ -1,3 +1,6 @@
+pub mod attachment;
 pub mod delete;
 pub mod get;
 pub mod post;
+pub mod reactions;
+pub mod revisions;
@@ -1,8 +1,8 @@
 use fydia_sql::impls::{channel::SqlChannel, message::SqlMessage};
 use fydia_struct::{
     event::EventContent,
-    messages::MessageType,
-    response::{FydiaResult, IntoFydia},
+    messages::{MessageError, MessageType},
+    response::{FydiaResponse, FydiaResult, IntoFydia},
 };
 
 use crate::handlers::{
@@ -13,12 +13,13 @@ use crate::handlers::{
     get_json, get_json_value_from_body,
 };
 
-/// Change content of a message
+/// Change content of a message, previous content is kept as a revision
 ///
 /// # Errors
 /// Return an error if :
 /// * channelid, serverid isn't valid
 /// * body isn't valid
+/// * content is empty
 pub async fn update_message(
     UserFromToken(user): UserFromToken,
     ServerFromId(server): ServerFromId,
@@ -40,7 +41,16 @@ pub async fn update_message(
 
     let content = get_json("content", &value)?.to_string();
 
-    message.update(&content, &database).await?;
+    let edited_at = message
+        .update(&content, &database)
+        .await
+        .map_err(|error| match error {
+            MessageError::ContentEmpty => FydiaResponse::StringError(Box::new(error.to_string())),
+            error => {
+                error!("{error}");
+                "Cannot edit message".into_server_error()
+            }
+        })?;
 
     let users = &channel.users(&database).await?;
 
@@ -51,6 +61,7 @@ pub async fn update_message(
                 content: EventContent::MessageUpdate {
                     message_id: message.id.clone(),
                     update: Box::new(message),
+                    edited_at,
                 },
             },
             users,
@@ -0,0 +1,213 @@
+use crate::handlers::{
+    api::{
+        manager::websockets::manager::{WbManagerChannelTrait, WebsocketManagerChannel},
+        server::check_channel_permission,
+    },
+    basic::{
+        ChannelFromId, Database, MessageFromId, ServerJoinedFromId, UserFromToken, WebsocketManager,
+    },
+    get_json_value_from_body,
+};
+use fydia_sql::{
+    impls::{channel::SqlChannel, emoji::SqlEmoji, reaction::SqlReaction},
+    sqlpool::DbConnection,
+};
+use fydia_struct::{
+    channel::Channel,
+    emoji::{Emoji, EmojiError},
+    event::{Event, EventContent},
+    permission::PermissionValue,
+    reaction::{Reaction, ReactionEmoji, ReactionError},
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    server::Server,
+};
+use fydia_utils::{http::StatusCode, serde_json};
+use std::sync::Arc;
+
+/// Read the emoji of a reaction from body, `{"unicode": "👍"}` or `{"custom": EMOJIID}`.
+/// A custom emoji has to be an emoji of the server.
+///
+/// # Errors
+/// Return an error if body isn't a valid emoji of the server
+async fn get_emoji(
+    body: &String,
+    server: &Server,
+    database: &DbConnection,
+) -> Result<ReactionEmoji, FydiaResponse> {
+    let emoji = serde_json::from_value::<ReactionEmoji>(get_json_value_from_body(body)?).map_err(
+        |error| {
+            error!("{error}");
+            "Emoji should be {\"unicode\": EMOJI} or {\"custom\": EMOJIID}"
+                .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+        },
+    )?;
+
+    emoji.check().map_err(|error| reaction_error(&error))?;
+
+    if let ReactionEmoji::Custom(id) = &emoji {
+        let custom =
+            Emoji::by_id(id.get_id_cloned()?, database)
+                .await
+                .map_err(|error| match error {
+                    EmojiError::NoEmojiWithId => reaction_error(&ReactionError::UnknownEmoji),
+                    error => error.to_string().into_server_error(),
+                })?;
+
+        if custom.server_id != server.id {
+            return Err(reaction_error(&ReactionError::UnknownEmoji));
+        }
+    }
+
+    Ok(emoji)
+}
+
+/// Convert an error of emoji or of reaction state to a `BAD_REQUEST` response
+fn reaction_error(error: &ReactionError) -> FydiaResponse {
+    match error {
+        ReactionError::InvalidEmoji
+        | ReactionError::UnknownEmoji
+        | ReactionError::AlreadyReacted
+        | ReactionError::NoReaction => error
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST),
+        _ => error.to_string().into_server_error(),
+    }
+}
+
+/// Send a reaction event to members of the channel
+///
+/// # Errors
+/// Return an error if members cannot be read or if event cannot be sent
+async fn send_reaction_event(
+    server: &Server,
+    channel: &Channel,
+    content: EventContent,
+    database: &DbConnection,
+    wbsocket: &Arc<WebsocketManagerChannel>,
+) -> Result<(), FydiaResponse> {
+    wbsocket
+        .send(
+            &Event::new(server.id.clone(), content),
+            &channel.users(database).await?,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send reaction event".into_server_error()
+        })
+}
+
+/// Return reactions of a message counted by emoji
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, messageid, token isn't valid
+/// * user cannot read this channel
+pub async fn get_reactions(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    MessageFromId(message): MessageFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    check_channel_permission(&user, &server, &channel, &PermissionValue::Read, &database).await?;
+
+    let mut messages = [message];
+    Reaction::mark_own(&mut messages, &user.id, &database).await?;
+    let [message] = messages;
+
+    FydiaResponse::from_serialize(message.reactions).into()
+}
+
+/// Add a reaction of user to a message.
+///
+/// Body is `{"unicode": "👍"}` or `{"custom": EMOJIID}` for an emoji of the server.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, messageid, token or body isn't valid
+/// * user cannot add reactions in this channel
+/// * user already reacted with this emoji
+pub async fn add_reaction(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    MessageFromId(message): MessageFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::AddReactions,
+        &database,
+    )
+    .await?;
+
+    let emoji = get_emoji(&body, &server, &database).await?;
+
+    Reaction::add(&message.id, &user.id, &emoji, &database)
+        .await
+        .map_err(|error| reaction_error(&error))?;
+
+    send_reaction_event(
+        &server,
+        &channel,
+        EventContent::ReactionAdd {
+            message_id: message.id,
+            userid: user.id,
+            emoji,
+        },
+        &database,
+        &wbsocket,
+    )
+    .await?;
+
+    "Reaction added".into()
+}
+
+/// Remove a reaction of user from a message.
+///
+/// Body is the emoji of the reaction, see `add_reaction`.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, messageid, token or body isn't valid
+/// * user didn't react with this emoji
+pub async fn remove_reaction(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    MessageFromId(message): MessageFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    let emoji = serde_json::from_value::<ReactionEmoji>(get_json_value_from_body(&body)?).map_err(
+        |error| {
+            error!("{error}");
+            reaction_error(&ReactionError::InvalidEmoji)
+        },
+    )?;
+
+    Reaction::remove(&message.id, &user.id, &emoji, &database)
+        .await
+        .map_err(|error| reaction_error(&error))?;
+
+    send_reaction_event(
+        &server,
+        &channel,
+        EventContent::ReactionRemove {
+            message_id: message.id,
+            userid: user.id,
+            emoji,
+        },
+        &database,
+        &wbsocket,
+    )
+    .await?;
+
+    "Reaction removed".into()
+}
@@ -0,0 +1,37 @@
+use fydia_sql::impls::message::SqlMessage;
+use fydia_struct::{
+    permission::PermissionValue,
+    response::{FydiaResponse, FydiaResult},
+};
+
+use crate::handlers::{
+    api::server::check_channel_permission,
+    basic::{ChannelFromId, Database, MessageFromId, ServerJoinedFromId, UserFromToken},
+};
+
+/// Return previous contents of a message with the time of each edit, oldest first
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, messageid or token isn't valid
+/// * user cannot manage messages in this channel
+pub async fn get_revisions(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    MessageFromId(message): MessageFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::ManageMessages,
+        &database,
+    )
+    .await?;
+
+    let revisions = message.revisions(&database).await?;
+
+    FydiaResponse::from_serialize(revisions).into()
+}
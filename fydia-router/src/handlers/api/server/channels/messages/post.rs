@@ -1,133 +1,185 @@
 use crate::handlers::api::manager::websockets::manager::{
     WbManagerChannelTrait, WebsocketManagerChannel,
 };
+use crate::handlers::api::server::channels::threads::bump_thread;
+use crate::handlers::api::server::check_channel_permission;
 use crate::handlers::basic::{ChannelFromId, ContentType, ServerJoinedFromId, UserFromToken};
 use crate::handlers::{get_json, get_json_value_from_body};
 use crate::ServerState;
-use axum::extract::State;
+use axum::extract::{BodyStream, State};
 use chrono::DateTime;
-use futures::stream::once;
+use futures::{StreamExt, TryStreamExt};
+use fydia_sql::impls::channel::SqlChannelId;
+use fydia_sql::impls::members::SqlMembers;
 use fydia_sql::impls::message::SqlMessage;
 use fydia_sql::impls::server::SqlServer;
 use fydia_sql::sqlpool::DbConnection;
+use fydia_storage::{StorageBackend, StorageError};
 use fydia_struct::channel::ChannelId;
 use fydia_struct::event::{Event, EventContent};
-use fydia_struct::file::{File, FileDescriptor};
+use fydia_struct::file::{ByteRange, File, FileDescriptor};
 use fydia_struct::instance::RsaData;
-use fydia_struct::messages::{Date, Message, MessageType};
+use fydia_struct::messages::{Date, Message, MessageError, MessageSnapshot, MessageType};
+use fydia_struct::permission::PermissionValue;
 use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
-use fydia_struct::server::{Server, ServerId};
+use fydia_struct::server::{Members, Server, ServerId};
 use fydia_struct::user::User;
 use fydia_utils::http::header::CONTENT_TYPE;
-use fydia_utils::http::HeaderMap;
+use fydia_utils::http::{HeaderMap, StatusCode};
 use fydia_utils::serde_json::Value;
 use mime::Mime;
-use multer::Multipart;
-use std::convert::Infallible;
+use multer::{Constraints, Field, Multipart, SizeLimit};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::SystemTime;
 
 const BOUNDARY: &str = "boundary=";
+/// Maximum size of a multipart body
+pub const MAX_ATTACHMENT_SIZE: u64 = 25_000_000;
 const CHECK_MIME: [mime::Mime; 3] = [
     mime::APPLICATION_JSON,
     mime::TEXT_PLAIN,
     mime::TEXT_PLAIN_UTF_8,
 ];
 
-/// Post a new messages in a channel
+/// Post a new messages in a channel.
+///
+/// A message posted in a thread unarchives it.
 ///
 /// # Errors
 /// Return an error if:
 /// * Channelid, Serverid isn't valid
+/// * User cannot write or attach files in this channel
 /// * Body is bad
 /// * Content-Type isn't valid
 pub async fn post_messages(
     UserFromToken(user): UserFromToken,
     ServerJoinedFromId(server): ServerJoinedFromId,
-    ChannelFromId(channel): ChannelFromId,
+    ChannelFromId(mut channel): ChannelFromId,
     ContentType(mime, raw_content_type): ContentType,
     State(state): State<ServerState>,
     headers: HeaderMap,
-    body: String,
+    mut body: BodyStream,
 ) -> FydiaResult {
     let ServerState {
         database,
         rsa,
         wbsocket,
+        storage,
         ..
     } = state;
 
-    if CHECK_MIME.contains(&mime) || raw_content_type == "application/json; charset=utf-8" {
+    check_channel_permission(&user, &server, &channel, &PermissionValue::Write, &database).await?;
+
+    let is_json =
+        CHECK_MIME.contains(&mime) || raw_content_type == "application/json; charset=utf-8";
+
+    let event = if is_json {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = body.next().await {
+            bytes.extend_from_slice(&chunk.map_err(|error| {
+                error!("{error}");
+                FydiaResponse::TextError("Body error")
+            })?);
+        }
+
+        let body = String::from_utf8(bytes).map_err(|error| {
+            error!("{error}");
+            FydiaResponse::TextError("Body isn't valid UTF-8")
+        })?;
+
         let json = get_json_value_from_body(&body)?;
-        let event = json_message(json, user, &channel.id, &server.id).await?;
-        return send_event(event, server, &rsa, wbsocket, database).await;
-    }
+        json_message(json, user, &channel.id, &server.id, &database).await?
+    } else if mime.essence_str() == mime::MULTIPART_FORM_DATA.essence_str() {
+        check_channel_permission(
+            &user,
+            &server,
+            &channel,
+            &PermissionValue::AttachFiles,
+            &database,
+        )
+        .await?;
 
-    if mime == mime::MULTIPART_FORM_DATA {
-        let stream = once(async move { Result::<String, Infallible>::Ok(body) });
         let boundary = get_boundary(&headers).ok_or("No boundary found")?;
 
-        let multer = multer::Multipart::new(stream, boundary.clone());
-
-        let event = multipart_to_event(multer, user.clone(), &channel.id, &server.id).await?;
+        let multer = multer::Multipart::with_constraints(
+            body,
+            boundary,
+            Constraints::new().size_limit(SizeLimit::new().whole_stream(MAX_ATTACHMENT_SIZE)),
+        );
+
+        multipart_to_event(
+            multer,
+            user.clone(),
+            &channel.id,
+            &server.id,
+            storage.as_ref(),
+        )
+        .await?
+    } else {
+        return "Content-Type error".into();
+    };
 
-        return send_event(event, server, &rsa, wbsocket, database).await;
+    if channel.is_thread() {
+        bump_thread(&mut channel, &server, &database, &wbsocket).await?;
     }
 
-    "Content-Type error".into()
+    send_event(event, server, &rsa, wbsocket, database).await
 }
 
 /// Transform a multipart request to a Message event
 ///
+/// The `file` field is written to storage chunk by chunk.
+///
 /// # Errors
 /// Return an error if:
-/// * body isn't valid
+/// * body isn't valid or is too large
+/// * there is no `file` field
 /// * Cannot write file
-pub async fn multipart_to_event<'a, 'r>(
-    mut multipart: Multipart<'r>,
+pub async fn multipart_to_event(
+    mut multipart: Multipart<'static>,
     user: User,
     channelid: &ChannelId,
     server_id: &ServerId,
+    storage: &dyn StorageBackend,
 ) -> Result<Event, FydiaResponse> {
-    let file = File::new();
-    while let Ok(Some(field)) = multipart.next_field().await {
-        if let Some(field_name) = field.name() {
-            if field_name == "file" {
-                let file = File::new();
-
-                file.create_with_description(&FileDescriptor::new_with_now(
+    let mut file = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|error| multipart_error(&error))?
+    {
+        match field.name() {
+            Some("file") => {
+                let new_file = File::new();
+                let descriptor = FileDescriptor::new_with_now(
                     field
                         .file_name()
-                        .map_or_else(|| file.get_name(), ToString::to_string),
-                ))
-                .map_err(|error| {
-                    error!("{error}");
-                    "File creation error".into_server_error()
-                })?;
+                        .map_or_else(|| new_file.get_name(), ToString::to_string),
+                );
 
-                let body = field
-                    .bytes()
-                    .await
-                    .map_err(|error| {
-                        error!("{error}");
-                        FydiaResponse::TextError("Body error")
-                    })?
-                    .to_vec();
+                write_field(field, &new_file, storage).await?;
 
-                file.write(&body).map_err(|error| {
-                    error!("{error}");
+                if let Err(error) = write_descriptor(&new_file, &descriptor, storage).await {
+                    if let Err(error) = storage.delete(&new_file.get_name()).await {
+                        error!("{error}");
+                    }
 
-                    "Can't write the file".into_server_error()
-                })?;
+                    return Err(error);
+                }
 
+                file = Some(new_file);
                 break;
-            } else if field_name.eq("context") {
+            }
+            Some("context") => {
                 info!("{}", field.text().await.unwrap_or_default());
             }
+            _ => {}
         }
     }
 
+    let file = file.ok_or(FydiaResponse::TextError("No file field"))?;
+
     let message = Message::new(
         file.get_name(),
         MessageType::FILE,
@@ -148,25 +200,35 @@ pub async fn multipart_to_event<'a, 'r>(
     Ok(event)
 }
 
-/// Get message from value
+/// Get message from value.
+///
+/// `reply_to` is the optional id of the message of the same channel this message replies to.
 ///
 /// # Errors
 /// Return an error if:
 /// * The body isn't valid
+/// * The type is `FILE`, files can only be sent with multipart
 /// * The channelid, serverid isn't valid
+/// * The replied message isn't a message of the channel
 pub async fn json_message(
     value: Value,
     user: User,
     channelid: &ChannelId,
     server_id: &ServerId,
+    database: &DbConnection,
 ) -> Result<Event, FydiaResponse> {
     let type_from_json = get_json("type", &value)?.to_string();
     let messagetype = MessageType::from_string(type_from_json)
         .map_err(|_f| FydiaResponse::TextError("Bad Message Type"))?;
 
+    if messagetype == MessageType::FILE {
+        return Err("File messages must be sent as multipart"
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST));
+    }
+
     let content = get_json("content", &value)?.to_string();
 
-    let message = Message::new(
+    let mut message = Message::new(
         content,
         messagetype,
         false,
@@ -176,6 +238,20 @@ pub async fn json_message(
     )
     .map_err(|f| FydiaResponse::StringError(Box::new(f.to_string())))?;
 
+    match value.get("reply_to") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(reply_to)) => {
+            let replied = reply_snapshot(reply_to, channelid, database).await?;
+            message.reply_to = Some(replied.id.clone());
+            message.referenced_message = Some(Box::new(replied));
+        }
+        Some(_) => {
+            return Err(
+                "reply_to must be a string".into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            )
+        }
+    }
+
     Ok(Event::new(
         server_id.clone(),
         EventContent::Message {
@@ -184,20 +260,161 @@ pub async fn json_message(
     ))
 }
 
-pub fn get_mime_of_file(path: &str) -> Mime {
-    let mut buf = [0; 16];
+/// Return a snapshot of the replied message
+///
+/// # Errors
+/// Return an error if message doesn't exist or isn't a message of the channel
+async fn reply_snapshot(
+    message_id: &str,
+    channelid: &ChannelId,
+    database: &DbConnection,
+) -> Result<MessageSnapshot, FydiaResponse> {
+    let invalid_reply = || {
+        MessageError::InvalidReply
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+    };
+
+    let replied = Message::by_id(message_id, database)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            invalid_reply()
+        })?;
+
+    if &replied.channel_id != channelid {
+        return Err(invalid_reply());
+    }
+
+    Ok(MessageSnapshot::new(
+        replied.id,
+        &replied.content,
+        replied.message_type,
+        replied.author_id,
+    ))
+}
+
+/// Write a multipart field in storage
+///
+/// # Errors
+/// Return an error if:
+/// * body isn't valid or is too large
+/// * Cannot write file
+async fn write_field(
+    field: Field<'static>,
+    file: &File,
+    storage: &dyn StorageBackend,
+) -> Result<(), FydiaResponse> {
+    let body = field
+        .map_err(|error| {
+            if is_size_exceeded(&error) {
+                StorageError::TooLarge
+            } else {
+                StorageError::Body(error.to_string())
+            }
+        })
+        .boxed();
+
+    storage
+        .put(&file.get_name(), body)
+        .await
+        .map(|_| ())
+        .map_err(|error| {
+            error!("{error}");
+            match error {
+                StorageError::TooLarge => {
+                    "File is too large".into_error_with_statuscode(StatusCode::PAYLOAD_TOO_LARGE)
+                }
+                StorageError::Body(_) => FydiaResponse::TextError("Body error"),
+                _ => "Can't write the file".into_server_error(),
+            }
+        })
+}
+
+/// Write the `FileDescriptor` of a file in storage
+///
+/// # Errors
+/// Return an error if descriptor cannot be written
+async fn write_descriptor(
+    file: &File,
+    descriptor: &FileDescriptor,
+    storage: &dyn StorageBackend,
+) -> Result<(), FydiaResponse> {
+    let descriptor = descriptor.to_string().map_err(|error| {
+        error!("{error}");
+        "File creation error".into_server_error()
+    })?;
+
+    storage
+        .put_bytes(&file.description_key(), descriptor.into_bytes())
+        .await
+        .map(|_| ())
+        .map_err(|error| {
+            error!("{error}");
+            "File creation error".into_server_error()
+        })
+}
 
-    let file = File::get(path);
+/// Return true if a size limit of multipart body is exceeded
+fn is_size_exceeded(error: &multer::Error) -> bool {
+    match error {
+        multer::Error::StreamSizeExceeded { .. } | multer::Error::FieldSizeExceeded { .. } => true,
+        // The whole stream limit is reported while reading a field
+        multer::Error::StreamReadFailed(error) => error
+            .downcast_ref::<multer::Error>()
+            .is_some_and(is_size_exceeded),
+        _ => false,
+    }
+}
 
-    if file.read_file(&mut buf).is_err() {
-        error!("Can't write on buf");
+pub fn multipart_error(error: &multer::Error) -> FydiaResponse {
+    error!("{error}");
+    if is_size_exceeded(error) {
+        return "File is too large".into_error_with_statuscode(StatusCode::PAYLOAD_TOO_LARGE);
     }
 
-    if let Some(e) = infer::get(&buf) {
+    FydiaResponse::TextError("Body error")
+}
+
+/// Return the `FileDescriptor` of a stored file
+pub async fn get_descriptor(file: &File, storage: &dyn StorageBackend) -> Option<FileDescriptor> {
+    let bytes = storage
+        .get_bytes(&file.description_key())
+        .await
+        .map_err(|error| error!("{error}"))
+        .ok()?;
+
+    FileDescriptor::from_slice(&bytes)
+        .map_err(|error| error!("{error}"))
+        .ok()
+}
+
+/// Guess mime type of a stored file from its first bytes or from its name
+pub async fn get_mime_of_file(name: &str, storage: &dyn StorageBackend) -> Mime {
+    let file = File::get(name);
+
+    let head = match storage
+        .get(name, Some(ByteRange { start: 0, end: 15 }))
+        .await
+    {
+        Ok(stream) => stream
+            .try_fold(Vec::new(), |mut buffer, chunk| async move {
+                buffer.extend_from_slice(&chunk);
+                Ok(buffer)
+            })
+            .await
+            .unwrap_or_default(),
+        Err(error) => {
+            error!("{error}");
+            Vec::new()
+        }
+    };
+
+    if let Some(e) = infer::get(&head) {
         return mime::Mime::from_str(e.mime_type()).unwrap_or(mime::APPLICATION_OCTET_STREAM);
     }
 
-    if let Ok(file_desc) = file.get_description() {
+    if let Some(file_desc) = get_descriptor(&file, storage).await {
         return mime_guess::from_path(file_desc.name).first_or_octet_stream();
     }
 
@@ -212,14 +429,16 @@ pub fn get_boundary(headers: &HeaderMap) -> Option<String> {
     })
 }
 
-/// Send message event
+/// Send an event to members of the server.
+///
+/// A message event inserts the message before, and mentions the author of the replied message.
 ///
 /// # Errors
 /// Return error if :
 /// * cannot get members of server
-/// * cannot get websocket manager
+/// * cannot insert the message
 pub async fn send_event(
-    event: Event,
+    mut event: Event,
     server: Server,
     rsa: &Arc<RsaData>,
     wbsocket: Arc<WebsocketManagerChannel>,
@@ -230,21 +449,71 @@ pub async fn send_event(
         Err(_) => return "Cannot get users of the server".into_server_error().into(),
     };
 
-    if let EventContent::Message { ref content } = event.content {
+    let mut mention = None;
+    if let EventContent::Message { ref mut content } = event.content {
+        content.author_member = Members::profile(&server.id, &content.author_id.id, &database)
+            .await
+            .ok();
+
         if content.insert(&database).await.is_err() {
             return "Cannot send message".into_server_error().into();
         }
 
-        let key = rsa.clone();
-        tokio::spawn(async move {
-            if let Err(error) = wbsocket
-                .send_with_origin_and_key(&event, members.as_slice(), Some(&key), None)
+        let replied_author = content
+            .referenced_message
+            .as_ref()
+            .map(|replied| replied.author_id.clone())
+            .filter(|author| author.id != content.author_id.id && members.contains(&author.id));
+
+        if let Some(author) = replied_author {
+            // Author of the replied message may have lost access to the channel
+            let can_read = match content.channel_id.channel(&database).await {
+                Ok(channel) => check_channel_permission(
+                    &author,
+                    &server,
+                    &channel,
+                    &PermissionValue::Read,
+                    &database,
+                )
                 .await
-            {
-                error!("{error}");
+                .is_ok(),
+                Err(error) => {
+                    error!("{error}");
+                    false
+                }
             };
-        });
+
+            if can_read {
+                mention = Some((
+                    author.id,
+                    Event::new(
+                        server.id.clone(),
+                        EventContent::Mention {
+                            message_id: content.id.clone(),
+                            channelid: content.channel_id.clone(),
+                            author: content.author_id.id.clone(),
+                        },
+                    ),
+                ));
+            }
+        }
     }
 
+    let key = rsa.clone();
+    tokio::spawn(async move {
+        if let Err(error) = wbsocket
+            .send_with_origin_and_key(&event, members.as_slice(), Some(&key), None)
+            .await
+        {
+            error!("{error}");
+        };
+
+        if let Some((author, mention)) = mention {
+            if let Err(error) = wbsocket.send(&mention, &[author]).await {
+                error!("{error}");
+            }
+        }
+    });
+
     "Message send".into()
 }
@@ -2,6 +2,8 @@ pub mod create;
 pub mod delete;
 pub mod messages;
 pub mod permission;
+pub mod pins;
+pub mod threads;
 pub mod typing;
 pub mod update;
 pub mod vocal;
@@ -1,10 +1,28 @@
 pub mod role;
 pub mod user;
 
-use crate::handlers::basic::{ChannelFromId, Database};
+use crate::handlers::api::server::check_channel_permission;
+use crate::handlers::basic::{
+    ChannelFromId, Database, MemberFromId, ServerJoinedFromId, UserFromToken,
+};
 use fydia_sql::impls::permission::PermissionSql;
-use fydia_struct::permission::Permission;
-use fydia_struct::response::{FydiaResponse, FydiaResult};
+use fydia_struct::channel::Channel;
+use fydia_struct::permission::{Permission, PermissionValue};
+use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
+use fydia_utils::http::StatusCode;
+
+/// Check that overwrites can be set on a channel, threads use overwrites of their parent
+///
+/// # Errors
+/// Return an error if channel is a thread
+fn check_not_thread(channel: &Channel) -> Result<(), FydiaResponse> {
+    if channel.is_thread() {
+        return Err("Threads use permission overwrites of their parent channel"
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST));
+    }
+
+    Ok(())
+}
 
 /// Get permission
 ///
@@ -15,7 +33,44 @@ pub async fn get_permission(
     ChannelFromId(channel): ChannelFromId,
     Database(database): Database,
 ) -> FydiaResult {
-    let perm = Permission::of_channel(&channel.id, &database).await?;
+    let perm = Permission::of_channel(channel.permission_channel(), &database).await?;
 
     FydiaResponse::from_serialize(perm).into()
 }
+
+/// Get computed permissions of a member in a channel.
+///
+/// Every flag comes with the owner, role or overwrite that allowed or denied it.
+/// Only members that can manage roles can see permissions of another member.
+///
+/// # Errors
+/// Return an error if :
+/// * channelid, serverid, userid isn't valid
+/// * user cannot manage roles
+pub async fn get_effective_permission(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    if member.id != user.id {
+        check_channel_permission(
+            &user,
+            &server,
+            &channel,
+            &PermissionValue::ManageRoles,
+            &database,
+        )
+        .await?;
+    }
+
+    let perms = Permission::of_user_with_role_in_channel(
+        channel.permission_channel(),
+        &member.id,
+        &database,
+    )
+    .await?;
+
+    FydiaResponse::from_serialize(perms.effective(server.owner == member.id)).into()
+}
@@ -1,10 +1,13 @@
+use super::check_not_thread;
+use crate::handlers::api::server::{
+    check_channel_permission, check_role_hierarchy, check_server_permissions,
+};
 use crate::handlers::basic::{
     ChannelFromId, Database, RoleFromId, ServerJoinedFromId, UserFromToken,
 };
-use crate::handlers::{get_json, get_json_value_from_body};
+use crate::handlers::{get_json_permission, get_json_value_from_body};
 use fydia_sql::impls::permission::PermissionSql;
-use fydia_sql::impls::user::SqlUser;
-use fydia_struct::permission::Permission;
+use fydia_struct::permission::{Permission, PermissionValue};
 use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
 
 /// Get permission of role
@@ -18,17 +21,24 @@ pub async fn get_permission_of_role(
     RoleFromId(role): RoleFromId,
     Database(database): Database,
 ) -> FydiaResult {
-    let perm = Permission::of_role_in_channel(&channel.id, &role.id, &database).await?;
+    let perm =
+        Permission::of_role_in_channel(channel.permission_channel(), &role.id, &database).await?;
 
     FydiaResult::Ok(FydiaResponse::from_serialize(perm))
 }
 
-/// Post permission of user
+/// Set permission overwrite of a role in a channel.
+///
+/// Body contains allowed flags as `value` and can contain denied flags as `deny`.
 ///
 /// # Errors
 /// Return an error if :
 /// * channelid, serverid, roleid isn't valid
-pub async fn post_permission_of_user(
+/// * user cannot manage roles
+/// * role isn't below user highest role
+/// * body isn't valid
+/// * a given flag isn't a permission of user
+pub async fn post_permission_of_role(
     UserFromToken(user): UserFromToken,
     ServerJoinedFromId(server): ServerJoinedFromId,
     ChannelFromId(channel): ChannelFromId,
@@ -36,31 +46,39 @@ pub async fn post_permission_of_user(
     Database(database): Database,
     body: String,
 ) -> FydiaResult {
-    let perm = user.permission_of_server(&server.id, &database).await?;
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::ManageRoles,
+        &database,
+    )
+    .await?;
+    check_role_hierarchy(&user, &server, &role, &database).await?;
 
-    if !perm.can(&fydia_struct::permission::PermissionValue::Admin) {
-        return FydiaResult::Err("Not enought permission".into_forbidden_error());
-    }
+    check_not_thread(&channel)?;
 
     let json = get_json_value_from_body(&body)?;
 
-    let value = get_json("value", &json)?
-        .parse()
-        .map_err(|_err| "Bad value".into_server_error())?;
+    let value = get_json_permission("value", &json)?;
+    let deny = if json.get("deny").is_some() {
+        get_json_permission("deny", &json)?
+    } else {
+        0
+    };
+
+    check_server_permissions(&user, &server, value | deny, &database).await?;
 
     if let Ok(mut permission) =
         Permission::of_role_in_channel(&channel.id, &role.id, &database).await
     {
         permission.value = value;
+        permission.deny = deny;
         if permission.update_value(&database).await.is_err() {
             return FydiaResult::Err("Cannot update value".into_server_error());
         };
     } else {
-        let perm = Permission {
-            permission_type: fydia_struct::permission::PermissionType::Role(role.id),
-            channelid: Some(channel.id),
-            value,
-        };
+        let perm = Permission::role(role.id, Some(channel.id), value).with_deny(deny);
 
         if perm.insert(&database).await.is_err() {
             return FydiaResult::Err("Cannot insert value".into_server_error());
@@ -1,65 +1,86 @@
 use fydia_sql::impls::permission::PermissionSql;
 
-use fydia_sql::impls::user::SqlUser;
-
-use fydia_struct::permission::Permission;
+use fydia_struct::permission::{Permission, PermissionValue};
 use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
 
-use crate::handlers::basic::{ChannelFromId, Database, ServerJoinedFromId, UserFromToken};
-use crate::handlers::{get_json, get_json_value_from_body};
+use super::check_not_thread;
+use crate::handlers::api::server::{
+    check_channel_permission, check_member_hierarchy, check_server_permissions,
+};
+use crate::handlers::basic::{
+    ChannelFromId, Database, MemberFromId, ServerJoinedFromId, UserFromToken,
+};
+use crate::handlers::{get_json_permission, get_json_value_from_body};
 
-/// Get permission of user
+/// Get permission overwrite of a member in a channel
 ///
 /// # Errors
 /// Return an error if :
-/// * channelid, serverid, roleid isn't valid
+/// * channelid, serverid, userid isn't valid
+/// * member has no overwrite in this channel
 pub async fn get_permission_of_user(
-    UserFromToken(user): UserFromToken,
     ChannelFromId(channel): ChannelFromId,
+    MemberFromId(member): MemberFromId,
     Database(database): Database,
 ) -> FydiaResult {
-    let perm = Permission::of_user_in_channel(&channel.id, &user.id, &database).await?;
+    let perm =
+        Permission::of_user_in_channel(channel.permission_channel(), &member.id, &database).await?;
 
     FydiaResult::Ok(FydiaResponse::from_serialize(perm))
 }
 
-/// Post permission of user
+/// Set permission overwrite of a member in a channel.
+///
+/// Body contains allowed flags as `value` and can contain denied flags as `deny`.
 ///
 /// # Errors
 /// Return an error if :
-/// * channelid, serverid, roleid isn't valid
+/// * channelid, serverid, userid isn't valid
+/// * user cannot manage roles
+/// * member isn't below user highest role
+/// * body isn't valid
+/// * a given flag isn't a permission of user
 pub async fn post_permission_of_user(
     UserFromToken(user): UserFromToken,
     ChannelFromId(channel): ChannelFromId,
     ServerJoinedFromId(server): ServerJoinedFromId,
+    MemberFromId(member): MemberFromId,
     Database(database): Database,
     body: String,
 ) -> FydiaResult {
-    let perm = user.permission_of_server(&server.id, &database).await?;
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::ManageRoles,
+        &database,
+    )
+    .await?;
+    check_member_hierarchy(&user, &server, &member, &database).await?;
 
-    if !perm.can(&fydia_struct::permission::PermissionValue::Admin) {
-        return FydiaResult::Err("Not enought permission".into_forbidden_error());
-    }
+    check_not_thread(&channel)?;
 
     let json = get_json_value_from_body(&body)?;
 
-    let value = get_json("value", &json)?
-        .parse()
-        .map_err(|_err| "Bad value")?;
+    let value = get_json_permission("value", &json)?;
+    let deny = if json.get("deny").is_some() {
+        get_json_permission("deny", &json)?
+    } else {
+        0
+    };
+
+    check_server_permissions(&user, &server, value | deny, &database).await?;
 
     if let Ok(mut permission) =
-        Permission::of_user_in_channel(&channel.id, &user.id, &database).await
+        Permission::of_user_in_channel(&channel.id, &member.id, &database).await
     {
         permission.value = value;
+        permission.deny = deny;
         if permission.update_value(&database).await.is_err() {
             return FydiaResult::Err("Cannot update value".into_server_error());
         };
     } else {
-        let perm = Permission {
-            permission_type: fydia_struct::permission::PermissionType::User(user.id),
-            channelid: Some(channel.id),
-            value,
-        };
+        let perm = Permission::user(member.id, Some(channel.id), value).with_deny(deny);
 
         if perm.insert(&database).await.is_err() {
             return FydiaResult::Err("Cannot insert value".into_server_error());
@@ -0,0 +1,137 @@
+use crate::handlers::api::server::channels::messages::post::send_event;
+use crate::handlers::api::server::check_channel_permission;
+use crate::handlers::basic::{
+    ChannelFromId, Database, MessageFromId, PinsSettings, Rsa, ServerJoinedFromId, UserFromToken,
+    WebsocketManager,
+};
+use fydia_sql::impls::pin::SqlPin;
+use fydia_struct::{
+    event::{Event, EventContent},
+    permission::PermissionValue,
+    pin::{Pin, PinError},
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+};
+use fydia_utils::http::StatusCode;
+
+/// Convert an error of pin state or limit to a `BAD_REQUEST` response
+fn pin_error(error: &PinError) -> FydiaResponse {
+    match error {
+        PinError::AlreadyPinned | PinError::NotPinned | PinError::TooManyPins => error
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST),
+        _ => error.to_string().into_server_error(),
+    }
+}
+
+/// Return pinned messages of a channel, most recently pinned first
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid or token isn't valid
+/// * user cannot read this channel
+pub async fn get_pins(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    check_channel_permission(&user, &server, &channel, &PermissionValue::Read, &database).await?;
+
+    let pins = Pin::of_channel(&channel.id, &database).await?;
+
+    FydiaResponse::from_serialize(pins).into()
+}
+
+/// Pin a message in its channel
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, messageid or token isn't valid
+/// * user cannot manage messages in this channel
+/// * message is already pinned
+/// * channel has already the max number of pins
+#[allow(clippy::too_many_arguments)]
+pub async fn pin_message(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    MessageFromId(message): MessageFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    Rsa(rsa): Rsa,
+    PinsSettings(settings): PinsSettings,
+) -> FydiaResult {
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::ManageMessages,
+        &database,
+    )
+    .await?;
+
+    Pin::new(message.clone(), user.id)
+        .insert(settings.max_per_channel, &database)
+        .await
+        .map_err(|error| pin_error(&error))?;
+
+    let event = Event::new(
+        server.id.clone(),
+        EventContent::PinsUpdate {
+            channelid: channel.id,
+            message_id: message.id,
+            pinned: true,
+        },
+    );
+
+    if let FydiaResult::Err(error) = send_event(event, server, &rsa, wbsocket, database).await {
+        return FydiaResult::Err(error);
+    }
+
+    "Message pinned".into()
+}
+
+/// Unpin a message of its channel
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, messageid or token isn't valid
+/// * user cannot manage messages in this channel
+/// * message isn't pinned
+pub async fn unpin_message(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    MessageFromId(message): MessageFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    Rsa(rsa): Rsa,
+) -> FydiaResult {
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::ManageMessages,
+        &database,
+    )
+    .await?;
+
+    Pin::delete(&message.id, &database)
+        .await
+        .map_err(|error| pin_error(&error))?;
+
+    let event = Event::new(
+        server.id.clone(),
+        EventContent::PinsUpdate {
+            channelid: channel.id,
+            message_id: message.id,
+            pinned: false,
+        },
+    );
+
+    if let FydiaResult::Err(error) = send_event(event, server, &rsa, wbsocket, database).await {
+        return FydiaResult::Err(error);
+    }
+
+    "Message unpinned".into()
+}
@@ -0,0 +1,217 @@
+use crate::handlers::api::manager::websockets::manager::{
+    WbManagerChannelTrait, WebsocketManagerChannel,
+};
+use crate::handlers::api::server::check_channel_permission;
+use crate::handlers::basic::{
+    ChannelFromId, Database, MessageFromId, ServerJoinedFromId, UserFromToken, WebsocketManager,
+};
+use crate::handlers::{get_json, get_json_value_from_body, get_optional_u32};
+use axum::extract::Query;
+use fydia_sql::impls::{channel::SqlChannel, thread::SqlThread};
+use fydia_sql::sqlpool::DbConnection;
+use fydia_struct::{
+    channel::{Channel, ChannelError},
+    event::{Event, EventContent},
+    permission::PermissionValue,
+    querystring::QsThreads,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    server::Server,
+};
+use fydia_utils::{http::StatusCode, serde_json::Value};
+use std::sync::Arc;
+
+/// Convert an error of thread state or value to a `BAD_REQUEST` response
+fn thread_error(error: &ChannelError) -> FydiaResponse {
+    match error {
+        ChannelError::EmptyName
+        | ChannelError::CannotHaveThreads
+        | ChannelError::ThreadExists
+        | ChannelError::InvalidAutoArchive
+        | ChannelError::NotAThread => error
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST),
+        _ => error.to_string().into_server_error(),
+    }
+}
+
+/// Send a thread event to members of the channel
+///
+/// # Errors
+/// Return an error if members cannot be read or if event cannot be sent
+pub async fn send_thread_event(
+    server: &Server,
+    channel: &Channel,
+    content: EventContent,
+    database: &DbConnection,
+    wbsocket: &Arc<WebsocketManagerChannel>,
+) -> Result<(), FydiaResponse> {
+    wbsocket
+        .send(
+            &Event::new(server.id.clone(), content),
+            &channel.users(database).await?,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send thread event".into_server_error()
+        })
+}
+
+/// Mark a new message in a thread, members are told when an archived thread is unarchived
+///
+/// # Errors
+/// Return an error if thread cannot be updated
+pub async fn bump_thread(
+    thread: &mut Channel,
+    server: &Server,
+    database: &DbConnection,
+    wbsocket: &Arc<WebsocketManagerChannel>,
+) -> Result<(), FydiaResponse> {
+    let was_archived = thread.thread.as_ref().is_some_and(|thread| thread.archived);
+
+    thread
+        .bump_thread(database)
+        .await
+        .map_err(|error| thread_error(&error))?;
+
+    if was_archived {
+        send_thread_event(
+            server,
+            thread,
+            EventContent::ThreadUpdate {
+                thread: Box::new(thread.clone()),
+            },
+            database,
+            wbsocket,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Start a thread from a message.
+///
+/// Body is `{"name": NAME}` and can contain minutes without message before
+/// the thread is archived as `auto_archive`. Return id of the thread.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, messageid, token or body isn't valid
+/// * user cannot write in this channel
+/// * channel isn't a text channel
+/// * a thread already started from this message
+pub async fn create_thread(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    MessageFromId(message): MessageFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_channel_permission(&user, &server, &channel, &PermissionValue::Write, &database).await?;
+
+    let json = get_json_value_from_body(&body)?;
+    let name = get_json("name", &json)?;
+    let auto_archive = get_optional_u32("auto_archive", &json)?;
+
+    let thread = Channel::new_thread(name, &channel, &message.id, auto_archive)
+        .map_err(|error| thread_error(&error))?;
+
+    thread
+        .insert_thread(&database)
+        .await
+        .map_err(|error| thread_error(&error))?;
+
+    send_thread_event(
+        &server,
+        &channel,
+        EventContent::ThreadCreate {
+            thread: Box::new(thread.clone()),
+        },
+        &database,
+        &wbsocket,
+    )
+    .await?;
+
+    thread.id.id.into()
+}
+
+/// Return active threads of a channel, or archived ones with `?archived=true`
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid or token isn't valid
+/// * user cannot read this channel
+pub async fn get_threads(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(channel): ChannelFromId,
+    Database(database): Database,
+    Query(qs): Query<QsThreads>,
+) -> FydiaResult {
+    check_channel_permission(&user, &server, &channel, &PermissionValue::Read, &database).await?;
+
+    let threads = Channel::threads_of(&channel.id, qs.archived, &database).await?;
+
+    FydiaResponse::from_serialize(threads).into()
+}
+
+/// Archive, unarchive or change minutes before archive of a thread.
+///
+/// Body can contain `archived` and `auto_archive`.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, channelid, token or body isn't valid
+/// * channel isn't a thread
+/// * user cannot manage channels
+pub async fn update_thread(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ChannelFromId(mut thread): ChannelFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_channel_permission(
+        &user,
+        &server,
+        &thread,
+        &PermissionValue::ManageChannels,
+        &database,
+    )
+    .await?;
+
+    let json = get_json_value_from_body(&body)?;
+    let archived = match json.get("archived") {
+        None | Some(Value::Null) => None,
+        Some(Value::Bool(archived)) => Some(*archived),
+        Some(_) => {
+            return "`archived` should be a boolean"
+                .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+                .into()
+        }
+    };
+    let auto_archive = get_optional_u32("auto_archive", &json)?;
+
+    thread
+        .update_thread(archived, auto_archive, &database)
+        .await
+        .map_err(|error| thread_error(&error))?;
+
+    let content = if archived == Some(true) {
+        EventContent::ThreadArchive {
+            channelid: thread.id.clone(),
+        }
+    } else {
+        EventContent::ThreadUpdate {
+            thread: Box::new(thread.clone()),
+        }
+    };
+
+    send_thread_event(&server, &thread, content, &database, &wbsocket).await?;
+
+    FydiaResponse::from_serialize(thread).into()
+}
@@ -1,23 +1,33 @@
-use fydia_struct::response::{FydiaResult, IntoFydia};
+use fydia_struct::{
+    permission::PermissionValue,
+    response::{FydiaResult, IntoFydia},
+};
 
 use crate::handlers::{
-    api::manager::typing::TypingManagerChannelTrait,
-    basic::{ChannelFromId, ServerJoinedFromId, TypingManager, UserFromToken},
+    api::{
+        manager::typing::{TypingManagerChannelTrait, TypingTarget},
+        server::check_channel_permission,
+    },
+    basic::{ChannelFromId, Database, ServerJoinedFromId, TypingManager, UserFromToken},
 };
 
 /// Start typing
 ///
 /// # Errors
-/// Return an error if typingmanager is unreachable
-/// or if serverid, channelid or the token isn't valid
+/// Return an error if typingmanager is unreachable,
+/// if serverid, channelid or the token isn't valid
+/// or if user cannot write in this channel
 pub async fn start_typing(
     UserFromToken(user): UserFromToken,
     ChannelFromId(channel): ChannelFromId,
     ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
     TypingManager(typing): TypingManager,
 ) -> FydiaResult {
+    check_channel_permission(&user, &server, &channel, &PermissionValue::Write, &database).await?;
+
     typing
-        .start_typing(user.id, channel.id, server.id)
+        .start_typing(user.id, TypingTarget::Channel(server.id, channel.id))
         .map_err(|error| {
             error!("{error}");
             "Can't start typing".into_server_error()
@@ -38,7 +48,7 @@ pub async fn stop_typing(
     TypingManager(typing): TypingManager,
 ) -> FydiaResult {
     typing
-        .stop_typing(user.id, channel.id, server.id)
+        .stop_typing(user.id, TypingTarget::Channel(server.id, channel.id))
         .map_err(|error| {
             error!("{error}");
             "Can't stop typing".into_server_error()
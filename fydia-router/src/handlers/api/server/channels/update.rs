@@ -1,29 +1,32 @@
 use fydia_sql::impls::channel::SqlChannel;
-use fydia_sql::impls::user::SqlUser;
 
+use fydia_struct::permission::PermissionValue;
 use fydia_struct::response::FydiaResult;
 
-use crate::handlers::basic::{ChannelFromId, Database, UserFromToken};
+use crate::handlers::api::server::check_channel_permission;
+use crate::handlers::basic::{ChannelFromId, Database, ServerJoinedFromId, UserFromToken};
 use crate::handlers::{get_json, get_json_value_from_body};
 
 /// Change name of a channel
 ///
 /// # Errors
 /// Return an error if serverid or channelid or body isn't valid
+/// or if user cannot manage channels
 pub async fn update_name(
     UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
     ChannelFromId(mut channel): ChannelFromId,
     Database(database): Database,
     body: String,
 ) -> FydiaResult {
-    if !user
-        .permission_of_channel(&channel.id, &database)
-        .await?
-        .calculate(Some(channel.id.clone()))?
-        .can_read()
-    {
-        return "Unknow channel".into();
-    }
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::ManageChannels,
+        &database,
+    )
+    .await?;
 
     let json = get_json_value_from_body(&body)?;
 
@@ -38,11 +41,23 @@ pub async fn update_name(
 ///
 /// # Errors
 /// Return an error if channelid or serverid or body isn't valid
+/// or if user cannot manage channels
 pub async fn update_description(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
     ChannelFromId(mut channel): ChannelFromId,
     Database(database): Database,
     body: String,
 ) -> FydiaResult {
+    check_channel_permission(
+        &user,
+        &server,
+        &channel,
+        &PermissionValue::ManageChannels,
+        &database,
+    )
+    .await?;
+
     let json = get_json_value_from_body(&body)?;
 
     let description = get_json("description", &json)?;
@@ -0,0 +1,62 @@
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::basic::{
+    Database, ServerJoinedFromId, Storage, UserFromToken, WebsocketManager,
+};
+use crate::handlers::picture::delete_picture;
+use fydia_sql::impls::server::SqlServer;
+use fydia_storage::StorageError;
+use fydia_struct::{
+    event::{Event, EventContent},
+    response::{FydiaResult, IntoFydia},
+};
+
+/// Delete a server with its channels, messages, roles, members, emojis and stored files
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token isn't valid
+/// * user isn't the owner of the server
+/// * database is unreachable
+pub async fn delete_server(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+    Storage(storage): Storage,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    if server.owner != user.id {
+        return "Only the owner can delete the server"
+            .into_forbidden_error()
+            .into();
+    }
+
+    let (files, pictures) = server.delete(&database).await?;
+
+    for file in files {
+        for key in [file.get_name(), file.description_key()] {
+            match storage.delete(&key).await {
+                Ok(()) | Err(StorageError::NotFound | StorageError::InvalidKey) => {}
+                Err(error) => error!("{error}"),
+            }
+        }
+    }
+
+    for picture in pictures {
+        delete_picture(storage.as_ref(), &picture).await;
+    }
+
+    delete_picture(storage.as_ref(), &server.icon).await;
+
+    wbsocket
+        .send(
+            &Event::new(server.id, EventContent::ServerDelete),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send server event".into_server_error()
+        })?;
+
+    "Server deleted".into()
+}
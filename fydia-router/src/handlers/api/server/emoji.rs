@@ -0,0 +1,204 @@
+use crate::handlers::api::manager::websockets::manager::{
+    WbManagerChannelTrait, WebsocketManagerChannel,
+};
+use crate::handlers::api::server::check_server_permission;
+use crate::handlers::basic::{
+    ContentType, Database, EmojiFromId, ServerJoinedFromId, Storage, UserFromToken,
+    WebsocketManager,
+};
+use crate::handlers::picture::{
+    delete_picture, picture_response, read_picture, resize_picture, store_picture,
+};
+use crate::handlers::{get_json, get_json_value_from_body};
+use axum::{
+    extract::{BodyStream, Query},
+    response::Response,
+};
+use fydia_sql::impls::emoji::SqlEmoji;
+use fydia_struct::{
+    emoji::{Emoji, EmojiError},
+    event::{Event, EventContent},
+    permission::PermissionValue,
+    querystring::{QsEmoji, QsPicture},
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    server::Server,
+};
+use fydia_utils::http::{HeaderMap, StatusCode};
+use std::sync::Arc;
+
+/// Send an emoji event to members of the server
+///
+/// # Errors
+/// Return an error if event cannot be sent
+async fn send_emoji_event(
+    server: &Server,
+    content: EventContent,
+    wbsocket: &Arc<WebsocketManagerChannel>,
+) -> Result<(), FydiaResponse> {
+    wbsocket
+        .send(
+            &Event::new(server.id.clone(), content),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send emoji event".into_server_error()
+        })
+}
+
+/// Convert an error of name or limit to a `BAD_REQUEST` response
+fn emoji_error(error: &EmojiError) -> FydiaResponse {
+    match error {
+        EmojiError::InvalidName | EmojiError::NameTaken | EmojiError::TooManyEmojis => error
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST),
+        _ => error.to_string().into_server_error(),
+    }
+}
+
+/// Return all emojis of a server
+///
+/// # Errors
+/// Return an error if serverid or token isn't valid
+pub async fn get_emojis(
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    let emojis = Emoji::by_server_id(&server.id, &database).await?;
+
+    FydiaResponse::from_serialize(emojis).into()
+}
+
+/// Return picture of an emoji.
+///
+/// Size can be chosen with `?size=64`.
+///
+/// # Errors
+/// Return an error if serverid, emojiid or token isn't valid
+pub async fn get_emoji(
+    EmojiFromId(emoji): EmojiFromId,
+    Storage(storage): Storage,
+    Query(qs): Query<QsPicture>,
+    headers: HeaderMap,
+) -> Result<Response, FydiaResponse> {
+    picture_response(storage.as_ref(), &emoji.path, qs.size, &headers).await
+}
+
+/// Upload a new emoji named with `?name=NAME`.
+///
+/// Body is a png / jpg / gif picture or a multipart body with a `file` field.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid or token isn't valid
+/// * user cannot manage emojis
+/// * name isn't valid or is already taken
+/// * server has too many emojis
+/// * file given isn't a picture or is too large
+#[allow(clippy::too_many_arguments)]
+pub async fn create_emoji(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+    Storage(storage): Storage,
+    WebsocketManager(wbsocket): WebsocketManager,
+    Query(qs): Query<QsEmoji>,
+    ContentType(mime, _): ContentType,
+    headers: HeaderMap,
+    body: BodyStream,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageEmojis, &database).await?;
+
+    Emoji::check_name(&qs.name).map_err(|error| emoji_error(&error))?;
+
+    let picture = read_picture(&mime, &headers, body).await?;
+    let pictures = resize_picture(picture).await?;
+    let name = store_picture(storage.as_ref(), pictures).await?;
+
+    let mut emoji = Emoji::new(server.id.clone(), qs.name, name.clone(), user.id.clone())
+        .map_err(|error| emoji_error(&error))?;
+
+    if let Err(error) = emoji.insert(&database).await {
+        delete_picture(storage.as_ref(), &name).await;
+        return emoji_error(&error).into();
+    }
+
+    send_emoji_event(
+        &server,
+        EventContent::EmojiCreate {
+            emoji: Box::new(emoji.clone()),
+        },
+        &wbsocket,
+    )
+    .await?;
+
+    FydiaResponse::from_serialize(emoji).into()
+}
+
+/// Rename an emoji
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, emojiid, token or body isn't valid
+/// * user cannot manage emojis
+/// * name isn't valid or is already taken
+pub async fn update_emoji_name(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    EmojiFromId(mut emoji): EmojiFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageEmojis, &database).await?;
+
+    let json = get_json_value_from_body(&body)?;
+    let name = get_json("name", &json)?;
+
+    emoji
+        .update_name(name, &database)
+        .await
+        .map_err(|error| emoji_error(&error))?;
+
+    send_emoji_event(
+        &server,
+        EventContent::EmojiUpdate {
+            emoji: Box::new(emoji),
+        },
+        &wbsocket,
+    )
+    .await?;
+
+    "Emoji renamed".into()
+}
+
+/// Delete an emoji and its picture
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, emojiid or token isn't valid
+/// * user cannot manage emojis
+pub async fn delete_emoji(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    EmojiFromId(emoji): EmojiFromId,
+    Database(database): Database,
+    Storage(storage): Storage,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageEmojis, &database).await?;
+
+    emoji.delete(&database).await?;
+
+    delete_picture(storage.as_ref(), &emoji.path).await;
+
+    send_emoji_event(
+        &server,
+        EventContent::EmojiDelete { emoji_id: emoji.id },
+        &wbsocket,
+    )
+    .await?;
+
+    "Emoji deleted".into()
+}
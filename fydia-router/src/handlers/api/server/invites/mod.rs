@@ -0,0 +1,90 @@
+use crate::handlers::api::server::check_server_permission;
+use crate::handlers::basic::{Database, InviteFromCode, ServerJoinedFromId, UserFromToken};
+use crate::handlers::{get_json_value_from_body, get_optional_u32};
+use fydia_sql::impls::invite::SqlInvite;
+use fydia_struct::{
+    invite::Invite,
+    permission::PermissionValue,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+};
+use fydia_utils::{http::StatusCode, serde_json::Value};
+
+/// Return all invites of a server
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token isn't valid
+/// * user cannot manage invites
+pub async fn get_invites(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageInvites, &database).await?;
+
+    let invites = Invite::by_server(&server.id, &database).await?;
+
+    FydiaResponse::from_serialize(invites).into()
+}
+
+/// Create a new invite in a server.
+///
+/// Body can contain `max_uses` and `expires_in` (in seconds),
+/// invite is unlimited without them.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token isn't valid
+/// * user cannot create invites
+/// * body isn't valid
+/// * database is unreachable
+pub async fn create_invite(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::CreateInvites, &database).await?;
+
+    let json = if body.is_empty() {
+        Value::Null
+    } else {
+        get_json_value_from_body(&body)?
+    };
+
+    let max_uses = get_optional_u32("max_uses", &json)?;
+    let expires_in = get_optional_u32("expires_in", &json)?;
+
+    let invite = Invite::new(server.id, user.id, max_uses, expires_in)?;
+
+    invite.insert(&database).await?;
+
+    FydiaResponse::from_serialize(invite).into()
+}
+
+/// Revoke an invite of a server
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, code, token isn't valid
+/// * user cannot manage invites
+pub async fn revoke_invite(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    InviteFromCode(invite): InviteFromCode,
+    Database(database): Database,
+) -> FydiaResult {
+    if invite.server_id != server.id {
+        return "No invite with this code"
+            .into_error_with_statuscode(StatusCode::NOT_FOUND)
+            .into();
+    }
+
+    if invite.creator != user.id {
+        check_server_permission(&user, &server, &PermissionValue::ManageInvites, &database).await?;
+    }
+
+    invite.delete(&database).await?;
+
+    "Invite revoked".into()
+}
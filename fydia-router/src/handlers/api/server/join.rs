@@ -1,23 +1,62 @@
-use fydia_sql::impls::server::SqlServer;
+use fydia_sql::impls::{ban::SqlBan, invite::SqlInvite, server::SqlServerId};
 
-use fydia_struct::{response::FydiaResult, server::ServerError};
+use fydia_struct::{
+    ban::Ban,
+    event::{Event, EventContent},
+    invite::InviteError,
+    response::{FydiaResult, IntoFydia},
+    server::ServerError,
+};
 
-use crate::handlers::basic::{Database, ServerFromId, UserFromToken};
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::basic::{Database, InviteFromCode, UserFromToken, WebsocketManager};
 
-/// Join a server
+/// Join a server with an invite code
 ///
 /// # Errors
-/// Return an error if serverid doesn't exist
+/// Return an error if:
+/// * code doesn't exist
+/// * invite is expired or has reached its maximum uses
+/// * user is already in the server
+/// * user is banned from the server
 pub async fn join(
     UserFromToken(mut user): UserFromToken,
-    ServerFromId(mut server): ServerFromId,
+    InviteFromCode(mut invite): InviteFromCode,
     Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
 ) -> FydiaResult {
+    if invite.is_expired() {
+        Err(InviteError::Expired)?;
+    }
+
+    let mut server = invite.server_id.get(&database).await?;
+
     if user.servers.is_join(&server.id) {
         Err(ServerError::AlreadyJoin)?;
     }
 
-    server.join(&mut user, &database).await?;
+    if let Err(error) = Ban::check_not_banned(&server.id, &user.id, &database).await {
+        return error.to_string().into_forbidden_error().into();
+    }
+
+    invite.use_invite(&mut server, &mut user, &database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::ServerJoin {
+                    userid: user.id,
+                    invite_code: invite.code,
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send join event".into_server_error()
+        })?;
 
     "Server joined".into()
 }
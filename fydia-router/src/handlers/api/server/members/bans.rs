@@ -0,0 +1,137 @@
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::api::server::{check_member_hierarchy, check_server_permission};
+use crate::handlers::basic::{
+    Database, ServerJoinedFromId, Storage, UserFromId, UserFromToken, WebsocketManager,
+};
+use crate::handlers::picture::delete_picture;
+use crate::handlers::{get_json_value_from_body, get_optional_u32};
+use fydia_sql::impls::ban::SqlBan;
+use fydia_struct::{
+    ban::Ban,
+    event::{Event, EventContent},
+    permission::PermissionValue,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+};
+use fydia_utils::serde_json::Value;
+
+/// Return all bans of a server
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token isn't valid
+/// * user cannot ban members
+pub async fn get_bans(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::BanMembers, &database).await?;
+
+    let bans = Ban::by_server(&server.id, &database).await?;
+
+    FydiaResponse::from_serialize(bans).into()
+}
+
+/// Ban a user from a server.
+///
+/// Body can contain a `reason` and the duration of the ban in seconds as `expires_in`.
+/// A member is removed from the server, a user that isn't a member cannot join it.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, userid, token or body isn't valid
+/// * user cannot ban members
+/// * banned user isn't below user highest role
+pub async fn ban_member(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    UserFromId(target): UserFromId,
+    Database(database): Database,
+    Storage(storage): Storage,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::BanMembers, &database).await?;
+    check_member_hierarchy(&user, &server, &target, &database).await?;
+
+    let json = if body.is_empty() {
+        Value::Null
+    } else {
+        get_json_value_from_body(&body)?
+    };
+
+    let reason = json
+        .get("reason")
+        .and_then(Value::as_str)
+        .map(ToString::to_string);
+    let expires_in = get_optional_u32("expires_in", &json)?;
+
+    let ban = Ban::new(
+        server.id.clone(),
+        target.id.clone(),
+        user.id.clone(),
+        reason,
+        expires_in,
+    )?;
+
+    if let Some(avatar) = ban.insert(&database).await? {
+        delete_picture(storage.as_ref(), &avatar).await;
+    }
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::MemberBan {
+                    ban: Box::new(ban.clone()),
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send member event".into_server_error()
+        })?;
+
+    FydiaResponse::from_serialize(ban).into()
+}
+
+/// Remove the ban of a user
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, userid, token isn't valid
+/// * user cannot ban members
+/// * user isn't banned
+pub async fn unban_member(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    UserFromId(target): UserFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::BanMembers, &database).await?;
+
+    let ban = Ban::by_user(&server.id, &target.id, &database).await?;
+    ban.delete(&database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::MemberUnban {
+                    userid: target.id,
+                    moderator: user.id,
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send member event".into_server_error()
+        })?;
+
+    "User unbanned".into()
+}
@@ -0,0 +1,102 @@
+pub mod bans;
+pub mod profile;
+
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::api::server::{check_member_hierarchy, check_server_permission};
+use crate::handlers::basic::{
+    Database, MemberFromId, ServerJoinedFromId, Storage, UserFromToken, WebsocketManager,
+};
+use crate::handlers::picture::delete_picture;
+use axum::extract::Query;
+use fydia_sql::impls::members::SqlMembers;
+use fydia_struct::{
+    event::{Event, EventContent},
+    member::MemberQuery,
+    permission::PermissionValue,
+    querystring::QsMembers,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    server::Members,
+};
+use fydia_utils::http::StatusCode;
+
+/// Return a page of members of a server with their name, roles and join date
+///
+/// Members are ordered by id, the next page is selected with `after`
+/// and `search` keeps members whose name starts with it.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token isn't valid
+/// * limit is 0
+/// * database is unreachable
+pub async fn get_members(
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+    Query(qs): Query<QsMembers>,
+) -> FydiaResult {
+    let query = MemberQuery::new(qs.after, qs.search, qs.limit).map_err(|error| {
+        error
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+    })?;
+
+    let page = Members::page(&server.id, &query, &database).await?;
+
+    FydiaResponse::from_serialize(page).into()
+}
+
+/// Remove a member from a server.
+///
+/// A member that removes themself leaves the server, removing another member
+/// kicks them and needs to be allowed to kick members.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, userid, token isn't valid
+/// * owner tries to leave the server
+/// * user cannot kick members
+/// * member isn't below user highest role
+pub async fn remove_member(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+    Storage(storage): Storage,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    let leave = member.id == user.id;
+
+    if leave {
+        if server.owner == user.id {
+            return "Owner cannot leave the server"
+                .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+                .into();
+        }
+    } else {
+        check_server_permission(&user, &server, &PermissionValue::KickMembers, &database).await?;
+        check_member_hierarchy(&user, &server, &member, &database).await?;
+    }
+
+    if let Some(avatar) = Members::delete(&server.id, &member.id, &database).await? {
+        delete_picture(storage.as_ref(), &avatar).await;
+    }
+
+    let content = if leave {
+        EventContent::ServerLeft { userid: member.id }
+    } else {
+        EventContent::MemberKick {
+            userid: member.id,
+            moderator: user.id,
+        }
+    };
+
+    wbsocket
+        .send(&Event::new(server.id, content), &server.members.members)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send member event".into_server_error()
+        })?;
+
+    "Member removed".into()
+}
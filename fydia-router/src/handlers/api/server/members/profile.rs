@@ -0,0 +1,240 @@
+use crate::handlers::api::manager::websockets::manager::{
+    WbManagerChannelTrait, WebsocketManagerChannel,
+};
+use crate::handlers::api::server::{check_member_hierarchy, check_server_permission};
+use crate::handlers::basic::{
+    ContentType, Database, MemberFromId, ServerJoinedFromId, Storage, UserFromToken,
+    WebsocketManager,
+};
+use crate::handlers::get_json_value_from_body;
+use crate::handlers::picture::{
+    delete_picture, picture_response, read_picture, resize_picture, store_picture,
+};
+use axum::{
+    extract::{BodyStream, Query},
+    response::Response,
+};
+use fydia_sql::{impls::members::SqlMembers, sqlpool::DbConnection};
+use fydia_struct::{
+    event::{Event, EventContent},
+    member::MemberProfile,
+    permission::PermissionValue,
+    querystring::QsPicture,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    server::{Members, Server},
+    user::{User, UserId},
+};
+use fydia_utils::{
+    http::{HeaderMap, StatusCode},
+    serde_json::Value,
+};
+use std::sync::Arc;
+
+/// Send a member with its profile to members of the server
+///
+/// # Errors
+/// Return an error if member cannot be read or if event cannot be sent
+async fn send_member_update(
+    server: &Server,
+    member: &UserId,
+    database: &DbConnection,
+    wbsocket: &Arc<WebsocketManagerChannel>,
+) -> Result<(), FydiaResponse> {
+    let member = Members::by_id(&server.id, member, database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id.clone(),
+                EventContent::MemberUpdate {
+                    member: Box::new(member),
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send member event".into_server_error()
+        })
+}
+
+/// Check that user can only change their own profile
+fn check_self(user: &User, member: &User) -> Result<(), FydiaResponse> {
+    if user.id != member.id {
+        return Err(
+            "You can only change your own avatar".into_error_with_statuscode(StatusCode::NOT_FOUND)
+        );
+    }
+
+    Ok(())
+}
+
+/// Return a member with its name, roles and server profile
+///
+/// # Errors
+/// Return an error if serverid, userid, token isn't valid
+pub async fn get_member(
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    let member = Members::by_id(&server.id, &member.id, &database).await?;
+
+    FydiaResponse::from_serialize(member).into()
+}
+
+/// Change nickname of a member.
+///
+/// Body contains the new nickname as `nickname`, an empty or null nickname removes it.
+/// Changing the nickname of another member needs to be allowed to manage nicknames.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, userid, token or body isn't valid
+/// * nickname is too long
+/// * user cannot change their nickname or cannot manage nicknames
+/// * member isn't below user highest role
+pub async fn update_nickname(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    if member.id == user.id {
+        check_server_permission(&user, &server, &PermissionValue::ChangeNickname, &database)
+            .await?;
+    } else {
+        check_server_permission(&user, &server, &PermissionValue::ManageNicknames, &database)
+            .await?;
+        check_member_hierarchy(&user, &server, &member, &database).await?;
+    }
+
+    let json = get_json_value_from_body(&body)?;
+    let nickname = match json.get("nickname") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(nickname)) => Some(nickname.as_str()),
+        Some(_) => {
+            return "nickname must be a string"
+                .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+                .into()
+        }
+    };
+
+    let nickname = MemberProfile::check_nickname(nickname).map_err(|error| {
+        error
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+    })?;
+
+    Members::update_nickname(&server.id, &member.id, nickname, &database).await?;
+
+    send_member_update(&server, &member.id, &database, &wbsocket).await?;
+
+    "Nickname updated".into()
+}
+
+/// Return avatar of a member in the server or avatar of the user without one.
+///
+/// Size can be chosen with `?size=64`.
+///
+/// # Errors
+/// Return an error if serverid, userid, token isn't valid or if member has no avatar
+pub async fn get_member_avatar(
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+    Storage(storage): Storage,
+    Query(qs): Query<QsPicture>,
+    headers: HeaderMap,
+) -> Result<Response, FydiaResponse> {
+    let profile = Members::profile(&server.id, &member.id, &database).await?;
+
+    let avatar = profile
+        .avatar
+        .or(member.avatar)
+        .ok_or_else(|| "No avatar".into_error_with_statuscode(StatusCode::NOT_FOUND))?;
+
+    picture_response(storage.as_ref(), &avatar, qs.size, &headers).await
+}
+
+/// Change avatar of user in the server.
+///
+/// Body is a png / jpg / gif picture or a multipart body with a `file` field.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, userid, token isn't valid
+/// * member isn't the user
+/// * file given isn't a picture or is too large
+#[allow(clippy::too_many_arguments)]
+pub async fn post_member_avatar(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+    Storage(storage): Storage,
+    WebsocketManager(wbsocket): WebsocketManager,
+    ContentType(mime, _): ContentType,
+    headers: HeaderMap,
+    body: BodyStream,
+) -> FydiaResult {
+    check_self(&user, &member)?;
+
+    let old_avatar = Members::profile(&server.id, &member.id, &database)
+        .await?
+        .avatar;
+
+    let picture = read_picture(&mime, &headers, body).await?;
+    let pictures = resize_picture(picture).await?;
+    let name = store_picture(storage.as_ref(), pictures).await?;
+
+    if let Err(error) =
+        Members::update_avatar(&server.id, &member.id, Some(name.clone()), &database).await
+    {
+        error!("{error}");
+        delete_picture(storage.as_ref(), &name).await;
+        return "Cannot update avatar".into_server_error().into();
+    }
+
+    if let Some(old_avatar) = old_avatar {
+        delete_picture(storage.as_ref(), &old_avatar).await;
+    }
+
+    send_member_update(&server, &member.id, &database, &wbsocket).await?;
+
+    "Avatar have been update".into()
+}
+
+/// Remove avatar of user in the server, avatar of the user is used instead.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, userid, token isn't valid
+/// * member isn't the user
+/// * member has no avatar in the server
+pub async fn delete_member_avatar(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+    Storage(storage): Storage,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    check_self(&user, &member)?;
+
+    let avatar = Members::profile(&server.id, &member.id, &database)
+        .await?
+        .avatar
+        .ok_or_else(|| "No avatar".into_error_with_statuscode(StatusCode::NOT_FOUND))?;
+
+    Members::update_avatar(&server.id, &member.id, None, &database).await?;
+
+    delete_picture(storage.as_ref(), &avatar).await;
+
+    send_member_update(&server, &member.id, &database, &wbsocket).await?;
+
+    "Avatar have been removed".into()
+}
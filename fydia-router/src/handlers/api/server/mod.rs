@@ -1,12 +1,25 @@
 use crate::handlers::basic::ServerJoinedFromId;
-use fydia_struct::response::{FydiaResponse, FydiaResult};
+use fydia_sql::{impls::user::SqlUser, sqlpool::DbConnection};
+use fydia_struct::{
+    channel::Channel,
+    permission::PermissionValue,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    roles::{Role, RoleError},
+    server::Server,
+    user::User,
+};
 
 pub mod channels;
 pub mod create;
+pub mod delete;
+pub mod emoji;
 pub mod info;
+pub mod invites;
 pub mod join;
+pub mod members;
 pub mod picture;
 pub mod roles;
+pub mod update;
 
 /// Return requested server
 ///
@@ -15,3 +28,145 @@ pub mod roles;
 pub async fn get_server(ServerJoinedFromId(server): ServerJoinedFromId) -> FydiaResult {
     FydiaResponse::from_serialize(server).into()
 }
+
+/// Check if user have a permission in the server.
+/// Owner of the server have all permissions.
+///
+/// # Errors
+/// Return an error if user isn't the owner of the server and
+/// doesn't have a role with this permission
+pub async fn check_server_permission(
+    user: &User,
+    server: &Server,
+    permission: &PermissionValue,
+    database: &DbConnection,
+) -> Result<(), FydiaResponse> {
+    if server.owner == user.id {
+        return Ok(());
+    }
+
+    let perm = user.permission_of_server(&server.id, database).await?;
+
+    if perm.is_empty() || !perm.can(permission) {
+        return Err("Not enought permission".into_forbidden_error());
+    }
+
+    Ok(())
+}
+
+/// Check if user have every flag of `value` in the server.
+/// Owner of the server have all permissions.
+///
+/// # Errors
+/// Return an error if user doesn't have a flag of `value`
+pub async fn check_server_permissions(
+    user: &User,
+    server: &Server,
+    value: u64,
+    database: &DbConnection,
+) -> Result<(), FydiaResponse> {
+    if server.owner == user.id {
+        return Ok(());
+    }
+
+    let perm = user.permission_of_server(&server.id, database).await?;
+
+    if !PermissionValue::from_value(value)
+        .iter()
+        .all(|flag| perm.can(flag))
+    {
+        return Err("Not enought permission".into_forbidden_error());
+    }
+
+    Ok(())
+}
+
+/// Check if user have a permission in a channel.
+///
+/// Server permissions of user roles are combined with overwrites of the channel,
+/// see `Permissions::value`. Owner and admins of the server have all permissions.
+/// Threads use overwrites of their parent channel.
+///
+/// # Errors
+/// Return an error if user doesn't have this permission in the channel
+pub async fn check_channel_permission(
+    user: &User,
+    server: &Server,
+    channel: &Channel,
+    permission: &PermissionValue,
+    database: &DbConnection,
+) -> Result<(), FydiaResponse> {
+    if server.owner == user.id {
+        return Ok(());
+    }
+
+    let perm = user
+        .permission_of_channel(channel.permission_channel(), database)
+        .await?;
+
+    if !perm.can(permission) {
+        return Err("Not enought permission".into_forbidden_error());
+    }
+
+    Ok(())
+}
+
+/// Return position of the highest role of user or `None` for the owner
+/// that is above every role.
+///
+/// # Errors
+/// Return an error if roles of user cannot be read
+pub async fn highest_role_position(
+    user: &User,
+    server: &Server,
+    database: &DbConnection,
+) -> Result<Option<u32>, FydiaResponse> {
+    if server.owner == user.id {
+        return Ok(None);
+    }
+
+    let roles = user.roles(&server.id, database).await?;
+
+    Ok(Some(Role::highest_position(&roles)))
+}
+
+/// Check if a role is strictly below the highest role of user
+///
+/// # Errors
+/// Return an error if user isn't the owner and role isn't below user highest role
+pub async fn check_role_hierarchy(
+    user: &User,
+    server: &Server,
+    role: &Role,
+    database: &DbConnection,
+) -> Result<(), FydiaResponse> {
+    match highest_role_position(user, server, database).await? {
+        Some(highest) if role.position >= highest => Err(RoleError::AboveHighestRole
+            .to_string()
+            .into_forbidden_error()),
+        _ => Ok(()),
+    }
+}
+
+/// Check if the highest role of a member is strictly below the highest role of user
+///
+/// # Errors
+/// Return an error if member is the owner or if member highest role
+/// isn't below user highest role
+pub async fn check_member_hierarchy(
+    user: &User,
+    server: &Server,
+    member: &User,
+    database: &DbConnection,
+) -> Result<(), FydiaResponse> {
+    let Some(member_highest) = highest_role_position(member, server, database).await? else {
+        return Err("Owner of the server cannot be managed".into_forbidden_error());
+    };
+
+    match highest_role_position(user, server, database).await? {
+        Some(highest) if member_highest >= highest => {
+            Err("Member isn't below your highest role".into_forbidden_error())
+        }
+        _ => Ok(()),
+    }
+}
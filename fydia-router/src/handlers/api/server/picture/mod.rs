@@ -1,72 +1,72 @@
-#![allow(clippy::unwrap_used)]
-
-use crate::handlers::basic::{Database, ServerJoinedFromId};
+use crate::handlers::api::server::{check_server_permission, update::send_server_update};
+use crate::handlers::basic::{
+    ContentType, Database, ServerJoinedFromId, Storage, UserFromToken, WebsocketManager,
+};
+use crate::handlers::picture::{
+    delete_picture, picture_response, read_picture, resize_picture, store_picture,
+};
+use axum::{
+    extract::{BodyStream, Query},
+    response::Response,
+};
 use fydia_sql::impls::server::SqlServer;
 use fydia_struct::{
-    file::File,
+    permission::PermissionValue,
+    querystring::QsPicture,
     response::{FydiaResponse, FydiaResult, IntoFydia},
 };
-use fydia_utils::http::StatusCode;
-use mime::Mime;
-use std::str::FromStr;
+use fydia_utils::http::HeaderMap;
 
-/// Return icon of server
+/// Return icon of server.
+///
+/// Size can be chosen with `?size=64`.
 ///
 /// # Errors
-/// Return an error if serverid isn't valid
-pub async fn get_picture_of_server(ServerJoinedFromId(server): ServerJoinedFromId) -> FydiaResult {
-    let value = File::get(server.icon).get_value().map_err(|error| {
-        error!("{error}");
-        "Cannot get file"
-    })?;
-
-    let mime_str = infer::get(&value)
-        .ok_or("Cannot get the mimetype")?
-        .to_string();
-
-    let mime = Mime::from_str(mime_str.as_str()).map_err(|error| {
-        error!("{error}");
-        "Cannot convert mime"
-    })?;
-
-    FydiaResponse::BytesWithContentType(value, mime).into()
+/// Return an error if serverid isn't valid or if server has no icon
+pub async fn get_picture_of_server(
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Storage(storage): Storage,
+    Query(qs): Query<QsPicture>,
+    headers: HeaderMap,
+) -> Result<Response, FydiaResponse> {
+    picture_response(storage.as_ref(), &server.icon, qs.size, &headers).await
 }
 
-const MAX_CONTENT_LENGHT: usize = 8_000_000;
-
-/// Change server picture
+/// Change server picture.
+///
+/// Body is a png / jpg / gif picture or a multipart body with a `file` field.
 ///
 /// # Errors
-/// This function will return an error if file given isn't a file or if file is too large
-/// of if server doesn't exist
+/// This function will return an error if file given isn't a picture, if file is too large,
+/// if server doesn't exist or if user cannot manage the server
+#[allow(clippy::too_many_arguments)]
 pub async fn post_picture_of_server(
+    UserFromToken(user): UserFromToken,
     ServerJoinedFromId(mut server): ServerJoinedFromId,
     Database(database): Database,
+    Storage(storage): Storage,
+    WebsocketManager(wbsocket): WebsocketManager,
+    ContentType(mime, _): ContentType,
+    headers: HeaderMap,
+    body: BodyStream,
 ) -> FydiaResult {
-    let body = vec![];
-    if body.len() > MAX_CONTENT_LENGHT {
-        return ""
-            .into_error_with_statuscode(StatusCode::PAYLOAD_TOO_LARGE)
-            .into();
-    }
-
-    let mimetype = infer::get(&body).ok_or("No body")?;
+    check_server_permission(&user, &server, &PermissionValue::ManageServer, &database).await?;
 
-    let mimetype_str = mimetype.extension();
-    if mimetype_str != "png" && mimetype_str != "jpg" && mimetype_str != "gif" {
-        return "Bad Image type retry with png / jpg / gif".into();
-    }
+    let picture = read_picture(&mime, &headers, body).await?;
+    let pictures = resize_picture(picture).await?;
+    let name = store_picture(storage.as_ref(), pictures).await?;
 
-    let file = File::new();
+    let old_icon = std::mem::replace(&mut server.icon, name.clone());
 
-    file.create_and_write(&body).map_err(|error| {
+    if let Err(error) = server.update(&database).await {
         error!("{error}");
-        "".into_server_error()
-    })?;
+        delete_picture(storage.as_ref(), &name).await;
+        return "Cannot update icon".into_server_error().into();
+    }
 
-    server.icon = file.get_name();
+    delete_picture(storage.as_ref(), &old_icon).await;
 
-    server.update(&database).await?;
+    send_server_update(&server, &wbsocket).await?;
 
     "Icon have been update".into()
 }
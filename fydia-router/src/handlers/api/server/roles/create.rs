@@ -0,0 +1,75 @@
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::basic::{Database, ServerJoinedFromId, UserFromToken, WebsocketManager};
+use crate::handlers::{get_json, get_json_permission, get_json_value_from_body};
+use fydia_sql::impls::role::SqlRoles;
+use fydia_struct::event::{Event, EventContent};
+use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
+use fydia_struct::roles::{Role, RoleError};
+use fydia_utils::http::StatusCode;
+
+use crate::handlers::api::server::{check_server_permission, check_server_permissions};
+use fydia_struct::permission::PermissionValue;
+
+const DEFAULT_COLOR: &str = "#ffffff";
+
+/// Create a new role in a server
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token isn't valid
+/// * user cannot manage roles
+/// * a given permission isn't a permission of user
+/// * color isn't formatted as `#RRGGBB`
+/// * body isn't valid
+/// * database is unreachable
+pub async fn create_role(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageRoles, &database).await?;
+
+    let json = get_json_value_from_body(&body)?;
+
+    let name = get_json("name", &json)?;
+    let color = get_json("color", &json).unwrap_or(DEFAULT_COLOR);
+
+    if !Role::is_valid_color(color) {
+        return RoleError::InvalidColor
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    let server_permission = if json.get("server_permission").is_some() {
+        get_json_permission("server_permission", &json)?
+    } else {
+        0
+    };
+
+    check_server_permissions(&user, &server, server_permission, &database).await?;
+
+    let mut role = Role::new(server.id.clone(), name, color, server_permission)?;
+
+    role.insert(&database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::RoleCreate {
+                    role: Box::new(role.clone()),
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send role event".into_server_error()
+        })?;
+
+    FydiaResponse::from_serialize(role).into()
+}
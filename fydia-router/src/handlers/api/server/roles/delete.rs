@@ -0,0 +1,52 @@
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::basic::{
+    Database, ServerJoinedFromId, ServerRoleFromId, UserFromToken, WebsocketManager,
+};
+use fydia_sql::impls::role::SqlRoles;
+use fydia_struct::event::{Event, EventContent};
+use fydia_struct::response::{FydiaResult, IntoFydia};
+use fydia_utils::http::StatusCode;
+
+use crate::handlers::api::server::{check_role_hierarchy, check_server_permission};
+use fydia_struct::permission::PermissionValue;
+
+/// Delete a role of a server
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, roleid, token isn't valid
+/// * user cannot manage roles
+/// * role isn't below user highest role
+/// * role is `@everyone`
+/// * database is unreachable
+pub async fn delete_role(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ServerRoleFromId(role): ServerRoleFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageRoles, &database).await?;
+    check_role_hierarchy(&user, &server, &role, &database).await?;
+
+    if role.is_everyone() {
+        return "@everyone cannot be deleted"
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    role.delete(&database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(server.id, EventContent::RoleDelete { role_id: role.id }),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send role event".into_server_error()
+        })?;
+
+    "Role deleted".into()
+}
@@ -0,0 +1,142 @@
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::basic::{
+    Database, MemberFromId, ServerJoinedFromId, ServerRoleFromId, UserFromToken, WebsocketManager,
+};
+use fydia_sql::impls::{role::SqlRoles, user::SqlUser};
+use fydia_struct::event::{Event, EventContent};
+use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
+use fydia_utils::http::StatusCode;
+
+use crate::handlers::api::server::{check_role_hierarchy, check_server_permission};
+use fydia_struct::permission::PermissionValue;
+
+/// Return all members that have the role
+///
+/// # Errors
+/// Return an error if serverid, roleid or token isn't valid
+pub async fn get_members_of_role(
+    ServerRoleFromId(role): ServerRoleFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    let users = role.users(&database).await?;
+
+    FydiaResponse::from_serialize(users).into()
+}
+
+/// Give a role to a member of the server
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, roleid, userid, token isn't valid
+/// * user cannot manage roles
+/// * role isn't below user highest role
+/// * role is `@everyone`
+/// * member already have the role
+pub async fn assign_role(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ServerRoleFromId(role): ServerRoleFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageRoles, &database).await?;
+    check_role_hierarchy(&user, &server, &role, &database).await?;
+
+    if role.is_everyone() {
+        return "Every member have @everyone"
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    if member
+        .roles(&server.id, &database)
+        .await?
+        .iter()
+        .any(|i| i.id == role.id)
+    {
+        return "Member already have this role"
+            .into_error_with_statuscode(StatusCode::CONFLICT)
+            .into();
+    }
+
+    role.add_user(&member.id, &database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::RoleAssign {
+                    role_id: role.id,
+                    userid: member.id,
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send role event".into_server_error()
+        })?;
+
+    "Role assigned".into()
+}
+
+/// Remove a role from a member of the server
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, roleid, userid, token isn't valid
+/// * user cannot manage roles
+/// * role isn't below user highest role
+/// * role is `@everyone`
+/// * member doesn't have the role
+pub async fn unassign_role(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ServerRoleFromId(role): ServerRoleFromId,
+    MemberFromId(member): MemberFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageRoles, &database).await?;
+    check_role_hierarchy(&user, &server, &role, &database).await?;
+
+    if role.is_everyone() {
+        return "Every member have @everyone"
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    if !member
+        .roles(&server.id, &database)
+        .await?
+        .iter()
+        .any(|i| i.id == role.id)
+    {
+        return "Member doesn't have this role"
+            .into_error_with_statuscode(StatusCode::NOT_FOUND)
+            .into();
+    }
+
+    role.remove_user(&member.id, &database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::RoleUnassign {
+                    role_id: role.id,
+                    userid: member.id,
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send role event".into_server_error()
+        })?;
+
+    "Role unassigned".into()
+}
@@ -1 +1,33 @@
+pub mod create;
+pub mod delete;
+pub mod members;
+pub mod reorder;
+pub mod update;
 
+use crate::handlers::basic::{Database, ServerJoinedFromId, ServerRoleFromId};
+use fydia_sql::impls::role::SqlRoles;
+use fydia_struct::{
+    response::{FydiaResponse, FydiaResult},
+    roles::Role,
+};
+
+/// Return all roles of a server
+///
+/// # Errors
+/// Return an error if serverid or token isn't valid
+pub async fn get_roles(
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+) -> FydiaResult {
+    let roles = Role::by_server_id(&server.id.id, &database).await?;
+
+    FydiaResponse::from_serialize(roles).into()
+}
+
+/// Return requested role
+///
+/// # Errors
+/// Return an error if serverid, roleid or token isn't valid
+pub async fn get_role(ServerRoleFromId(role): ServerRoleFromId) -> FydiaResult {
+    FydiaResponse::from_serialize(role).into()
+}
@@ -0,0 +1,76 @@
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::basic::{Database, ServerJoinedFromId, UserFromToken, WebsocketManager};
+use crate::handlers::get_json_value_from_body;
+use fydia_sql::impls::role::SqlRoles;
+use fydia_struct::event::{Event, EventContent};
+use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
+use fydia_struct::roles::{Role, RoleError};
+use fydia_struct::utils::Id;
+use fydia_utils::{http::StatusCode, serde_json};
+
+use crate::handlers::api::server::{check_server_permission, highest_role_position};
+use fydia_struct::permission::PermissionValue;
+
+/// Change the order of roles of a server.
+///
+/// Body contains ids of every role except `@everyone` as `roles`,
+/// from the lowest to the highest. All positions are saved at once.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token or body isn't valid
+/// * user cannot manage roles
+/// * a role at or above user highest role is moved
+/// * database is unreachable
+pub async fn reorder_roles(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageRoles, &database).await?;
+
+    let json = get_json_value_from_body(&body)?;
+    let order = json
+        .get("roles")
+        .and_then(|roles| serde_json::from_value::<Vec<u32>>(roles.clone()).ok())
+        .ok_or(FydiaResponse::TextError(
+            "roles must be an array of role ids",
+        ))?
+        .into_iter()
+        .map(Id::Id)
+        .collect::<Vec<_>>();
+
+    let limit = highest_role_position(&user, &server, &database).await?;
+    let mut roles = Role::by_server_id(&server.id.id, &database).await?;
+
+    let moved = Role::reorder(&mut roles, &order, limit).map_err(|error| match error {
+        RoleError::AboveHighestRole => error.to_string().into_forbidden_error(),
+        _ => error
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST),
+    })?;
+
+    Role::update_positions(&moved, &database).await?;
+
+    roles.sort_by_key(|role| role.position);
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::RolesReorder {
+                    roles: roles.clone(),
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send role event".into_server_error()
+        })?;
+
+    FydiaResponse::from_serialize(roles).into()
+}
@@ -0,0 +1,197 @@
+use crate::handlers::api::manager::websockets::manager::WbManagerChannelTrait;
+use crate::handlers::basic::{
+    Database, ServerJoinedFromId, ServerRoleFromId, UserFromToken, WebsocketManager,
+};
+use crate::handlers::{get_json, get_json_permission, get_json_value_from_body};
+use fydia_sql::impls::role::SqlRoles;
+use fydia_struct::event::{Event, EventContent};
+use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
+use fydia_struct::roles::{Role, RoleError};
+
+use crate::handlers::api::server::{
+    check_role_hierarchy, check_server_permission, check_server_permissions,
+};
+use fydia_struct::permission::PermissionValue;
+
+/// Return name of a role
+///
+/// # Errors
+/// Return an error if serverid, roleid or token isn't valid
+pub async fn get_name(ServerRoleFromId(role): ServerRoleFromId) -> FydiaResult {
+    role.name.into()
+}
+
+/// Return color of a role
+///
+/// # Errors
+/// Return an error if serverid, roleid or token isn't valid
+pub async fn get_color(ServerRoleFromId(role): ServerRoleFromId) -> FydiaResult {
+    role.color.into()
+}
+
+/// Return server permissions of a role
+///
+/// # Errors
+/// Return an error if serverid, roleid or token isn't valid
+pub async fn get_permission(ServerRoleFromId(role): ServerRoleFromId) -> FydiaResult {
+    FydiaResponse::from_serialize(PermissionValue::from_value(role.server_permission)).into()
+}
+
+/// Change name of a role
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, roleid, token or body isn't valid
+/// * user cannot manage roles
+/// * role isn't below user highest role
+/// * role is `@everyone`
+pub async fn update_name(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ServerRoleFromId(mut role): ServerRoleFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageRoles, &database).await?;
+    check_role_hierarchy(&user, &server, &role, &database).await?;
+
+    if role.is_everyone() {
+        return "@everyone cannot be renamed"
+            .into_error_with_statuscode(fydia_utils::http::StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    let json = get_json_value_from_body(&body)?;
+    let name = get_json("name", &json)?;
+
+    if name.is_empty() {
+        return "Role name cannot be empty"
+            .into_error_with_statuscode(fydia_utils::http::StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    role.update_name(name, &database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::RoleUpdate {
+                    role: Box::new(role),
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send role event".into_server_error()
+        })?;
+
+    "Role name updated".into()
+}
+
+/// Change color of a role
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, roleid, token or body isn't valid
+/// * user cannot manage roles
+/// * role isn't below user highest role
+/// * color isn't formatted as `#RRGGBB`
+pub async fn update_color(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ServerRoleFromId(mut role): ServerRoleFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageRoles, &database).await?;
+    check_role_hierarchy(&user, &server, &role, &database).await?;
+
+    let json = get_json_value_from_body(&body)?;
+    let color = get_json("color", &json)?;
+
+    if !Role::is_valid_color(color) {
+        return RoleError::InvalidColor
+            .to_string()
+            .into_error_with_statuscode(fydia_utils::http::StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    role.update_color(color, &database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::RoleUpdate {
+                    role: Box::new(role),
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send role event".into_server_error()
+        })?;
+
+    "Role color updated".into()
+}
+
+/// Change server permissions of a role.
+///
+/// Body contains flags as `server_permission`.
+/// Only permissions that user has can be given or removed.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, roleid, token or body isn't valid
+/// * user cannot manage roles
+/// * role isn't below user highest role
+/// * a given or removed permission isn't a permission of user
+pub async fn update_permission(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(server): ServerJoinedFromId,
+    ServerRoleFromId(mut role): ServerRoleFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageRoles, &database).await?;
+    check_role_hierarchy(&user, &server, &role, &database).await?;
+
+    let json = get_json_value_from_body(&body)?;
+    let server_permission = get_json_permission("server_permission", &json)?;
+
+    check_server_permissions(
+        &user,
+        &server,
+        server_permission ^ role.server_permission,
+        &database,
+    )
+    .await?;
+
+    role.update_permission(server_permission, &database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                server.id,
+                EventContent::RoleUpdate {
+                    role: Box::new(role),
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send role event".into_server_error()
+        })?;
+
+    "Role permission updated".into()
+}
@@ -0,0 +1,127 @@
+use crate::handlers::api::manager::websockets::manager::{
+    WbManagerChannelTrait, WebsocketManagerChannel,
+};
+use crate::handlers::api::server::check_server_permission;
+use crate::handlers::basic::{Database, ServerJoinedFromId, UserFromToken, WebsocketManager};
+use crate::handlers::{get_json, get_json_value_from_body};
+use fydia_sql::impls::server::SqlServer;
+use fydia_struct::{
+    event::{Event, EventContent},
+    permission::PermissionValue,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    server::{Server, ServerError},
+    user::UserId,
+};
+use fydia_utils::http::StatusCode;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// Send name, owner and icon of a server to its members
+///
+/// # Errors
+/// Return an error if event cannot be sent
+pub async fn send_server_update(
+    server: &Server,
+    wbsocket: &Arc<WebsocketManagerChannel>,
+) -> Result<(), FydiaResponse> {
+    wbsocket
+        .send(
+            &Event::new(
+                server.id.clone(),
+                EventContent::ServerUpdate {
+                    name: server.name.clone(),
+                    owner: server.owner.clone(),
+                    icon: server.icon.clone(),
+                },
+            ),
+            &server.members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send server event".into_server_error()
+        })
+}
+
+/// Change name of a server
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token or body isn't valid
+/// * name is empty
+/// * user cannot manage the server
+pub async fn update_server_name(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(mut server): ServerJoinedFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    check_server_permission(&user, &server, &PermissionValue::ManageServer, &database).await?;
+
+    let json = get_json_value_from_body(&body)?;
+    let name = get_json("name", &json)?;
+
+    if name.is_empty() {
+        return ServerError::EmptyNameServer
+            .to_string()
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    server.update_name(name, &database).await?;
+
+    send_server_update(&server, &wbsocket).await?;
+
+    "Server name updated".into()
+}
+
+/// Give the ownership of a server to one of its members.
+///
+/// Body contains the id of the new owner as `owner`.
+///
+/// # Errors
+/// Return an error if:
+/// * serverid, token or body isn't valid
+/// * user isn't the owner of the server
+/// * new owner isn't a member of the server or is already the owner
+pub async fn transfer_ownership(
+    UserFromToken(user): UserFromToken,
+    ServerJoinedFromId(mut server): ServerJoinedFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
+) -> FydiaResult {
+    if server.owner != user.id {
+        return "Only the owner can transfer the server"
+            .into_forbidden_error()
+            .into();
+    }
+
+    let json = get_json_value_from_body(&body)?;
+    let owner = json
+        .get("owner")
+        .and_then(|owner| owner.as_u64())
+        .and_then(|owner| u32::try_from(owner).ok())
+        .map(UserId::new)
+        .ok_or(FydiaResponse::TextError("owner must be a user id"))?;
+
+    if owner == server.owner {
+        return "User is already the owner"
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    if !server.members.members.contains(&owner) {
+        return "New owner isn't a member of the server"
+            .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            .into();
+    }
+
+    server.owner = owner;
+    server.update(&database).await?;
+
+    send_server_update(&server, &wbsocket).await?;
+
+    "Server ownership transferred".into()
+}
@@ -0,0 +1,74 @@
+use crate::handlers::basic::{ContentType, Database, Storage, UserFromToken};
+use crate::handlers::picture::{
+    delete_picture, picture_response, read_picture, resize_picture, store_picture,
+};
+use axum::{
+    extract::{BodyStream, Path, Query},
+    response::Response,
+};
+use fydia_sql::impls::user::SqlUser;
+use fydia_struct::{
+    querystring::QsPicture,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    user::User,
+};
+use fydia_utils::http::{HeaderMap, StatusCode};
+
+/// Return avatar of an user.
+///
+/// Size can be chosen with `?size=64`.
+///
+/// # Errors
+/// Return an error if token or userid isn't valid or if user has no avatar
+pub async fn get_avatar(
+    UserFromToken(_): UserFromToken,
+    Path(userid): Path<u32>,
+    Database(database): Database,
+    Storage(storage): Storage,
+    Query(qs): Query<QsPicture>,
+    headers: HeaderMap,
+) -> Result<Response, FydiaResponse> {
+    let user = User::by_id(userid, &database).await.map_err(|error| {
+        error!("{error}");
+        "Unknown user".into_error_with_statuscode(StatusCode::NOT_FOUND)
+    })?;
+
+    let avatar = user
+        .avatar
+        .ok_or_else(|| "No avatar".into_error_with_statuscode(StatusCode::NOT_FOUND))?;
+
+    picture_response(storage.as_ref(), &avatar, qs.size, &headers).await
+}
+
+/// Change avatar of user.
+///
+/// Body is a png / jpg / gif picture or a multipart body with a `file` field.
+///
+/// # Errors
+/// Return an error if token isn't valid, if file given isn't a picture or if file is too large
+pub async fn post_avatar(
+    UserFromToken(mut user): UserFromToken,
+    Database(database): Database,
+    Storage(storage): Storage,
+    ContentType(mime, _): ContentType,
+    headers: HeaderMap,
+    body: BodyStream,
+) -> FydiaResult {
+    let picture = read_picture(&mime, &headers, body).await?;
+    let pictures = resize_picture(picture).await?;
+    let name = store_picture(storage.as_ref(), pictures).await?;
+
+    let old_avatar = user.avatar.clone();
+
+    if let Err(error) = user.update_avatar(Some(name.clone()), &database).await {
+        error!("{error}");
+        delete_picture(storage.as_ref(), &name).await;
+        return "Cannot update avatar".into_server_error().into();
+    }
+
+    if let Some(old_avatar) = old_avatar {
+        delete_picture(storage.as_ref(), &old_avatar).await;
+    }
+
+    "Avatar have been update".into()
+}
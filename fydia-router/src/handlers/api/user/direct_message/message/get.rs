@@ -1,26 +1,30 @@
-use axum::extract::Path;
-use fydia_sql::impls::message::SqlMessage;
+use axum::extract::Query;
+use fydia_sql::impls::direct_message::SqlDirectMessageMessages;
 use fydia_struct::{
-    channel::ChannelId,
-    messages::Message,
-    response::{FydiaResult, IntoFydia},
+    messages::MessageQuery,
+    querystring::QsMessages,
+    response::{FydiaResponse, FydiaResult},
 };
 
-use fydia_utils::http::HeaderMap;
+use crate::handlers::basic::{Database, DirectMessageFromId};
 
-use crate::handlers::basic::Database;
-
-/// Get messages of a dm
+/// Get a page of messages of a dm
+///
+/// Page is selected like messages of a channel with
+/// `before`, `after`, `around` and `limit` in query string.
 ///
 /// # Errors
-/// This function will return an error if dm does not exist
+/// This function will return an error if:
+/// * dm does not exist or user isn't a member of it
+/// * query string isn't valid
 pub async fn get_message_dm(
-    _headers: HeaderMap,
-    Path(dm_id): Path<String>,
+    DirectMessageFromId(dm): DirectMessageFromId,
     Database(database): Database,
+    Query(qs): Query<QsMessages>,
 ) -> FydiaResult {
-    let message = Message::by_channel(ChannelId::new(dm_id.clone()), &database).await;
-    println!("{message:?}");
+    let query = MessageQuery::new(qs.before, qs.after, qs.around, qs.limit)?;
+
+    let page = dm.messages(&query, &database).await?;
 
-    "".into_not_implemented_error().into()
+    FydiaResponse::from_serialize(page).into()
 }
@@ -1,25 +1,70 @@
-use axum::extract::{Extension, Path};
-use fydia_sql::{impls::message::SqlMessage, sqlpool::DbConnection};
+use fydia_sql::impls::direct_message::{DirectMessageMembers, SqlDirectMessageMessages};
 use fydia_struct::{
     channel::ChannelId,
-    messages::Message,
-    response::{FydiaResult, IntoFydia},
+    event::{Event, EventContent},
+    messages::{Date, Message, MessageType},
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+    server::ServerId,
+};
+
+use crate::handlers::{
+    api::manager::websockets::manager::WbManagerChannelTrait,
+    basic::{Database, DirectMessageFromId, UserFromToken, WebsocketManager},
+    get_json, get_json_value_from_body,
 };
-use fydia_utils::http::HeaderMap;
 
 /// Send a new message in dm
 ///
 /// # Errors
-/// This function will return an error if dm isn't exists
+/// This function will return an error if:
+/// * dm doesn't exist or user isn't a member of it
+/// * body isn't valid
 pub async fn post_message_dm(
-    _headers: HeaderMap,
-    Path(dm_id): Path<String>,
-    Extension(database): Extension<DbConnection>,
+    UserFromToken(user): UserFromToken,
+    DirectMessageFromId(dm): DirectMessageFromId,
+    Database(database): Database,
+    WebsocketManager(wbsocket): WebsocketManager,
+    body: String,
 ) -> FydiaResult {
-    println!(
-        "{:?}",
-        Message::by_channel(ChannelId::new(dm_id.clone()), &database).await
-    );
+    let json = get_json_value_from_body(&body)?;
+
+    let message_type = MessageType::from_string(get_json("type", &json)?)
+        .map_err(|_f| FydiaResponse::TextError("Bad Message Type"))?;
+    let content = get_json("content", &json)?;
+
+    let dm_id = dm.id.get_id_cloned()?;
+
+    let message = Message::new(
+        content,
+        message_type,
+        false,
+        Date::now(),
+        user,
+        ChannelId {
+            id: dm_id.to_string(),
+        },
+    )?;
+
+    dm.insert_message(&message, &database).await?;
+
+    let members = dm.members(&database).await?;
+
+    wbsocket
+        .send(
+            &Event::new(
+                ServerId::new(String::new()),
+                EventContent::DirectMessage {
+                    directmessage_id: dm.id,
+                    content: Box::new(message),
+                },
+            ),
+            &members.members,
+        )
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            "Cannot send message".into_server_error()
+        })?;
 
-    "".into_not_implemented_error().into()
+    "Message send".into()
 }
@@ -0,0 +1,46 @@
+use fydia_struct::response::{FydiaResult, IntoFydia};
+
+use crate::handlers::{
+    api::manager::typing::{TypingManagerChannelTrait, TypingTarget},
+    basic::{DirectMessageFromId, TypingManager, UserFromToken},
+};
+
+/// Start typing in a dm
+///
+/// # Errors
+/// Return an error if typingmanager is unreachable
+/// or if dm doesn't exist or user isn't a member of it
+pub async fn start_typing_dm(
+    UserFromToken(user): UserFromToken,
+    DirectMessageFromId(dm): DirectMessageFromId,
+    TypingManager(typing): TypingManager,
+) -> FydiaResult {
+    typing
+        .start_typing(user.id, TypingTarget::DirectMessage(dm.id))
+        .map_err(|error| {
+            error!("{error}");
+            "Can't start typing".into_server_error()
+        })?;
+
+    "".into()
+}
+
+/// Stop typing in a dm
+///
+/// # Errors
+/// Return an error if typingmanager is unreachable
+/// or if dm doesn't exist or user isn't a member of it
+pub async fn stop_typing_dm(
+    UserFromToken(user): UserFromToken,
+    DirectMessageFromId(dm): DirectMessageFromId,
+    TypingManager(typing): TypingManager,
+) -> FydiaResult {
+    typing
+        .stop_typing(user.id, TypingTarget::DirectMessage(dm.id))
+        .map_err(|error| {
+            error!("{error}");
+            "Can't stop typing".into_server_error()
+        })?;
+
+    "".into()
+}
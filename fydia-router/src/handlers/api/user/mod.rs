@@ -1,3 +1,4 @@
+pub mod avatar;
 pub mod create;
 pub mod direct_message;
 pub mod login;
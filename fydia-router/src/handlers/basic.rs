@@ -3,21 +3,35 @@ use axum::{
     extract::{FromRequest, FromRequestParts, RawPathParams},
     http::{header::CONTENT_TYPE, Request},
 };
+use fydia_config::{PinsConfig, WebsocketConfig};
 use fydia_sql::{
     impls::{
-        channel::SqlChannelId, message::SqlMessage, role::SqlRoles, server::SqlServerId,
-        token::SqlToken, user::SqlUser,
+        channel::SqlChannelId,
+        direct_message::{DirectMessageMembers, SqlDirectMessage},
+        emoji::SqlEmoji,
+        invite::SqlInvite,
+        message::SqlMessage,
+        role::SqlRoles,
+        server::SqlServerId,
+        token::SqlToken,
+        user::{SqlUser, UserFrom},
     },
     sqlpool::DbConnection,
 };
+use fydia_storage::StorageBackend;
 use fydia_struct::{
     channel::{Channel, ChannelError, ChannelId},
+    directmessage::{DirectMessage, DirectMessageError},
+    emoji::{Emoji, EmojiError},
     instance::RsaData,
-    messages::Message,
+    invite::Invite,
+    messages::{Message, MessageError},
+    permission::PermissionValue,
     response::FydiaResponse,
     roles::Role,
-    server::{Server, ServerError, ServerId},
-    user::{Token, User},
+    server::{MembersError, Server, ServerError, ServerId},
+    user::{Token, User, UserId},
+    utils::Id,
 };
 use fydia_utils::async_trait;
 use mime::Mime;
@@ -76,6 +90,9 @@ create_from_state!(WebsocketManager, Arc<WebsocketManagerChannel>, wbsocket);
 create_from_state!(Rsa, Arc<RsaData>, rsa);
 create_from_state!(Database, DbConnection, database);
 create_from_state!(TypingManager, Arc<TypingManagerChannel>, typing);
+create_from_state!(WebsocketSettings, Arc<WebsocketConfig>, websocket_config);
+create_from_state!(Storage, Arc<dyn StorageBackend>, storage);
+create_from_state!(PinsSettings, Arc<PinsConfig>, pins_config);
 
 #[derive(Debug)]
 struct UrlGetter<T: UrlName>(String, PhantomData<T>);
@@ -134,14 +151,43 @@ impl FromRequestParts<ServerState> for RoleFromId {
         let UrlGetter(roleid, _) =
             UrlGetter::<RoleFromId>::from_request_parts(parts, state).await?;
 
-        if !user
-            .permission_of_channel(&channel.id, &state.database)
-            .await?
-            .calculate(Some(channel.id.clone()))?
-            .is_admin()
-        {
-            return Err(FydiaResponse::TextError("Unknow channel"));
-        }
+        check_channel_permission(
+            &user,
+            &server,
+            &channel,
+            &PermissionValue::ManageRoles,
+            &state.database,
+        )
+        .await?;
+
+        let roleid = roleid.as_str().parse()?;
+        let role = Role::by_id(roleid, &server.id, &state.database).await?;
+
+        Ok(Self(role))
+    }
+}
+
+#[derive(Debug)]
+pub struct ServerRoleFromId(pub Role);
+
+impl UrlName for ServerRoleFromId {
+    const URL_KEY: &'static str = "roleid";
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<ServerState> for ServerRoleFromId {
+    type Rejection = FydiaResponse;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection> {
+        let ServerJoinedFromId(server) =
+            ServerJoinedFromId::from_request_parts(parts, state).await?;
+
+        let UrlGetter(roleid, _) =
+            UrlGetter::<ServerRoleFromId>::from_request_parts(parts, state).await?;
+
         let roleid = roleid.as_str().parse()?;
         let role = Role::by_id(roleid, &server.id, &state.database).await?;
 
@@ -149,6 +195,150 @@ impl FromRequestParts<ServerState> for RoleFromId {
     }
 }
 
+#[derive(Debug)]
+pub struct EmojiFromId(pub Emoji);
+
+impl UrlName for EmojiFromId {
+    const URL_KEY: &'static str = "emojiid";
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<ServerState> for EmojiFromId {
+    type Rejection = FydiaResponse;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection> {
+        let ServerJoinedFromId(server) =
+            ServerJoinedFromId::from_request_parts(parts, state).await?;
+
+        let UrlGetter(emojiid, _) =
+            UrlGetter::<EmojiFromId>::from_request_parts(parts, state).await?;
+
+        let emoji = Emoji::by_id(emojiid.as_str().parse()?, &state.database).await?;
+
+        if emoji.server_id != server.id {
+            return Err(EmojiError::NoEmojiWithId.into());
+        }
+
+        Ok(Self(emoji))
+    }
+}
+
+#[derive(Debug)]
+pub struct MemberFromId(pub User);
+
+impl UrlName for MemberFromId {
+    const URL_KEY: &'static str = "userid";
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<ServerState> for MemberFromId {
+    type Rejection = FydiaResponse;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection> {
+        let ServerJoinedFromId(server) =
+            ServerJoinedFromId::from_request_parts(parts, state).await?;
+
+        let UrlGetter(userid, _) =
+            UrlGetter::<MemberFromId>::from_request_parts(parts, state).await?;
+
+        let member = UserId::new(userid.as_str().parse()?)
+            .to_user(&state.database)
+            .await?;
+
+        if !member.servers.is_join(&server.id) {
+            return Err(MembersError::NotMember.into());
+        }
+
+        Ok(Self(member))
+    }
+}
+
+#[derive(Debug)]
+pub struct UserFromId(pub User);
+
+impl UrlName for UserFromId {
+    const URL_KEY: &'static str = "userid";
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<ServerState> for UserFromId {
+    type Rejection = FydiaResponse;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection> {
+        let UrlGetter(userid, _) =
+            UrlGetter::<UserFromId>::from_request_parts(parts, state).await?;
+
+        let user = UserId::new(userid.as_str().parse()?)
+            .to_user(&state.database)
+            .await?;
+
+        Ok(Self(user))
+    }
+}
+
+#[derive(Debug)]
+pub struct InviteFromCode(pub Invite);
+
+impl UrlName for InviteFromCode {
+    const URL_KEY: &'static str = "code";
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<ServerState> for InviteFromCode {
+    type Rejection = FydiaResponse;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection> {
+        let UrlGetter(code, _) =
+            UrlGetter::<InviteFromCode>::from_request_parts(parts, state).await?;
+
+        let invite = Invite::by_code(&code, &state.database).await?;
+
+        Ok(Self(invite))
+    }
+}
+
+#[derive(Debug)]
+pub struct DirectMessageFromId(pub DirectMessage);
+
+impl UrlName for DirectMessageFromId {
+    const URL_KEY: &'static str = "dmid";
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<ServerState> for DirectMessageFromId {
+    type Rejection = FydiaResponse;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection> {
+        let UserFromToken(user) = UserFromToken::from_request_parts(parts, state).await?;
+
+        let UrlGetter(dmid, _) =
+            UrlGetter::<DirectMessageFromId>::from_request_parts(parts, state).await?;
+
+        let dm = DirectMessage::get(Id::Id(dmid.as_str().parse()?), &state.database).await?;
+
+        if !dm.is_member(&user.id, &state.database).await? {
+            return Err(DirectMessageError::CannotGetById.into());
+        }
+
+        Ok(Self(dm))
+    }
+}
+
 struct MessageId(pub String);
 
 impl UrlName for MessageId {
@@ -166,28 +356,37 @@ impl FromRequestParts<ServerState> for MessageFromId {
         state: &ServerState,
     ) -> Result<Self, Self::Rejection> {
         let UserFromToken(user) = UserFromToken::from_request_parts(parts, state).await?;
+        let ServerJoinedFromId(server) =
+            ServerJoinedFromId::from_request_parts(parts, state).await?;
         let ChannelFromId(channel) = ChannelFromId::from_request_parts(parts, state).await?;
 
         let UrlGetter(url_param, _) =
             UrlGetter::<MessageId>::from_request_parts(parts, state).await?;
 
-        if !user
-            .permission_of_channel(&channel.id, &state.database)
-            .await?
-            .calculate(Some(channel.id.clone()))?
-            .can_read()
-        {
-            return Err(ChannelError::CannotGetById.into());
-        }
+        check_channel_permission(
+            &user,
+            &server,
+            &channel,
+            &PermissionValue::Read,
+            &state.database,
+        )
+        .await?;
 
         let message = Message::by_id(&url_param, &state.database).await?;
 
+        if message.channel_id != channel.id {
+            return Err(MessageError::CannotGetById.into());
+        }
+
         Ok(Self(message))
     }
 }
 
 use super::{
-    api::manager::{typing::TypingManagerChannel, websockets::manager::WebsocketManagerChannel},
+    api::{
+        manager::{typing::TypingManagerChannel, websockets::manager::WebsocketManagerChannel},
+        server::check_channel_permission,
+    },
     get_json, get_json_value_from_body,
 };
 
@@ -214,7 +413,8 @@ impl FromRequestParts<ServerState> for ChannelFromId {
 
         let channel = ChannelId::new(channelid).channel(&state.database).await?;
 
-        if !server.channel.is_exists(&channel.id) {
+        // Threads aren't listed in channels of the server
+        if channel.parent_id != server.id {
             return Err(ChannelError::CannotGetById.into());
         }
 
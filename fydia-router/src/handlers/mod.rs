@@ -1,10 +1,18 @@
-use fydia_struct::response::{FydiaResponse, FydiaResult, IntoFydia};
-use fydia_utils::serde_json::{self, Value};
+use fydia_struct::{
+    permission::PermissionValue,
+    response::{FydiaResponse, FydiaResult, IntoFydia},
+};
+use fydia_utils::{
+    http::StatusCode,
+    serde_json::{self, Value},
+};
+use std::convert::TryFrom;
 use thiserror::Error;
 pub mod api;
 pub mod basic;
 pub mod event;
 pub mod federation;
+pub mod picture;
 
 /// Default response
 ///
@@ -43,6 +51,37 @@ pub fn get_json<T: Into<String>>(string: T, json: &Value) -> Result<&str, FydiaR
         .ok_or(JsonError::CannotConvert(string).into())
 }
 
+/// Get a permission value from json.
+///
+/// Value can be a number, a string of a number or an array of permission names.
+///
+/// # Errors
+/// This function will return an error if value isn't found or isn't a valid permission
+pub fn get_json_permission<T: Into<String>>(string: T, json: &Value) -> Result<u64, FydiaResponse> {
+    let string = string.into();
+    let value = json.get(&string).ok_or(JsonError::CannotGet(string))?;
+
+    Ok(PermissionValue::from_json(value)?)
+}
+
+/// Get an optional positive integer from json
+///
+/// # Errors
+/// This function will return an error if value isn't null or a positive integer
+pub fn get_optional_u32(key: &'static str, json: &Value) -> Result<Option<u32>, FydiaResponse> {
+    match json.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+            .map(Some)
+            .ok_or_else(|| {
+                format!("`{key}` should be a positive integer")
+                    .into_error_with_statuscode(StatusCode::BAD_REQUEST)
+            }),
+    }
+}
+
 #[derive(Debug, Error)]
 enum JsonError {
     #[error("No {0} in JSON payload")]
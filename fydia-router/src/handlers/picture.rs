@@ -0,0 +1,260 @@
+//! Pictures are server icons and user avatars.
+//!
+//! An uploaded picture is re-encoded as png in every size of `PICTURE_SIZES`
+//! and stored as `{name}-{size}`. A new upload gets a new name, so the name
+//! is used as `ETag`.
+
+use crate::handlers::api::server::channels::messages::post::{get_boundary, multipart_error};
+use axum::{
+    body::{boxed, Full},
+    extract::BodyStream,
+    response::Response,
+};
+use futures::StreamExt;
+use fydia_storage::{StorageBackend, StorageError};
+use fydia_struct::{
+    file::File,
+    response::{FydiaResponse, IntoFydia},
+};
+use fydia_utils::http::{
+    header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    HeaderMap, StatusCode,
+};
+use image::{imageops::FilterType, ImageFormat, ImageOutputFormat};
+use mime::Mime;
+use multer::{Constraints, Multipart, SizeLimit};
+use std::io::Cursor;
+
+/// Max size of an uploaded picture
+pub const MAX_CONTENT_LENGHT: usize = 8_000_000;
+
+/// Sizes in pixels of stored pictures
+pub const PICTURE_SIZES: [u32; 3] = [64, 128, 256];
+
+/// Room left for boundaries and other fields of a multipart body
+const MULTIPART_OVERHEAD: u64 = 64_000;
+
+const CACHE_CONTROL_VALUE: &str = "public, max-age=3600";
+
+/// Read a picture from a `multipart/form-data` body with a `file` field
+/// or from a raw body
+///
+/// # Errors
+/// Return an error if:
+/// * body isn't valid
+/// * body is larger than `MAX_CONTENT_LENGHT`
+/// * there is no `file` field
+pub async fn read_picture(
+    mime: &Mime,
+    headers: &HeaderMap,
+    mut body: BodyStream,
+) -> Result<Vec<u8>, FydiaResponse> {
+    if mime.essence_str() == mime::MULTIPART_FORM_DATA.essence_str() {
+        let boundary =
+            get_boundary(headers).ok_or(FydiaResponse::TextError("No boundary found"))?;
+        let mut multipart = Multipart::with_constraints(
+            body,
+            boundary,
+            Constraints::new().size_limit(
+                SizeLimit::new()
+                    .whole_stream(MAX_CONTENT_LENGHT as u64 + MULTIPART_OVERHEAD)
+                    .for_field("file", MAX_CONTENT_LENGHT as u64),
+            ),
+        );
+
+        while let Some(mut field) = multipart
+            .next_field()
+            .await
+            .map_err(|error| multipart_error(&error))?
+        {
+            if field.name() != Some("file") {
+                continue;
+            }
+
+            let mut picture = Vec::new();
+            while let Some(chunk) = field
+                .chunk()
+                .await
+                .map_err(|error| multipart_error(&error))?
+            {
+                picture.extend_from_slice(&chunk);
+            }
+
+            return Ok(picture);
+        }
+
+        return Err(FydiaResponse::TextError("No file field"));
+    }
+
+    let mut picture = Vec::new();
+    while let Some(chunk) = body.next().await {
+        picture.extend_from_slice(&chunk.map_err(|error| {
+            error!("{error}");
+            FydiaResponse::TextError("Body error")
+        })?);
+
+        if picture.len() > MAX_CONTENT_LENGHT {
+            return Err(
+                "Picture is too large".into_error_with_statuscode(StatusCode::PAYLOAD_TOO_LARGE)
+            );
+        }
+    }
+
+    Ok(picture)
+}
+
+/// Check the type of a picture
+///
+/// # Errors
+/// Return an error if picture isn't a png, jpg or gif
+pub fn check_picture(picture: &[u8]) -> Result<ImageFormat, FydiaResponse> {
+    let mimetype = infer::get(picture).ok_or(FydiaResponse::TextError("No body"))?;
+
+    match mimetype.extension() {
+        "png" => Ok(ImageFormat::Png),
+        "jpg" => Ok(ImageFormat::Jpeg),
+        "gif" => Ok(ImageFormat::Gif),
+        _ => Err(FydiaResponse::TextError(
+            "Bad Image type retry with png / jpg / gif",
+        )),
+    }
+}
+
+/// Resize a picture in every size of `PICTURE_SIZES`
+///
+/// # Errors
+/// Return an error if picture isn't a valid png, jpg or gif
+pub async fn resize_picture(picture: Vec<u8>) -> Result<Vec<(u32, Vec<u8>)>, FydiaResponse> {
+    let format = check_picture(&picture)?;
+
+    tokio::task::spawn_blocking(move || {
+        let image = image::load_from_memory_with_format(&picture, format)?;
+
+        PICTURE_SIZES
+            .iter()
+            .map(|size| {
+                let mut buffer = Cursor::new(Vec::new());
+                image
+                    .resize_to_fill(*size, *size, FilterType::Lanczos3)
+                    .write_to(&mut buffer, ImageOutputFormat::Png)?;
+
+                Ok((*size, buffer.into_inner()))
+            })
+            .collect::<Result<Vec<(u32, Vec<u8>)>, image::ImageError>>()
+    })
+    .await
+    .map_err(|error| {
+        error!("{error}");
+        "Cannot resize picture".into_server_error()
+    })?
+    .map_err(|error| {
+        error!("{error}");
+        FydiaResponse::TextError("Picture cannot be decoded")
+    })
+}
+
+fn picture_key(name: &str, size: u32) -> String {
+    format!("{name}-{size}")
+}
+
+/// Store all sizes of a picture and return its name
+///
+/// # Errors
+/// Return an error if a size cannot be written
+pub async fn store_picture(
+    storage: &dyn StorageBackend,
+    pictures: Vec<(u32, Vec<u8>)>,
+) -> Result<String, FydiaResponse> {
+    let name = File::new().get_name();
+
+    for (size, picture) in pictures {
+        if let Err(error) = storage.put_bytes(&picture_key(&name, size), picture).await {
+            error!("{error}");
+            delete_picture(storage, &name).await;
+
+            return Err("Cannot store picture".into_server_error());
+        }
+    }
+
+    Ok(name)
+}
+
+/// Delete all sizes of a picture
+pub async fn delete_picture(storage: &dyn StorageBackend, name: &str) {
+    if name.is_empty() {
+        return;
+    }
+
+    let keys = PICTURE_SIZES
+        .iter()
+        .map(|size| picture_key(name, *size))
+        // Pictures uploaded before resizing were stored as is
+        .chain(std::iter::once(name.to_string()));
+
+    for key in keys {
+        match storage.delete(&key).await {
+            Ok(()) | Err(StorageError::NotFound | StorageError::InvalidKey) => {}
+            Err(error) => error!("{error}"),
+        }
+    }
+}
+
+/// Return the smallest picture that is at least `size` pixels or the largest one.
+///
+/// A `304 Not Modified` is returned if `If-None-Match` matches.
+///
+/// # Errors
+/// Return an error if picture doesn't exist
+pub async fn picture_response(
+    storage: &dyn StorageBackend,
+    name: &str,
+    size: Option<u32>,
+    headers: &HeaderMap,
+) -> Result<Response, FydiaResponse> {
+    let size = PICTURE_SIZES
+        .iter()
+        .copied()
+        .find(|picture_size| size.is_some_and(|size| size <= *picture_size))
+        .unwrap_or(PICTURE_SIZES[PICTURE_SIZES.len() - 1]);
+
+    let key = picture_key(name, size);
+    let etag = format!("\"{key}\"");
+
+    let not_modified = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        });
+
+    let response = Response::builder()
+        .header(ETAG, &etag)
+        .header(CACHE_CONTROL, CACHE_CONTROL_VALUE);
+
+    let response = if not_modified {
+        response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(boxed(axum::body::Empty::new()))
+    } else {
+        let picture = storage.get_bytes(&key).await.map_err(|error| {
+            error!("{error}");
+            match error {
+                StorageError::NotFound | StorageError::InvalidKey => {
+                    "No picture".into_error_with_statuscode(StatusCode::NOT_FOUND)
+                }
+                _ => "Cannot get picture".into_server_error(),
+            }
+        })?;
+
+        response
+            .header(CONTENT_TYPE, mime::IMAGE_PNG.as_ref())
+            .body(boxed(Full::from(picture)))
+    };
+
+    response.map_err(|error| {
+        error!("{error}");
+        "Cannot build response".into_server_error()
+    })
+}
@@ -19,11 +19,15 @@ use axum::body::Body;
 use axum::http::StatusCode;
 use axum::Router;
 use client::client_router;
-use fydia_config::{Config, DatabaseConfig, InstanceConfig};
+use fydia_config::{
+    Config, DatabaseConfig, InstanceConfig, PinsConfig, StorageConfig, TypingConfig,
+    WebsocketConfig,
+};
 use fydia_crypto::key::{private_to_public, Private, Rsa};
 use fydia_sql::connection::get_connection;
 use fydia_sql::setup::create_tables;
 use fydia_sql::sqlpool::DbConnection;
+use fydia_storage::StorageBackend;
 use fydia_struct::instance::{Instance, RsaData};
 use fydia_utils::http::{self, Response};
 use handlers::api::manager::typing::TypingManagerChannel;
@@ -67,6 +71,10 @@ pub async fn get_axum_router_from_config(config: Config) -> Result<axum::Router<
     get_axum_router(
         get_database_connection(&config.database).await?,
         &config.instance,
+        &config.websocket,
+        &config.typing,
+        &config.storage,
+        &config.pins,
         &config.format_ip(),
         config.server.port,
     )
@@ -95,9 +103,14 @@ pub fn generate_key() -> Result<Rsa<Private>, String> {
 ///
 /// # Panics
 /// Panic if private to public key failed
+#[allow(clippy::too_many_arguments)]
 pub async fn get_axum_router(
     database: DbConnection,
     instance: &InstanceConfig,
+    websocket: &WebsocketConfig,
+    typing: &TypingConfig,
+    storage: &StorageConfig,
+    pins: &PinsConfig,
     formated_ip: &str,
     port: u16,
 ) -> Result<axum::Router<()>, String> {
@@ -121,9 +134,9 @@ pub async fn get_axum_router(
         error!("{}", error);
         return Err(String::from("Cannot set websocket manager"));
     };
-    if let Err(error) = typing_manager.set_selfmanager(&typing_manager) {
+    if let Err(error) = typing_manager.set_expiry(Duration::from_secs(typing.expiry)) {
         error!("{}", error);
-        return Err(String::from("Cannot set typing manager"));
+        return Err(String::from("Cannot set typing expiry"));
     }
 
     if let Err(error) = typing_manager.set_database(&database) {
@@ -131,6 +144,11 @@ pub async fn get_axum_router(
         return Err(String::from("Cannot set database"));
     }
 
+    crate::handlers::api::manager::threads::spawn_archiver(
+        database.clone(),
+        websocket_manager.clone(),
+    );
+
     Ok(get_router(
         database,
         Arc::new(Instance::new(
@@ -141,15 +159,22 @@ pub async fn get_axum_router(
         Arc::new(RsaData(private_key, public_key)),
         websocket_manager,
         typing_manager,
+        Arc::new(websocket.clone()),
+        fydia_storage::from_config(storage),
+        Arc::new(pins.clone()),
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn get_router(
     database: DbConnection,
     instance: Arc<Instance>,
     rsadata: Arc<RsaData>,
     websocket_manager: Arc<WebsocketManagerChannel>,
     typing_manager: Arc<TypingManagerChannel>,
+    websocket_config: Arc<WebsocketConfig>,
+    storage: Arc<dyn StorageBackend>,
+    pins_config: Arc<PinsConfig>,
 ) -> Router<()> {
     let state = ServerState {
         database,
@@ -157,6 +182,9 @@ pub fn get_router(
         rsa: rsadata,
         wbsocket: websocket_manager,
         typing: typing_manager,
+        websocket_config,
+        storage,
+        pins_config,
     };
 
     axum::Router::<ServerState>::new()
@@ -184,6 +212,9 @@ pub struct ServerState {
     pub rsa: Arc<RsaData>,
     pub wbsocket: Arc<WebsocketManagerChannel>,
     pub typing: Arc<TypingManagerChannel>,
+    pub websocket_config: Arc<WebsocketConfig>,
+    pub storage: Arc<dyn StorageBackend>,
+    pub pins_config: Arc<PinsConfig>,
 }
 
 #[derive(Clone)]
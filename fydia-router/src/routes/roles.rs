@@ -1,16 +1,58 @@
 use axum::Router;
 
-use crate::{handlers::default, ServerState};
+use crate::{
+    handlers::{
+        api::server::roles::{
+            create::create_role,
+            delete::delete_role,
+            get_role, get_roles,
+            members::{assign_role, get_members_of_role, unassign_role},
+            reorder::reorder_roles,
+            update::{
+                get_color, get_name, get_permission, update_color, update_name, update_permission,
+            },
+        },
+        default,
+    },
+    ServerState,
+};
 
 /// All routes related to the roles
+///
+/// ```ignore
+/// Roles:
+///     /api/server/:serverid/roles/
+///         - GET / -> Give all roles of server
+///         - POST /create -> Create a new role
+///         - POST /positions -> Change the order of roles
+///         - GET /:roleid -> Give info of role
+///         - DELETE /:roleid -> Delete role
+///         - GET, POST /:roleid/color -> Get or update color of role
+///         - GET, POST /:roleid/name -> Get or update name of role
+///         - GET, POST /:roleid/permission -> Get or update server permissions of role
+///         - GET /:roleid/members -> Give all members with this role
+///         - POST, DELETE /:roleid/members/:userid -> Assign or unassign role
+/// ```
 pub fn roles_routes() -> Router<ServerState> {
     axum::Router::new()
-        .route("/create", axum::routing::get(default))
+        .route("/", axum::routing::get(get_roles))
+        .route("/create", axum::routing::post(create_role))
+        .route("/positions", axum::routing::post(reorder_roles))
         .nest(
-            "/:id",
+            "/:roleid",
             axum::Router::new()
-                .route("/color", axum::routing::get(default).post(default))
-                .route("/name", axum::routing::get(default).post(default))
-                .route("/description", axum::routing::get(default)),
+                .route("/", axum::routing::get(get_role).delete(delete_role))
+                .route("/color", axum::routing::get(get_color).post(update_color))
+                .route("/name", axum::routing::get(get_name).post(update_name))
+                .route(
+                    "/permission",
+                    axum::routing::get(get_permission).post(update_permission),
+                )
+                .route("/description", axum::routing::get(default))
+                .route("/members", axum::routing::get(get_members_of_role))
+                .route(
+                    "/members/:userid",
+                    axum::routing::post(assign_role).delete(unassign_role),
+                ),
         )
 }
@@ -2,6 +2,9 @@ use axum::Router;
 
 use crate::{
     handlers::{
+        api::server::channels::messages::messageid::reactions::{
+            add_reaction, get_reactions, remove_reaction,
+        },
         api::server::{
             channels::{
                 create::create_channel,
@@ -9,25 +12,42 @@ use crate::{
                 info_channel,
                 messages::{
                     get::get_messages,
-                    messageid::{delete::delete_message, get::get_message, post::update_message},
+                    messageid::{
+                        attachment::get_attachment, delete::delete_message, get::get_message,
+                        post::update_message, revisions::get_revisions,
+                    },
                     post::post_messages,
                 },
                 permission::{
-                    get_permission,
-                    role::get_permission_of_role,
+                    get_effective_permission, get_permission,
+                    role::{get_permission_of_role, post_permission_of_role},
                     user::{get_permission_of_user, post_permission_of_user},
                 },
+                pins::{get_pins, pin_message, unpin_message},
+                threads::{create_thread, get_threads, update_thread},
                 typing::{start_typing, stop_typing},
                 update::{update_description, update_name},
                 vocal::join_channel,
             },
             create::create_server,
+            delete::delete_server,
+            emoji::{create_emoji, delete_emoji, get_emoji, get_emojis, update_emoji_name},
             get_server,
             info::get_server_of_user,
+            invites::{create_invite, get_invites, revoke_invite},
             join::join,
+            members::{
+                bans::{ban_member, get_bans, unban_member},
+                get_members,
+                profile::{
+                    delete_member_avatar, get_member, get_member_avatar, post_member_avatar,
+                    update_nickname,
+                },
+                remove_member,
+            },
             picture::{get_picture_of_server, post_picture_of_server},
+            update::{transfer_ownership, update_server_name},
         },
-        default,
     },
     ServerState,
 };
@@ -39,17 +59,52 @@ pub fn server_routes() -> Router<ServerState> {
     axum::Router::new()
         .route("/", axum::routing::get(get_server_of_user))
         .route("/create", axum::routing::post(create_server))
-        .route("/join/:serverid", axum::routing::get(join))
+        .route("/join/:code", axum::routing::get(join))
         .nest(
             "/:serverid",
             axum::Router::<ServerState>::new()
-                .route("/", axum::routing::get(get_server))
+                .route("/", axum::routing::get(get_server).delete(delete_server))
+                .route("/name", axum::routing::put(update_server_name))
+                .route("/owner", axum::routing::put(transfer_ownership))
                 .route(
                     "/picture",
                     axum::routing::get(get_picture_of_server).post(post_picture_of_server),
                 )
                 .nest("/channel", channelid())
-                .nest("/roles", roles_routes()),
+                .nest("/roles", roles_routes())
+                .nest(
+                    "/emoji",
+                    Router::new()
+                        .route("/", axum::routing::get(get_emojis))
+                        .route("/create", axum::routing::post(create_emoji))
+                        .route(
+                            "/:emojiid",
+                            axum::routing::get(get_emoji).delete(delete_emoji),
+                        )
+                        .route("/:emojiid/name", axum::routing::put(update_emoji_name)),
+                )
+                .route("/bans", axum::routing::get(get_bans))
+                .route("/members", axum::routing::get(get_members))
+                .nest(
+                    "/members/:userid",
+                    Router::new()
+                        .route("/", axum::routing::get(get_member).delete(remove_member))
+                        .route("/nickname", axum::routing::put(update_nickname))
+                        .route(
+                            "/avatar",
+                            axum::routing::get(get_member_avatar)
+                                .post(post_member_avatar)
+                                .delete(delete_member_avatar),
+                        )
+                        .route("/ban", axum::routing::post(ban_member).delete(unban_member)),
+                )
+                .nest(
+                    "/invites",
+                    Router::new()
+                        .route("/", axum::routing::get(get_invites))
+                        .route("/create", axum::routing::post(create_invite))
+                        .route("/:code", axum::routing::delete(revoke_invite)),
+                ),
         )
 }
 
@@ -63,6 +118,12 @@ pub fn server_routes() -> Router<ServerState> {
 ///             - /description -> Update description of channel
 ///         - GET /messages -> Give message of channel
 ///         - POST /messages -> Post a message into channel
+///         - GET /pins -> Give pinned messages of channel
+///         - GET /threads -> Give threads of channel
+///         - PUT /thread -> Archive or update a thread
+///         - GET, POST /permission/role/:roleid -> Give or set overwrite of a role
+///         - GET, POST /permission/user/:userid -> Give or set overwrite of a member
+///         - GET /permission/effective/:userid -> Give computed permissions of a member
 /// ```
 pub fn channelid() -> Router<ServerState> {
     axum::Router::new()
@@ -75,14 +136,25 @@ pub fn channelid() -> Router<ServerState> {
                 .route("/description", axum::routing::put(update_description))
                 .route("/join", axum::routing::get(join_channel))
                 .route("/permissions", axum::routing::get(get_permission))
+                .route("/pins", axum::routing::get(get_pins))
+                .route("/threads", axum::routing::get(get_threads))
+                .route("/thread", axum::routing::put(update_thread))
                 .nest(
                     "/permission",
                     Router::new()
-                        .route("/role/:roleid", axum::routing::get(get_permission_of_role))
+                        .route(
+                            "/role/:roleid",
+                            axum::routing::get(get_permission_of_role)
+                                .post(post_permission_of_role),
+                        )
                         .route(
                             "/user/:userid",
                             axum::routing::get(get_permission_of_user)
                                 .post(post_permission_of_user),
+                        )
+                        .route(
+                            "/effective/:userid",
+                            axum::routing::get(get_effective_permission),
                         ),
                 )
                 .nest(
@@ -106,6 +178,11 @@ pub fn channelid() -> Router<ServerState> {
 ///         - GET /
 ///         - POST /
 ///         - DELETE /
+///         - GET /attachment
+///         - GET, POST, DELETE /reactions
+///         - GET /revisions -> Previous contents of the message
+///         - POST /thread -> Start a thread from the message
+///         - POST, DELETE /pin -> Pin or unpin the message
 /// ```
 pub fn messageid() -> Router<ServerState> {
     axum::Router::<ServerState>::new()
@@ -115,5 +192,17 @@ pub fn messageid() -> Router<ServerState> {
                 .post(update_message)
                 .delete(delete_message),
         )
-        .route("/reactions", axum::routing::post(default).delete(default))
+        .route("/attachment", axum::routing::get(get_attachment))
+        .route(
+            "/reactions",
+            axum::routing::get(get_reactions)
+                .post(add_reaction)
+                .delete(remove_reaction),
+        )
+        .route("/revisions", axum::routing::get(get_revisions))
+        .route("/thread", axum::routing::post(create_thread))
+        .route(
+            "/pin",
+            axum::routing::post(pin_message).delete(unpin_message),
+        )
 }
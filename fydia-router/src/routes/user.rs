@@ -1,9 +1,11 @@
-use crate::handlers::api::manager::websockets::messages::ws_handler;
+use crate::handlers::api::manager::websockets::messages::{get_websocket_sessions, ws_handler};
+use crate::handlers::api::user::avatar::{get_avatar, post_avatar};
 use crate::handlers::api::user::create::create_user;
 use crate::handlers::api::user::direct_message;
 use crate::handlers::api::user::direct_message::get::get_direct_messages;
 use crate::handlers::api::user::direct_message::message::get::get_message_dm;
 use crate::handlers::api::user::direct_message::message::post::post_message_dm;
+use crate::handlers::api::user::direct_message::typing::{start_typing_dm, stop_typing_dm};
 use crate::handlers::api::user::login::user_login;
 use crate::handlers::api::user::selfinfo::get_info_of_self;
 use crate::handlers::api::user::token::verify;
@@ -19,9 +21,15 @@ pub fn user_routes() -> Router<ServerState> {
         .route("/delete", axum::routing::delete(default))
         .route("/logout", axum::routing::get(default))
         .route("/websocket", axum::routing::get(ws_handler))
+        .route(
+            "/websocket/sessions",
+            axum::routing::get(get_websocket_sessions),
+        )
         .route("/login", axum::routing::post(user_login))
         .route("/token/verify", axum::routing::get(verify))
         .route("/me", axum::routing::get(get_info_of_self))
+        .route("/avatar", axum::routing::post(post_avatar))
+        .route("/:userid/avatar", axum::routing::get(get_avatar))
         .nest("/direct_message", direct_message())
 }
 
@@ -38,8 +46,16 @@ pub fn direct_message() -> Router<ServerState> {
         .nest(
             "/:dmid",
             Router::new()
-                .route("/message", axum::routing::get(get_message_dm).post(default))
-                .route("/message/:message_id", axum::routing::get(post_message_dm))
-                .route("/users", axum::routing::get(default)),
+                .route(
+                    "/message",
+                    axum::routing::get(get_message_dm).post(post_message_dm),
+                )
+                .route("/users", axum::routing::get(default))
+                .nest(
+                    "/typing",
+                    Router::new()
+                        .route("/start", axum::routing::post(start_typing_dm))
+                        .route("/stop", axum::routing::post(stop_typing_dm)),
+                ),
         )
 }
@@ -0,0 +1,83 @@
+use fydia_struct::{
+    ban::{Ban, BanError},
+    messages::Date,
+    server::ServerId,
+    user::UserId,
+};
+use sea_orm::{entity::prelude::*, Set};
+use shared::sea_orm;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "bans")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub server_id: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: u32,
+    pub moderator: u32,
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+impl Model {
+    pub fn to_ban(&self) -> Ban {
+        Ban {
+            server_id: ServerId::new(self.server_id.clone()),
+            user_id: UserId::new(self.user_id),
+            moderator: UserId::new(self.moderator),
+            reason: self.reason.clone(),
+            expires_at: self.expires_at.map(Date::parse_from_naivetime),
+            created_at: Date::parse_from_naivetime(self.created_at),
+        }
+    }
+}
+
+impl TryFrom<Ban> for ActiveModel {
+    type Error = BanError;
+
+    fn try_from(value: Ban) -> Result<Self, Self::Error> {
+        Ok(Self {
+            server_id: Set(value.server_id.id),
+            user_id: Set(value.user_id.0.get_id()?),
+            moderator: Set(value.moderator.0.get_id()?),
+            reason: Set(value.reason),
+            expires_at: Set(value.expires_at.map(|date| date.0.naive_utc())),
+            created_at: Set(value.created_at.0.naive_utc()),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::server::Entity",
+        from = "Column::ServerId",
+        to = "super::server::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    Server,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    User,
+}
+
+impl Related<super::server::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Server.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
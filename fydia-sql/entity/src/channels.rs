@@ -18,18 +18,30 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
     pub channel_type: u32,
+    pub parent_channel: Option<String>,
+    pub thread_message: Option<String>,
+    pub archived: bool,
+    pub auto_archive: Option<u32>,
+    pub last_activity: Option<DateTime>,
 }
 
 impl TryFrom<Channel> for ActiveModel {
     type Error = ChannelError;
 
     fn try_from(channel: Channel) -> Result<Self, Self::Error> {
+        let thread = channel.thread.as_ref();
+
         Ok(Self {
             id: Set(channel.id.id.clone()),
             server_id: Set(channel.parent_id.id),
             name: Set(channel.name.clone()),
             description: Set(Some(channel.description.clone())),
             channel_type: Set(channel.channel_type as u32),
+            parent_channel: Set(thread.map(|thread| thread.parent_channel.id.clone())),
+            thread_message: Set(thread.map(|thread| thread.message_id.clone())),
+            archived: Set(thread.is_some_and(|thread| thread.archived)),
+            auto_archive: Set(thread.map(|thread| thread.auto_archive)),
+            last_activity: Set(thread.map(|thread| thread.last_activity.0.naive_utc())),
         })
     }
 }
@@ -0,0 +1,70 @@
+use fydia_struct::messages::{Message, MessageError};
+use sea_orm::{entity::prelude::*, Set};
+use shared::sea_orm;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "direct_message_messages")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub content: Option<String>,
+    pub message_type: String,
+    pub edited: i8,
+    pub timestamp: DateTime,
+    pub directmessage: u32,
+    pub author_id: u32,
+}
+
+impl ActiveModel {
+    /// Return an activemodel of a message sent in a direct message
+    ///
+    /// # Errors
+    /// Return an error if :
+    /// * Id of author is unset
+    pub fn new(message: Message, directmessage: u32) -> Result<Self, MessageError> {
+        Ok(Self {
+            id: Set(message.id),
+            content: Set(Some(message.content)),
+            message_type: Set(message.message_type.to_string()),
+            edited: Set(i8::from(message.edited)),
+            timestamp: Set(message.timestamp.0.naive_utc()),
+            directmessage: Set(directmessage),
+            author_id: Set(message.author_id.id.0.get_id()?),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::direct_message::Entity",
+        from = "Column::Directmessage",
+        to = "super::direct_message::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    DirectMessage,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::AuthorId",
+        to = "super::user::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    User,
+}
+
+impl Related<super::direct_message::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DirectMessage.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
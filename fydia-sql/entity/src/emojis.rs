@@ -0,0 +1,65 @@
+use fydia_struct::{
+    emoji::{Emoji, EmojiError},
+    server::ServerId,
+    user::UserId,
+    utils::Id,
+};
+use sea_orm::{entity::prelude::*, ActiveValue::NotSet, Set};
+use shared::sea_orm;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "emojis")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: u32,
+    pub server_id: String,
+    pub name: String,
+    pub path: String,
+    pub creator: u32,
+}
+
+impl Model {
+    pub fn to_emoji(&self) -> Emoji {
+        Emoji {
+            id: Id::Id(self.id),
+            server_id: ServerId::new(self.server_id.clone()),
+            name: self.name.clone(),
+            path: self.path.clone(),
+            creator: UserId::new(self.creator),
+        }
+    }
+}
+
+impl TryFrom<Emoji> for ActiveModel {
+    type Error = EmojiError;
+
+    fn try_from(value: Emoji) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: NotSet,
+            server_id: Set(value.server_id.id),
+            name: Set(value.name),
+            path: Set(value.path),
+            creator: Set(value.creator.0.get_id()?),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::server::Entity",
+        from = "Column::ServerId",
+        to = "super::server::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    Server,
+}
+
+impl Related<super::server::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Server.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,107 @@
+use fydia_struct::{
+    invite::{Invite, InviteError},
+    messages::Date,
+    server::ServerId,
+    user::UserId,
+};
+use sea_orm::{entity::prelude::*, Set};
+use shared::sea_orm;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "invites")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub code: String,
+    pub server_id: String,
+    pub creator: u32,
+    pub uses: u32,
+    pub max_uses: Option<u32>,
+    pub expires_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+impl Model {
+    pub fn to_invite(&self) -> Invite {
+        Invite {
+            code: self.code.clone(),
+            server_id: ServerId::new(self.server_id.clone()),
+            creator: UserId::new(self.creator),
+            uses: self.uses,
+            max_uses: self.max_uses,
+            expires_at: self.expires_at.map(Date::parse_from_naivetime),
+            created_at: Date::parse_from_naivetime(self.created_at),
+        }
+    }
+}
+
+impl TryFrom<Invite> for ActiveModel {
+    type Error = InviteError;
+
+    fn try_from(value: Invite) -> Result<Self, Self::Error> {
+        Ok(Self {
+            code: Set(value.code),
+            server_id: Set(value.server_id.id),
+            creator: Set(value.creator.0.get_id()?),
+            uses: Set(value.uses),
+            max_uses: Set(value.max_uses),
+            expires_at: Set(value.expires_at.map(|date| date.0.naive_utc())),
+            created_at: Set(value.created_at.0.naive_utc()),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::server::Entity",
+        from = "Column::ServerId",
+        to = "super::server::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    Server,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::Creator",
+        to = "super::user::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    User,
+}
+
+impl Related<super::server::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Server.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub mod uses {
+    use sea_orm::entity::prelude::*;
+    use shared::sea_orm;
+
+    /// Record of the invite used by a user to join a server
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "invites_uses")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: u32,
+        pub code: String,
+        pub server_id: String,
+        pub user_id: u32,
+        pub used_at: DateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
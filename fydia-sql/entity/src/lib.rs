@@ -3,12 +3,20 @@
 
 pub mod prelude;
 
+pub mod bans;
 pub mod channels;
 pub mod direct_message;
 pub mod direct_message_members;
+pub mod direct_message_messages;
+pub mod emojis;
+pub mod invites;
 pub mod members;
+pub mod message_revisions;
 pub mod messages;
 pub mod permission;
+pub mod pins;
+pub mod reactions;
+pub mod read_states;
 pub mod roles;
 pub mod server;
 pub mod user;
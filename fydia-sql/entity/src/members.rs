@@ -1,6 +1,8 @@
 //! `SeoORM` Entity. Generated by sea-orm-codegen 0.6.0
 
 use fydia_struct::{
+    member::MemberProfile,
+    messages::Date,
     server::{MembersError, ServerId},
     user::UserId,
     utils::{Id, IdError},
@@ -14,6 +16,9 @@ pub struct Model {
     pub serverid: String,
     #[sea_orm(primary_key, auto_increment = false)]
     pub userid: u32,
+    pub joined_at: DateTime,
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
 }
 
 impl Model {
@@ -59,6 +64,14 @@ impl Model {
         ServerId::new(self.serverid.clone())
     }
 
+    pub fn to_profile(&self) -> MemberProfile {
+        MemberProfile {
+            nickname: self.nickname.clone(),
+            avatar: self.avatar.clone(),
+            joined_at: Date::parse_from_naivetime(self.joined_at),
+        }
+    }
+
     /// Return an activemodel from `userid`
     ///
     /// # Errors
@@ -68,6 +81,9 @@ impl Model {
         Ok(ActiveModel {
             serverid: Set(serverid.id),
             userid: Set(userid.0.get_id_cloned()?),
+            joined_at: Set(Date::now().0.naive_utc()),
+            nickname: Set(None),
+            avatar: Set(None),
         })
     }
 }
@@ -0,0 +1,33 @@
+use sea_orm::entity::prelude::*;
+use shared::sea_orm;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "message_revisions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: u32,
+    pub message_id: String,
+    #[sea_orm(column_type = "Text")]
+    pub content: String,
+    pub edited_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::messages::Entity",
+        from = "Column::MessageId",
+        to = "super::messages::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    Message,
+}
+
+impl Related<super::messages::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Message.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -15,9 +15,11 @@ pub struct Model {
     pub content: Option<String>,
     pub message_type: String,
     pub edited: i8,
+    pub edited_at: Option<DateTime>,
     pub timestamp: DateTime,
     pub channel_id: String,
     pub author_id: u32,
+    pub reply_to: Option<String>,
 }
 
 impl TryFrom<Message> for ActiveModel {
@@ -30,8 +32,10 @@ impl TryFrom<Message> for ActiveModel {
             message_type: Set(value.message_type.to_string()),
             timestamp: Set(value.timestamp.0.naive_utc()),
             edited: Set(i8::from(value.edited)),
+            edited_at: Set(value.edited_at.map(|date| date.0.naive_utc())),
             channel_id: Set(value.channel_id.id.clone()),
             author_id: Set(value.author_id.id.0.get_id()?),
+            reply_to: Set(value.reply_to),
         })
     }
 }
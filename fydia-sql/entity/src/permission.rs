@@ -1,6 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.6.0
 pub mod role {
-    use fydia_struct::permission::{Permission, PermissionError, PermissionType};
+    use fydia_struct::permission::{Permission, PermissionError, PermissionType, PermissionValue};
     use sea_orm::{entity::prelude::*, Set};
     use shared::sea_orm;
     #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -11,7 +11,9 @@ pub mod role {
         #[sea_orm(primary_key, auto_increment = false)]
         pub role: u32,
         #[sea_orm(auto_increment = false)]
-        pub value: u64,
+        pub value: i64,
+        #[sea_orm(auto_increment = false)]
+        pub deny: i64,
     }
 
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -61,7 +63,8 @@ pub mod role {
                     .ok_or_else(|| PermissionError::NoChannelId)?
                     .id),
                 role: Set(role.get_id()?),
-                value: Set(perm.value),
+                value: Set(PermissionValue::to_database(perm.value)),
+                deny: Set(PermissionValue::to_database(perm.deny)),
             })
         }
     }
@@ -75,7 +78,7 @@ pub mod role {
 }
 
 pub mod user {
-    use fydia_struct::permission::{Permission, PermissionError, PermissionType};
+    use fydia_struct::permission::{Permission, PermissionError, PermissionType, PermissionValue};
     use sea_orm::{entity::prelude::*, Set};
     use shared::sea_orm;
     #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -86,7 +89,9 @@ pub mod user {
         #[sea_orm(primary_key, auto_increment = false)]
         pub channel: String,
         #[sea_orm(auto_increment = false)]
-        pub value: u64,
+        pub value: i64,
+        #[sea_orm(auto_increment = false)]
+        pub deny: i64,
     }
 
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -136,7 +141,8 @@ pub mod user {
                     .ok_or_else(|| PermissionError::NoChannelId)?
                     .id),
                 user: Set(user.0.get_id()?),
-                value: Set(perm.value),
+                value: Set(PermissionValue::to_database(perm.value)),
+                deny: Set(PermissionValue::to_database(perm.deny)),
             })
         }
     }
@@ -3,8 +3,14 @@
 pub use super::channels::Entity as Channels;
 pub use super::direct_message::Entity as DirectMessage;
 pub use super::direct_message_members::Entity as DirectMessageMembers;
+pub use super::direct_message_messages::Entity as DirectMessageMessages;
+pub use super::emojis::Entity as Emojis;
+pub use super::invites::Entity as Invites;
 pub use super::members::Entity as Members;
+pub use super::message_revisions::Entity as MessageRevisions;
 pub use super::messages::Entity as Messages;
+pub use super::reactions::Entity as Reactions;
+pub use super::read_states::Entity as ReadStates;
 pub use super::roles::Entity as Roles;
 pub use super::server::Entity as Server;
 pub use super::user::Entity as User;
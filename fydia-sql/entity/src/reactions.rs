@@ -0,0 +1,79 @@
+use fydia_struct::{
+    reaction::{ReactionEmoji, ReactionError},
+    utils::Id,
+};
+use sea_orm::entity::prelude::*;
+use shared::sea_orm;
+
+/// Prefix of custom emojis in the `emoji` column, unicode emojis are never only ascii
+const CUSTOM_PREFIX: char = ':';
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "reactions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub message_id: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: u32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub emoji: String,
+    pub created_at: DateTime,
+}
+
+impl Model {
+    pub fn to_emoji(&self) -> ReactionEmoji {
+        match self
+            .emoji
+            .strip_prefix(CUSTOM_PREFIX)
+            .and_then(|id| id.parse().ok())
+        {
+            Some(id) => ReactionEmoji::Custom(Id::Id(id)),
+            None => ReactionEmoji::Unicode(self.emoji.clone()),
+        }
+    }
+}
+
+/// Value of `emoji` column for an emoji, custom emojis are stored as `:ID`
+///
+/// # Errors
+/// Return an error if id of a custom emoji is unset
+pub fn emoji_key(emoji: &ReactionEmoji) -> Result<String, ReactionError> {
+    Ok(match emoji {
+        ReactionEmoji::Unicode(emoji) => emoji.clone(),
+        ReactionEmoji::Custom(id) => format!("{CUSTOM_PREFIX}{}", id.get_id_cloned()?),
+    })
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::messages::Entity",
+        from = "Column::MessageId",
+        to = "super::messages::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    Message,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    User,
+}
+
+impl Related<super::messages::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Message.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
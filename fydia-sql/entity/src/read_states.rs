@@ -0,0 +1,78 @@
+use fydia_struct::{
+    channel::ChannelId,
+    messages::{Date, MessageError, ReadState},
+    user::UserId,
+};
+use sea_orm::{entity::prelude::*, Set};
+use shared::sea_orm;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "read_states")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: u32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub channel_id: String,
+    pub message_id: String,
+    pub updated_at: DateTime,
+}
+
+impl Model {
+    pub fn to_read_state(&self) -> ReadState {
+        ReadState {
+            user_id: UserId::new(self.user_id),
+            channel_id: ChannelId {
+                id: self.channel_id.clone(),
+            },
+            message_id: self.message_id.clone(),
+            timestamp: Date::parse_from_naivetime(self.updated_at),
+        }
+    }
+}
+
+impl TryFrom<&ReadState> for ActiveModel {
+    type Error = MessageError;
+
+    fn try_from(value: &ReadState) -> Result<Self, Self::Error> {
+        Ok(Self {
+            user_id: Set(value.user_id.0.get_id_cloned()?),
+            channel_id: Set(value.channel_id.id.clone()),
+            message_id: Set(value.message_id.clone()),
+            updated_at: Set(value.timestamp.0.naive_utc()),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::channels::Entity",
+        from = "Column::ChannelId",
+        to = "super::channels::Column::Id",
+        on_update = "Restrict",
+        on_delete = "Restrict"
+    )]
+    Channels,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::channels::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Channels.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,6 +1,6 @@
 //! `SeoORM` Entity. Generated by sea-orm-codegen 0.6.0
 
-use fydia_struct::{roles::Role, server::ServerId, utils::Id};
+use fydia_struct::{permission::PermissionValue, roles::Role, server::ServerId, utils::Id};
 use sea_orm::{entity::prelude::*, ActiveValue::NotSet, Set};
 use shared::sea_orm;
 
@@ -12,7 +12,8 @@ pub struct Model {
     pub serverid: String,
     pub name: String,
     pub color: String,
-    pub server_permission: u64,
+    pub server_permission: i64,
+    pub position: u32,
 }
 
 impl Model {
@@ -22,7 +23,8 @@ impl Model {
             server_id: ServerId::new(self.serverid.clone()),
             name: self.name.clone(),
             color: self.color.clone(),
-            server_permission: self.server_permission,
+            server_permission: PermissionValue::from_database(self.server_permission),
+            position: self.position,
         }
     }
 }
@@ -54,7 +56,8 @@ impl From<Role> for ActiveModel {
             serverid: Set(role.server_id.id),
             name: Set(role.name),
             color: Set(role.color),
-            server_permission: Set(role.server_permission),
+            server_permission: Set(PermissionValue::to_database(role.server_permission)),
+            position: Set(role.position),
         }
     }
 }
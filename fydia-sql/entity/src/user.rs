@@ -24,6 +24,8 @@ pub struct Model {
     pub password: String,
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub avatar: Option<String>,
 }
 
 impl TryFrom<User> for ActiveModel {
@@ -42,6 +44,7 @@ impl TryFrom<User> for ActiveModel {
             password: Set(password),
             instance: Set(0),
             description: Set(value.description),
+            avatar: Set(value.avatar),
             ..Default::default()
         })
     }
@@ -4,13 +4,49 @@ pub use sea_orm_migration::prelude::*;
 use sea_orm_migration::sea_orm::DbConn;
 
 mod m20220101_000001_create_table;
+mod m20230101_000002_messages_channel_index;
+mod m20230101_000003_create_invites;
+mod m20230101_000004_create_direct_message_messages;
+mod m20230101_000005_create_read_states;
+mod m20230101_000006_add_user_avatar;
+mod m20230101_000007_permission_flags;
+mod m20230101_000008_permission_deny;
+mod m20230101_000009_role_position;
+mod m20230101_000010_create_bans;
+mod m20230101_000011_member_joined_at;
+mod m20230101_000012_member_profile;
+mod m20230101_000013_create_emojis;
+mod m20230101_000014_create_reactions;
+mod m20230101_000015_message_reply_to;
+mod m20230101_000016_channel_threads;
+mod m20230101_000017_create_pins;
+mod m20230101_000018_message_revisions;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20230101_000002_messages_channel_index::Migration),
+            Box::new(m20230101_000003_create_invites::Migration),
+            Box::new(m20230101_000004_create_direct_message_messages::Migration),
+            Box::new(m20230101_000005_create_read_states::Migration),
+            Box::new(m20230101_000006_add_user_avatar::Migration),
+            Box::new(m20230101_000007_permission_flags::Migration),
+            Box::new(m20230101_000008_permission_deny::Migration),
+            Box::new(m20230101_000009_role_position::Migration),
+            Box::new(m20230101_000010_create_bans::Migration),
+            Box::new(m20230101_000011_member_joined_at::Migration),
+            Box::new(m20230101_000012_member_profile::Migration),
+            Box::new(m20230101_000013_create_emojis::Migration),
+            Box::new(m20230101_000014_create_reactions::Migration),
+            Box::new(m20230101_000015_message_reply_to::Migration),
+            Box::new(m20230101_000016_channel_threads::Migration),
+            Box::new(m20230101_000017_create_pins::Migration),
+            Box::new(m20230101_000018_message_revisions::Migration),
+        ]
     }
 }
 
@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+const INDEX_NAME: &str = "idx_messages_channel_timestamp";
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000002_messages_channel_index"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name(INDEX_NAME)
+                    .table(entity::messages::Entity)
+                    .col(entity::messages::Column::ChannelId)
+                    .col(entity::messages::Column::Timestamp)
+                    .col(entity::messages::Column::Id)
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(INDEX_NAME)
+                    .table(entity::messages::Entity)
+                    .clone(),
+            )
+            .await
+    }
+}
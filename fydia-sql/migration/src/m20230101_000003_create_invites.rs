@@ -0,0 +1,130 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000003_create_invites"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::invites::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(entity::invites::Column::Code)
+                            .string_len(10)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::invites::Column::ServerId)
+                            .string_len(30)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::invites::Column::Creator)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::invites::Column::Uses)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::invites::Column::MaxUses)
+                            .integer()
+                            .unsigned(),
+                    )
+                    .col(ColumnDef::new(entity::invites::Column::ExpiresAt).date_time())
+                    .col(
+                        ColumnDef::new(entity::invites::Column::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::server::Entity, entity::server::Column::Id)
+                            .from(entity::invites::Entity, entity::invites::Column::ServerId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::user::Entity, entity::user::Column::Id)
+                            .from(entity::invites::Entity, entity::invites::Column::Creator),
+                    )
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::invites::uses::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(entity::invites::uses::Column::Id)
+                            .integer()
+                            .unsigned()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::invites::uses::Column::Code)
+                            .string_len(10)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::invites::uses::Column::ServerId)
+                            .string_len(30)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::invites::uses::Column::UserId)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::invites::uses::Column::UsedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::server::Entity, entity::server::Column::Id)
+                            .from(
+                                entity::invites::uses::Entity,
+                                entity::invites::uses::Column::ServerId,
+                            ),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::user::Entity, entity::user::Column::Id)
+                            .from(
+                                entity::invites::uses::Entity,
+                                entity::invites::uses::Column::UserId,
+                            ),
+                    )
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(entity::invites::uses::Entity).clone())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(entity::invites::Entity).clone())
+            .await
+    }
+}
@@ -0,0 +1,105 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+const INDEX_NAME: &str = "idx_direct_message_messages_timestamp";
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000004_create_direct_message_messages"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::direct_message_messages::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(entity::direct_message_messages::Column::Id)
+                            .string_len(32)
+                            .primary_key()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::direct_message_messages::Column::Content)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::direct_message_messages::Column::MessageType)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::direct_message_messages::Column::Edited)
+                            .small_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::direct_message_messages::Column::Timestamp)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::direct_message_messages::Column::Directmessage)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::direct_message_messages::Column::AuthorId)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(
+                                entity::direct_message::Entity,
+                                entity::direct_message::Column::Id,
+                            )
+                            .from(
+                                entity::direct_message_messages::Entity,
+                                entity::direct_message_messages::Column::Directmessage,
+                            ),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::user::Entity, entity::user::Column::Id)
+                            .from(
+                                entity::direct_message_messages::Entity,
+                                entity::direct_message_messages::Column::AuthorId,
+                            ),
+                    )
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name(INDEX_NAME)
+                    .table(entity::direct_message_messages::Entity)
+                    .col(entity::direct_message_messages::Column::Directmessage)
+                    .col(entity::direct_message_messages::Column::Timestamp)
+                    .col(entity::direct_message_messages::Column::Id)
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(entity::direct_message_messages::Entity)
+                    .clone(),
+            )
+            .await
+    }
+}
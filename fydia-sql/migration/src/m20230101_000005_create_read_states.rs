@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000005_create_read_states"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::read_states::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(entity::read_states::Column::UserId)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::read_states::Column::ChannelId)
+                            .string_len(15)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::read_states::Column::MessageId)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::read_states::Column::UpdatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(entity::read_states::Column::UserId)
+                            .col(entity::read_states::Column::ChannelId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::user::Entity, entity::user::Column::Id)
+                            .from(
+                                entity::read_states::Entity,
+                                entity::read_states::Column::UserId,
+                            ),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::channels::Entity, entity::channels::Column::Id)
+                            .from(
+                                entity::read_states::Entity,
+                                entity::read_states::Column::ChannelId,
+                            ),
+                    )
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(entity::read_states::Entity).clone())
+            .await
+    }
+}
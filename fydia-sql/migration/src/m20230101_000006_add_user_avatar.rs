@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000006_add_user_avatar"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::user::Entity)
+                    .add_column(ColumnDef::new(entity::user::Column::Avatar).text())
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::user::Entity)
+                    .drop_column(entity::user::Column::Avatar)
+                    .clone(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,222 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, DbBackend},
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000007_permission_flags"
+    }
+}
+
+// Bits of `PermissionValue` when this migration was written
+const WRITE: u64 = 1 << 1;
+const ATTACH_FILES: u64 = 1 << 9;
+const ADD_REACTIONS: u64 = 1 << 11;
+const CREATE_INVITES: u64 = 1 << 14;
+const CHANGE_NICKNAME: u64 = 1 << 16;
+
+/// `Read`, `Write`, `Admin` and `ManageServer` existed before
+const LEGACY_FLAGS: u64 = (1 << 4) - 1;
+
+/// Channel permissions that were granted by `Write`
+const WRITE_FLAGS: u64 = ATTACH_FILES | ADD_REACTIONS;
+
+/// Server permissions that were granted to every member
+const MEMBER_FLAGS: u64 = CREATE_INVITES | CHANGE_NICKNAME;
+
+/// Values are stored as signed integers because sqlite cannot store `u64`
+async fn set_value_type<T, C>(
+    manager: &SchemaManager<'_>,
+    table: T,
+    column: C,
+    signed: bool,
+) -> Result<(), DbErr>
+where
+    T: IntoTableRef,
+    C: IntoIden,
+{
+    // Sqlite columns have no fixed type
+    if manager.get_database_backend() == DbBackend::Sqlite {
+        return Ok(());
+    }
+
+    let mut column = ColumnDef::new(column);
+    if signed {
+        column.big_integer();
+    } else {
+        column.big_unsigned();
+    }
+
+    manager
+        .alter_table(
+            Table::alter()
+                .table(table)
+                .modify_column(column.not_null())
+                .clone(),
+        )
+        .await
+}
+
+/// Replace every value of `column` with `update(value)`.
+///
+/// Bitwise operators differ between databases so each distinct value is updated
+/// on its own. `update` must give the same value when it is applied twice.
+async fn update_values<T, C>(
+    manager: &SchemaManager<'_>,
+    table: T,
+    column: C,
+    update: impl Fn(u64) -> u64 + Send + Sync,
+) -> Result<(), DbErr>
+where
+    T: IntoTableRef + Copy,
+    C: IntoIden + sea_orm::IdenStatic + Copy + 'static,
+{
+    let connection = manager.get_connection();
+    let backend = manager.get_database_backend();
+
+    let rows = connection
+        .query_all(
+            backend.build(
+                &Query::select()
+                    .distinct()
+                    .column(column)
+                    .from(table)
+                    .to_owned(),
+            ),
+        )
+        .await?;
+
+    for row in rows {
+        let value: i64 = row.try_get("", column.as_str())?;
+        let new_value: i64 = update(value.try_into().unwrap_or_default())
+            .try_into()
+            .unwrap_or_default();
+
+        if new_value == value {
+            continue;
+        }
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(table)
+                    .value(column, new_value)
+                    .and_where(Expr::col(column).eq(value))
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Channel permissions that had `Write` keep uploading files and reacting
+fn add_write_flags(value: u64) -> u64 {
+    if value & WRITE == WRITE {
+        value | WRITE_FLAGS
+    } else {
+        value
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use entity::{permission, roles};
+
+        set_value_type(
+            manager,
+            permission::role::Entity,
+            permission::role::Column::Value,
+            true,
+        )
+        .await?;
+        set_value_type(
+            manager,
+            permission::user::Entity,
+            permission::user::Column::Value,
+            true,
+        )
+        .await?;
+        set_value_type(
+            manager,
+            roles::Entity,
+            roles::Column::ServerPermission,
+            true,
+        )
+        .await?;
+
+        update_values(
+            manager,
+            permission::role::Entity,
+            permission::role::Column::Value,
+            add_write_flags,
+        )
+        .await?;
+        update_values(
+            manager,
+            permission::user::Entity,
+            permission::user::Column::Value,
+            add_write_flags,
+        )
+        .await?;
+        update_values(
+            manager,
+            roles::Entity,
+            roles::Column::ServerPermission,
+            |value| value | MEMBER_FLAGS,
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use entity::{permission, roles};
+
+        update_values(
+            manager,
+            permission::role::Entity,
+            permission::role::Column::Value,
+            |value| value & LEGACY_FLAGS,
+        )
+        .await?;
+        update_values(
+            manager,
+            permission::user::Entity,
+            permission::user::Column::Value,
+            |value| value & LEGACY_FLAGS,
+        )
+        .await?;
+        update_values(
+            manager,
+            roles::Entity,
+            roles::Column::ServerPermission,
+            |value| value & LEGACY_FLAGS,
+        )
+        .await?;
+
+        set_value_type(
+            manager,
+            permission::role::Entity,
+            permission::role::Column::Value,
+            false,
+        )
+        .await?;
+        set_value_type(
+            manager,
+            permission::user::Entity,
+            permission::user::Column::Value,
+            false,
+        )
+        .await?;
+        set_value_type(
+            manager,
+            roles::Entity,
+            roles::Column::ServerPermission,
+            false,
+        )
+        .await
+    }
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000008_permission_deny"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::permission::role::Entity)
+                    .add_column(
+                        ColumnDef::new(entity::permission::role::Column::Deny)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::permission::user::Entity)
+                    .add_column(
+                        ColumnDef::new(entity::permission::user::Column::Deny)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::permission::role::Entity)
+                    .drop_column(entity::permission::role::Column::Deny)
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::permission::user::Entity)
+                    .drop_column(entity::permission::user::Column::Deny)
+                    .clone(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,183 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, IdenStatic},
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000009_role_position"
+    }
+}
+
+/// Name of `Role::everyone` when this migration was written
+const EVERYONE_NAME: &str = "@everyone";
+const EVERYONE_COLOR: &str = "#ffffff";
+
+/// Give positions to existing roles in order of creation
+async fn set_positions(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    use entity::roles;
+
+    let connection = manager.get_connection();
+    let backend = manager.get_database_backend();
+
+    let rows = connection
+        .query_all(
+            backend.build(
+                &Query::select()
+                    .columns([roles::Column::Id, roles::Column::Serverid])
+                    .from(roles::Entity)
+                    .order_by(roles::Column::Serverid, Order::Asc)
+                    .order_by(roles::Column::Id, Order::Asc)
+                    .to_owned(),
+            ),
+        )
+        .await?;
+
+    let mut server = String::new();
+    let mut position: u32 = 0;
+
+    for row in rows {
+        let id: u32 = row.try_get("", roles::Column::Id.as_str())?;
+        let serverid: String = row.try_get("", roles::Column::Serverid.as_str())?;
+
+        if serverid == server {
+            position += 1;
+        } else {
+            server = serverid;
+            position = 1;
+        }
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(roles::Entity)
+                    .value(roles::Column::Position, position)
+                    .and_where(Expr::col(roles::Column::Id).eq(id))
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Create `@everyone` for existing servers.
+///
+/// It has no permission so members keep the permissions they had.
+async fn insert_everyone(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    use entity::{roles, server};
+
+    let connection = manager.get_connection();
+    let backend = manager.get_database_backend();
+
+    let rows = connection
+        .query_all(
+            backend.build(
+                &Query::select()
+                    .column(server::Column::Id)
+                    .from(server::Entity)
+                    .to_owned(),
+            ),
+        )
+        .await?;
+
+    for row in rows {
+        let serverid: String = row.try_get("", server::Column::Id.as_str())?;
+
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(roles::Entity)
+                    .columns([
+                        roles::Column::Serverid,
+                        roles::Column::Name,
+                        roles::Column::Color,
+                        roles::Column::ServerPermission,
+                        roles::Column::Position,
+                    ])
+                    .values_panic([
+                        serverid.into(),
+                        EVERYONE_NAME.into(),
+                        EVERYONE_COLOR.into(),
+                        0i64.into(),
+                        0u32.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::roles::Entity)
+                    .add_column(
+                        ColumnDef::new(entity::roles::Column::Position)
+                            .unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    .clone(),
+            )
+            .await?;
+
+        set_positions(manager).await?;
+        insert_everyone(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use entity::{permission, roles};
+
+        let everyone = Query::select()
+            .column(roles::Column::Id)
+            .from(roles::Entity)
+            .and_where(Expr::col(roles::Column::Position).eq(0))
+            .to_owned();
+
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(permission::role::Entity)
+                    .and_where(
+                        Expr::col(permission::role::Column::Role).in_subquery(everyone.clone()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(roles::assignation::Entity)
+                    .and_where(Expr::col(roles::assignation::Column::RoleId).in_subquery(everyone))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(roles::Entity)
+                    .and_where(Expr::col(roles::Column::Position).eq(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(roles::Entity)
+                    .drop_column(roles::Column::Position)
+                    .clone(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000010_create_bans"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::bans::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(entity::bans::Column::ServerId)
+                            .string_len(30)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::bans::Column::UserId)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::bans::Column::Moderator)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(entity::bans::Column::Reason).string_len(512))
+                    .col(ColumnDef::new(entity::bans::Column::ExpiresAt).date_time())
+                    .col(
+                        ColumnDef::new(entity::bans::Column::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(entity::bans::Column::ServerId)
+                            .col(entity::bans::Column::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::server::Entity, entity::server::Column::Id)
+                            .from(entity::bans::Entity, entity::bans::Column::ServerId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::user::Entity, entity::user::Column::Id)
+                            .from(entity::bans::Entity, entity::bans::Column::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::user::Entity, entity::user::Column::Id)
+                            .from(entity::bans::Entity, entity::bans::Column::Moderator),
+                    )
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(entity::bans::Entity).clone())
+            .await
+    }
+}
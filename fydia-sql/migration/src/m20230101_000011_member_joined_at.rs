@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000011_member_joined_at"
+    }
+}
+
+/// Sqlite cannot add a column with a non-constant default
+const UNKNOWN_DATE: &str = "1970-01-01 00:00:00";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use entity::members;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(members::Entity)
+                    .add_column(
+                        ColumnDef::new(members::Column::JoinedAt)
+                            .date_time()
+                            .not_null()
+                            .default(UNKNOWN_DATE),
+                    )
+                    .clone(),
+            )
+            .await?;
+
+        // Join date of existing members isn't known, the migration date is the closest one
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(members::Entity)
+                    .value(members::Column::JoinedAt, Expr::current_timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::members::Entity)
+                    .drop_column(entity::members::Column::JoinedAt)
+                    .clone(),
+            )
+            .await
+    }
+}
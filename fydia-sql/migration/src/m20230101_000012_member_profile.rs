@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000012_member_profile"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sqlite can only add one column by statement
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::members::Entity)
+                    .add_column(ColumnDef::new(entity::members::Column::Nickname).string_len(128))
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::members::Entity)
+                    .add_column(ColumnDef::new(entity::members::Column::Avatar).text())
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::members::Entity)
+                    .drop_column(entity::members::Column::Avatar)
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::members::Entity)
+                    .drop_column(entity::members::Column::Nickname)
+                    .clone(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000013_create_emojis"
+    }
+}
+
+/// Shortcodes are unique in a server
+const INDEX_NAME: &str = "idx_emojis_server_name";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::emojis::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(entity::emojis::Column::Id)
+                            .unsigned()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::emojis::Column::ServerId)
+                            .string_len(30)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::emojis::Column::Name)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::emojis::Column::Path)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::emojis::Column::Creator)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::server::Entity, entity::server::Column::Id)
+                            .from(entity::emojis::Entity, entity::emojis::Column::ServerId),
+                    )
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name(INDEX_NAME)
+                    .table(entity::emojis::Entity)
+                    .col(entity::emojis::Column::ServerId)
+                    .col(entity::emojis::Column::Name)
+                    .unique()
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(entity::emojis::Entity).clone())
+            .await
+    }
+}
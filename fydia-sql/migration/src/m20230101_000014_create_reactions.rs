@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000014_create_reactions"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::reactions::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(entity::reactions::Column::MessageId)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::reactions::Column::UserId)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::reactions::Column::Emoji)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(entity::reactions::Column::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(entity::reactions::Column::MessageId)
+                            .col(entity::reactions::Column::UserId)
+                            .col(entity::reactions::Column::Emoji),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::messages::Entity, entity::messages::Column::Id)
+                            .from(
+                                entity::reactions::Entity,
+                                entity::reactions::Column::MessageId,
+                            ),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::user::Entity, entity::user::Column::Id)
+                            .from(entity::reactions::Entity, entity::reactions::Column::UserId),
+                    )
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(entity::reactions::Entity).clone())
+            .await
+    }
+}
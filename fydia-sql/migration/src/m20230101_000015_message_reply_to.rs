@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000015_message_reply_to"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::messages::Entity)
+                    .add_column(ColumnDef::new(entity::messages::Column::ReplyTo).string_len(32))
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::messages::Entity)
+                    .drop_column(entity::messages::Column::ReplyTo)
+                    .clone(),
+            )
+            .await
+    }
+}
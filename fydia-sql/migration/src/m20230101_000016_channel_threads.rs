@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000016_channel_threads"
+    }
+}
+
+const INDEX_NAME: &str = "idx-channels-parent_channel";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use entity::channels::Column;
+
+        // Sqlite can only add one column by statement
+        let columns = [
+            ColumnDef::new(Column::ParentChannel).string_len(15).clone(),
+            ColumnDef::new(Column::ThreadMessage).string_len(32).clone(),
+            ColumnDef::new(Column::Archived)
+                .boolean()
+                .not_null()
+                .default(false)
+                .clone(),
+            ColumnDef::new(Column::AutoArchive).unsigned().clone(),
+            ColumnDef::new(Column::LastActivity).date_time().clone(),
+        ];
+
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(entity::channels::Entity)
+                        .add_column(&mut column)
+                        .clone(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name(INDEX_NAME)
+                    .table(entity::channels::Entity)
+                    .col(Column::ParentChannel)
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use entity::channels::Column;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(INDEX_NAME)
+                    .table(entity::channels::Entity)
+                    .clone(),
+            )
+            .await?;
+
+        for column in [
+            Column::ParentChannel,
+            Column::ThreadMessage,
+            Column::Archived,
+            Column::AutoArchive,
+            Column::LastActivity,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(entity::channels::Entity)
+                        .drop_column(column)
+                        .clone(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000017_create_pins"
+    }
+}
+
+const INDEX_NAME: &str = "idx-pins-channel_id";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use entity::pins::Column;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::pins::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::MessageId)
+                            .string_len(32)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::ChannelId).string_len(15).not_null())
+                    .col(
+                        ColumnDef::new(Column::PinnedBy)
+                            .integer()
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Column::PinnedAt).date_time().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::messages::Entity, entity::messages::Column::Id)
+                            .from(entity::pins::Entity, Column::MessageId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::channels::Entity, entity::channels::Column::Id)
+                            .from(entity::pins::Entity, Column::ChannelId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::user::Entity, entity::user::Column::Id)
+                            .from(entity::pins::Entity, Column::PinnedBy),
+                    )
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name(INDEX_NAME)
+                    .table(entity::pins::Entity)
+                    .col(Column::ChannelId)
+                    .col(Column::PinnedAt)
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(entity::pins::Entity).clone())
+            .await
+    }
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230101_000018_message_revisions"
+    }
+}
+
+const INDEX_NAME: &str = "idx-message_revisions-message_id";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        use entity::message_revisions::Column;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::messages::Entity)
+                    .add_column(ColumnDef::new(entity::messages::Column::EditedAt).date_time())
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(entity::message_revisions::Entity)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Column::Id)
+                            .unsigned()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Column::MessageId).string_len(32).not_null())
+                    .col(ColumnDef::new(Column::Content).text().not_null())
+                    .col(ColumnDef::new(Column::EditedAt).date_time().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .to(entity::messages::Entity, entity::messages::Column::Id)
+                            .from(entity::message_revisions::Entity, Column::MessageId),
+                    )
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name(INDEX_NAME)
+                    .table(entity::message_revisions::Entity)
+                    .col(Column::MessageId)
+                    .col(Column::EditedAt)
+                    .clone(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(entity::message_revisions::Entity)
+                    .clone(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(entity::messages::Entity)
+                    .drop_column(entity::messages::Column::EditedAt)
+                    .clone(),
+            )
+            .await
+    }
+}
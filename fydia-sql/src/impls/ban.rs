@@ -0,0 +1,139 @@
+use std::convert::TryFrom;
+
+use entity::bans::Model;
+use fydia_struct::{
+    ban::{Ban, BanError},
+    server::ServerId,
+    user::UserId,
+};
+use fydia_utils::async_trait;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use shared::sea_orm;
+
+use super::members::remove_member;
+
+#[async_trait::async_trait]
+pub trait SqlBan {
+    async fn by_server(
+        serverid: &ServerId,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Ban>, BanError>;
+    async fn by_user(
+        serverid: &ServerId,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<Ban, BanError>;
+    /// Return an error if user has a ban that isn't expired
+    async fn check_not_banned(
+        serverid: &ServerId,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<(), BanError>;
+    /// Insert the ban and remove the user from the server in one transaction,
+    /// an older ban of this user is replaced.
+    ///
+    /// Return the server avatar of the removed member
+    async fn insert(&self, executor: &DatabaseConnection) -> Result<Option<String>, BanError>;
+    async fn delete(&self, executor: &DatabaseConnection) -> Result<(), BanError>;
+}
+
+#[async_trait::async_trait]
+impl SqlBan for Ban {
+    async fn by_server(
+        serverid: &ServerId,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Ban>, BanError> {
+        let models = entity::bans::Entity::find()
+            .filter(entity::bans::Column::ServerId.eq(serverid.id.as_str()))
+            .order_by_asc(entity::bans::Column::CreatedAt)
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                BanError::CannotGet
+            })?;
+
+        Ok(models.iter().map(Model::to_ban).collect())
+    }
+
+    async fn by_user(
+        serverid: &ServerId,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<Ban, BanError> {
+        match entity::bans::Entity::find_by_id((serverid.id.clone(), userid.0.get_id_cloned()?))
+            .one(executor)
+            .await
+        {
+            Ok(Some(model)) => Ok(model.to_ban()),
+            Ok(None) => Err(BanError::NotBanned),
+            Err(error) => {
+                error!("{error}");
+                Err(BanError::CannotGet)
+            }
+        }
+    }
+
+    async fn check_not_banned(
+        serverid: &ServerId,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<(), BanError> {
+        match Self::by_user(serverid, userid, executor).await {
+            Ok(ban) if !ban.is_expired() => Err(BanError::Banned),
+            Ok(_) | Err(BanError::NotBanned) => Ok(()),
+            Err(error) => Err(error),
+        }
+    }
+
+    async fn insert(&self, executor: &DatabaseConnection) -> Result<Option<String>, BanError> {
+        let cannot_insert = |error: DbErr| {
+            error!("{error}");
+            BanError::CannotInsert
+        };
+        let userid = self.user_id.0.get_id_cloned()?;
+        let active_model = entity::bans::ActiveModel::try_from(self.clone())?;
+
+        let transaction = executor.begin().await.map_err(cannot_insert)?;
+
+        entity::bans::Entity::delete_many()
+            .filter(entity::bans::Column::ServerId.eq(self.server_id.id.as_str()))
+            .filter(entity::bans::Column::UserId.eq(userid))
+            .exec(&transaction)
+            .await
+            .map_err(cannot_insert)?;
+
+        entity::bans::Entity::insert(active_model)
+            .exec(&transaction)
+            .await
+            .map_err(cannot_insert)?;
+
+        let member = remove_member(&self.server_id, userid, &transaction)
+            .await
+            .map_err(cannot_insert)?;
+
+        transaction.commit().await.map_err(cannot_insert)?;
+
+        Ok(member.and_then(|member| member.avatar))
+    }
+
+    async fn delete(&self, executor: &DatabaseConnection) -> Result<(), BanError> {
+        let result = entity::bans::Entity::delete_many()
+            .filter(entity::bans::Column::ServerId.eq(self.server_id.id.as_str()))
+            .filter(entity::bans::Column::UserId.eq(self.user_id.0.get_id_cloned()?))
+            .exec(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                BanError::CannotDelete
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(BanError::NotBanned);
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
-use super::{channel::SqlChannel, members::SqlMembers, role::SqlRoles, user::SqlUser};
+use super::{
+    channel::SqlChannel, emoji::SqlEmoji, members::SqlMembers, role::SqlRoles, user::SqlUser,
+};
 use fydia_struct::{
-    channel::{Channel, ChannelError, ChannelId, ChannelType},
+    channel::{
+        Channel, ChannelError, ChannelId, ChannelType, ThreadMetadata, DEFAULT_AUTO_ARCHIVE,
+    },
+    emoji::{Emoji, EmojiError},
     instance::Instance,
-    messages::{Message, MessageError, MessageType, MessageTypeError},
-    permission::{Permission, PermissionError},
+    messages::{Date, Message, MessageError, MessageType, MessageTypeError},
+    permission::{Permission, PermissionError, PermissionValue},
+    reaction::ReactionError,
     roles::{Role, RoleError},
     server::{Members, MembersError, Server, ServerError, ServerId, Servers},
     user::{Token, User, UserError, UserId},
@@ -67,12 +73,26 @@ impl BasicModel for entity::channels::Model {
 
         let parent_id = ServerId::new(self.server_id.clone());
 
+        let thread = match (&self.parent_channel, &self.thread_message) {
+            (Some(parent_channel), Some(message_id)) => Some(ThreadMetadata {
+                parent_channel: ChannelId::new(parent_channel.clone()),
+                message_id: message_id.clone(),
+                archived: self.archived,
+                auto_archive: self.auto_archive.unwrap_or(DEFAULT_AUTO_ARCHIVE),
+                last_activity: self
+                    .last_activity
+                    .map_or_else(Date::now, Date::parse_from_naivetime),
+            }),
+            _ => None,
+        };
+
         Ok(Channel {
             id: ChannelId::new(self.id.clone()),
             name: self.name.clone(),
             parent_id,
             channel_type,
             description: self.description.clone().unwrap_or_default(),
+            thread,
         })
     }
 
@@ -94,6 +114,7 @@ impl BasicModel for entity::server::Model {
         let members = Members::users_of(&id, executor).await?;
         let roles = Role::by_server_id(&id.id, executor).await?;
         let channel = Channel::by_serverid(&id, executor).await?;
+        let emoji = Emoji::by_server_id(&id, executor).await?;
 
         Ok(Server {
             id,
@@ -103,7 +124,7 @@ impl BasicModel for entity::server::Model {
             members,
             channel,
             roles,
-            emoji: Vec::new(),
+            emoji,
         })
     }
 
@@ -127,6 +148,7 @@ impl BasicModel for entity::user::Model {
             id: UserId::new(self.id),
             name: self.name.clone(),
             description: self.description.clone(),
+            avatar: self.avatar.clone(),
             email: self.email.clone(),
             instance: Instance::default(),
             token: Token::new(self.token.clone()),
@@ -158,9 +180,16 @@ impl BasicModel for entity::messages::Model {
             content: self.content.clone().unwrap_or_default(),
             message_type,
             edited: self.edited != 0,
+            edited_at: self
+                .edited_at
+                .map(fydia_struct::messages::Date::parse_from_naivetime),
             timestamp: fydia_struct::messages::Date::parse_from_naivetime(self.timestamp),
             channel_id: ChannelId::new(self.channel_id.clone()),
             author_id,
+            author_member: None,
+            reactions: Vec::new(),
+            reply_to: self.reply_to.clone(),
+            referenced_message: None,
         })
     }
 
@@ -172,6 +201,46 @@ impl BasicModel for entity::messages::Model {
     }
 }
 
+#[async_trait::async_trait]
+impl BasicModel for entity::direct_message_messages::Model {
+    type StructSelf = fydia_struct::messages::Message;
+    type Entity = entity::direct_message_messages::Entity;
+
+    async fn to_struct(&self, executor: &DbConnection) -> Result<Self::StructSelf, ModelError> {
+        let author_id = User::by_id(self.author_id, executor).await?;
+
+        let message_type = MessageType::from_string(&self.message_type)?;
+
+        Ok(Message {
+            id: self.id.clone(),
+            content: self.content.clone().unwrap_or_default(),
+            message_type,
+            edited: self.edited != 0,
+            edited_at: None,
+            timestamp: fydia_struct::messages::Date::parse_from_naivetime(self.timestamp),
+            channel_id: ChannelId {
+                id: self.directmessage.to_string(),
+            },
+            author_id,
+            author_member: None,
+            reactions: Vec::new(),
+            reply_to: None,
+            referenced_message: None,
+        })
+    }
+
+    async fn get_model_by_id(
+        id: &str,
+        executor: &DbConnection,
+    ) -> Result<<<Self as BasicModel>::Entity as EntityTrait>::Model, ModelError> {
+        Self::get_model_by(
+            &[entity::direct_message_messages::Column::Id.eq(id)],
+            executor,
+        )
+        .await
+    }
+}
+
 #[async_trait::async_trait]
 impl BasicModel for entity::permission::role::Model {
     type StructSelf = fydia_struct::permission::Permission;
@@ -188,7 +257,12 @@ impl BasicModel for entity::permission::role::Model {
 
         let role = Role::by_id(self.role, &channel.parent_id, executor).await?;
 
-        Ok(Permission::role(role.id, Some(channel.id), self.value))
+        Ok(Permission::role(
+            role.id,
+            Some(channel.id),
+            PermissionValue::from_database(self.value),
+        )
+        .with_deny(PermissionValue::from_database(self.deny)))
     }
 
     async fn get_model_by_id(
@@ -215,7 +289,12 @@ impl BasicModel for entity::permission::user::Model {
         )
         .await?;
 
-        Ok(Permission::user(user.id, Some(channel.id), self.value))
+        Ok(Permission::user(
+            user.id,
+            Some(channel.id),
+            PermissionValue::from_database(self.value),
+        )
+        .with_deny(PermissionValue::from_database(self.deny)))
     }
 
     async fn get_model_by_id(
@@ -267,9 +346,25 @@ pub enum ModelError {
     #[error("{0}")]
     MembersError(Box<MembersError>),
     #[error("{0}")]
+    EmojiError(Box<EmojiError>),
+    #[error("{0}")]
+    ReactionError(Box<ReactionError>),
+    #[error("{0}")]
     Other(String),
 }
 
+impl From<ReactionError> for ModelError {
+    fn from(value: ReactionError) -> Self {
+        Self::ReactionError(Box::new(value))
+    }
+}
+
+impl From<EmojiError> for ModelError {
+    fn from(value: EmojiError) -> Self {
+        Self::EmojiError(Box::new(value))
+    }
+}
+
 impl From<MembersError> for ModelError {
     fn from(value: MembersError) -> Self {
         Self::MembersError(Box::new(value))
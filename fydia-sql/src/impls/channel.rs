@@ -2,7 +2,7 @@ use std::convert::TryFrom;
 
 use super::{
     basic_model::BasicModel,
-    delete, insert,
+    insert,
     message::SqlMessage,
     server::{SqlServer, SqlServerId},
     update,
@@ -11,12 +11,12 @@ use super::{
 use entity::channels::Model;
 use fydia_struct::{
     channel::{Channel, ChannelError, ChannelId},
-    messages::{Message, MessageError},
+    messages::{Message, MessageError, MessageQuery, MessagesPage},
     server::{Channels, ServerId},
     user::UserId,
 };
 use fydia_utils::async_trait;
-use sea_orm::{ColumnTrait, DatabaseConnection, Set};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, TransactionTrait};
 use shared::sea_orm;
 
 #[async_trait::async_trait]
@@ -39,7 +39,11 @@ pub trait SqlChannel {
         executor: &DatabaseConnection,
     ) -> Result<(), ChannelError>;
     async fn delete(mut self, executor: &DatabaseConnection) -> Result<(), ChannelError>;
-    async fn messages(&self, executor: &DatabaseConnection) -> Result<Vec<Message>, MessageError>;
+    async fn messages(
+        &self,
+        query: &MessageQuery,
+        executor: &DatabaseConnection,
+    ) -> Result<MessagesPage, MessageError>;
 }
 
 #[async_trait::async_trait]
@@ -55,8 +59,12 @@ impl SqlChannel for Channel {
         executor: &DatabaseConnection,
     ) -> Result<Channels, ChannelError> {
         let mut channels: Vec<Channel> = Vec::new();
+        // Threads are listed with their parent channel
         let models = Model::get_models_by(
-            &[entity::channels::Column::ServerId.eq(id.id.as_str())],
+            &[
+                entity::channels::Column::ServerId.eq(id.id.as_str()),
+                entity::channels::Column::ParentChannel.is_null(),
+            ],
             executor,
         )
         .await?;
@@ -142,15 +150,44 @@ impl SqlChannel for Channel {
                 })?
                 .into();
 
-        delete(active_model, executor).await?;
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{}", error);
+            ChannelError::CannotDelete
+        })?;
+
+        entity::channels::Entity::delete_many()
+            .filter(entity::channels::Column::ParentChannel.eq(self.id.id.as_str()))
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{}", error);
+                ChannelError::CannotDelete
+            })?;
+
+        entity::channels::Entity::delete(active_model)
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{}", error);
+                ChannelError::CannotDelete
+            })?;
+
+        transaction.commit().await.map_err(|error| {
+            error!("{}", error);
+            ChannelError::CannotDelete
+        })?;
 
         drop(self);
 
         Ok(())
     }
 
-    async fn messages(&self, executor: &DatabaseConnection) -> Result<Vec<Message>, MessageError> {
-        Message::by_channel(self.id.clone(), executor).await
+    async fn messages(
+        &self,
+        query: &MessageQuery,
+        executor: &DatabaseConnection,
+    ) -> Result<MessagesPage, MessageError> {
+        Message::by_channel(self.id.clone(), query, executor).await
     }
 }
 
@@ -1,20 +1,31 @@
 use std::convert::TryFrom;
 
-use super::{delete, get_set_column, insert, user::UserFrom};
+use super::{
+    basic_model::BasicModel, delete, get_set_column, insert, message::paginate, user::UserFrom,
+};
 use fydia_struct::{
     directmessage::{DirectMessage, DirectMessageError},
+    messages::{Message, MessageError, MessageQuery, MessagesPage},
     server::Members,
     sqlerror::{GenericError, GenericSqlError},
     user::UserId,
     utils::Id,
 };
 use fydia_utils::async_trait;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait};
 use shared::sea_orm;
-use {entity::direct_message as dm, entity::direct_message_members as dm_members};
+use {
+    entity::direct_message as dm, entity::direct_message_members as dm_members,
+    entity::direct_message_messages as dm_messages,
+};
 #[async_trait::async_trait]
 pub trait DirectMessageMembers {
     async fn members(&self, executor: &DatabaseConnection) -> Result<Members, DirectMessageError>;
+    async fn is_member(
+        &self,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<bool, DirectMessageError>;
     async fn of_user(
         userid: &UserId,
         executor: &DatabaseConnection,
@@ -47,6 +58,13 @@ impl DirectMessageMembers for DirectMessage {
 
         Ok(Members::new(userids))
     }
+    async fn is_member(
+        &self,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<bool, DirectMessageError> {
+        Ok(self.members(executor).await?.members.contains(userid))
+    }
     async fn of_user(
         userid: &UserId,
         executor: &DatabaseConnection,
@@ -136,3 +154,55 @@ impl SqlDirectMessage for DirectMessage {
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+pub trait SqlDirectMessageMessages {
+    async fn messages(
+        &self,
+        query: &MessageQuery,
+        executor: &DatabaseConnection,
+    ) -> Result<MessagesPage, MessageError>;
+    async fn insert_message(
+        &self,
+        message: &Message,
+        executor: &DatabaseConnection,
+    ) -> Result<(), MessageError>;
+}
+
+#[async_trait::async_trait]
+impl SqlDirectMessageMessages for DirectMessage {
+    async fn messages(
+        &self,
+        query: &MessageQuery,
+        executor: &DatabaseConnection,
+    ) -> Result<MessagesPage, MessageError> {
+        let id = self.id.get_id_cloned()?;
+
+        let (models, next) = paginate::<dm_messages::Entity>(
+            Condition::all().add(dm_messages::Column::Directmessage.eq(id)),
+            query,
+            executor,
+        )
+        .await?;
+
+        let mut messages = Vec::with_capacity(models.len());
+        for i in models {
+            messages.push(i.to_struct(executor).await?);
+        }
+
+        Ok(MessagesPage { messages, next })
+    }
+
+    async fn insert_message(
+        &self,
+        message: &Message,
+        executor: &DatabaseConnection,
+    ) -> Result<(), MessageError> {
+        let active_model =
+            dm_messages::ActiveModel::new(message.clone(), self.id.get_id_cloned()?)?;
+
+        insert(active_model, executor).await?;
+
+        Ok(())
+    }
+}
@@ -1,19 +1,202 @@
-use fydia_struct::emoji::Emoji;
+use entity::emojis::{Column, Entity};
+use fydia_struct::{
+    emoji::{Emoji, EmojiError, MAX_EMOJIS_PER_SERVER},
+    reaction::ReactionEmoji,
+    server::ServerId,
+    utils::Id,
+};
 use fydia_utils::async_trait;
-use sea_orm::DatabaseConnection;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
 use shared::sea_orm;
+use std::convert::TryFrom;
+
 #[async_trait::async_trait]
 pub trait SqlEmoji {
-    async fn get_emoji_by_server_id(server_id: String, executor: &DatabaseConnection)
-        -> Vec<Emoji>;
+    async fn by_server_id(
+        server_id: &ServerId,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Emoji>, EmojiError>;
+    async fn by_id(emoji_id: u32, executor: &DatabaseConnection) -> Result<Emoji, EmojiError>;
+    /// Insert an emoji if server has room for it and its name isn't taken
+    async fn insert(&mut self, executor: &DatabaseConnection) -> Result<(), EmojiError>;
+    async fn update_name<T: Into<String> + Send>(
+        &mut self,
+        name: T,
+        executor: &DatabaseConnection,
+    ) -> Result<(), EmojiError>;
+    async fn delete(&self, executor: &DatabaseConnection) -> Result<(), EmojiError>;
 }
 
 #[async_trait::async_trait]
 impl SqlEmoji for Emoji {
-    async fn get_emoji_by_server_id(
-        _server_id: String,
-        _executor: &DatabaseConnection,
-    ) -> Vec<Emoji> {
-        Vec::new()
+    async fn by_server_id(
+        server_id: &ServerId,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Emoji>, EmojiError> {
+        Ok(Entity::find()
+            .filter(Column::ServerId.eq(server_id.id.as_str()))
+            .order_by_asc(Column::Id)
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                EmojiError::CannotGet
+            })?
+            .iter()
+            .map(entity::emojis::Model::to_emoji)
+            .collect())
+    }
+
+    async fn by_id(emoji_id: u32, executor: &DatabaseConnection) -> Result<Emoji, EmojiError> {
+        Entity::find_by_id(emoji_id)
+            .one(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                EmojiError::CannotGet
+            })?
+            .map(|model| model.to_emoji())
+            .ok_or(EmojiError::NoEmojiWithId)
+    }
+
+    async fn insert(&mut self, executor: &DatabaseConnection) -> Result<(), EmojiError> {
+        let model = entity::emojis::ActiveModel::try_from(self.clone())?;
+
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{error}");
+            EmojiError::CannotInsert
+        })?;
+
+        let count = Entity::find()
+            .filter(Column::ServerId.eq(self.server_id.id.as_str()))
+            .count(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                EmojiError::CannotInsert
+            })?;
+
+        if count >= MAX_EMOJIS_PER_SERVER as u64 {
+            return Err(EmojiError::TooManyEmojis);
+        }
+
+        check_name_taken(&self.server_id, &self.name, None, &transaction).await?;
+
+        let result = Entity::insert(model)
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                EmojiError::CannotInsert
+            })?;
+
+        transaction.commit().await.map_err(|error| {
+            error!("{error}");
+            EmojiError::CannotInsert
+        })?;
+
+        self.id = Id::Id(result.last_insert_id);
+
+        Ok(())
+    }
+
+    async fn update_name<T: Into<String> + Send>(
+        &mut self,
+        name: T,
+        executor: &DatabaseConnection,
+    ) -> Result<(), EmojiError> {
+        let name = name.into();
+        Emoji::check_name(&name)?;
+
+        let id = self.id.get_id_cloned()?;
+
+        check_name_taken(&self.server_id, &name, Some(id), executor).await?;
+
+        Entity::update(entity::emojis::ActiveModel {
+            id: Set(id),
+            name: Set(name.clone()),
+            ..Default::default()
+        })
+        .exec(executor)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            EmojiError::CannotUpdate
+        })?;
+
+        self.name = name;
+
+        Ok(())
+    }
+
+    async fn delete(&self, executor: &DatabaseConnection) -> Result<(), EmojiError> {
+        let id = self.id.get_id_cloned()?;
+        let key = entity::reactions::emoji_key(&ReactionEmoji::Custom(self.id.clone())).map_err(
+            |error| {
+                error!("{error}");
+                EmojiError::CannotDelete
+            },
+        )?;
+
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{error}");
+            EmojiError::CannotDelete
+        })?;
+
+        entity::reactions::Entity::delete_many()
+            .filter(entity::reactions::Column::Emoji.eq(key))
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                EmojiError::CannotDelete
+            })?;
+
+        Entity::delete_by_id(id)
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                EmojiError::CannotDelete
+            })?;
+
+        transaction.commit().await.map_err(|error| {
+            error!("{error}");
+            EmojiError::CannotDelete
+        })
     }
 }
+
+/// Check that no other emoji of the server has this name
+async fn check_name_taken<C: ConnectionTrait>(
+    server_id: &ServerId,
+    name: &str,
+    except: Option<u32>,
+    executor: &C,
+) -> Result<(), EmojiError> {
+    let mut query = Entity::find()
+        .filter(Column::ServerId.eq(server_id.id.as_str()))
+        .filter(Column::Name.eq(name));
+
+    if let Some(id) = except {
+        query = query.filter(Column::Id.ne(id));
+    }
+
+    let taken = query
+        .one(executor)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            EmojiError::CannotGet
+        })?
+        .is_some();
+
+    if taken {
+        return Err(EmojiError::NameTaken);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use std::convert::TryFrom;
+
+use entity::invites::{uses, Model};
+use fydia_struct::{
+    invite::{Invite, InviteError},
+    messages::Date,
+    server::{Server, ServerId},
+    user::User,
+};
+use fydia_utils::async_trait;
+use migration::Expr;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, Set, TransactionTrait,
+};
+use shared::sea_orm;
+
+use super::{delete, insert};
+
+#[async_trait::async_trait]
+pub trait SqlInvite {
+    async fn by_code(code: &str, executor: &DatabaseConnection) -> Result<Invite, InviteError>;
+    async fn by_server(
+        serverid: &ServerId,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Invite>, InviteError>;
+    async fn insert(&self, executor: &DatabaseConnection) -> Result<(), InviteError>;
+    async fn delete(self, executor: &DatabaseConnection) -> Result<(), InviteError>;
+    async fn use_invite(
+        &mut self,
+        server: &mut Server,
+        user: &mut User,
+        executor: &DatabaseConnection,
+    ) -> Result<(), InviteError>;
+}
+
+#[async_trait::async_trait]
+impl SqlInvite for Invite {
+    async fn by_code(code: &str, executor: &DatabaseConnection) -> Result<Invite, InviteError> {
+        match entity::invites::Entity::find_by_id(code.to_string())
+            .one(executor)
+            .await
+        {
+            Ok(Some(model)) => Ok(model.to_invite()),
+            Ok(None) => Err(InviteError::NoInviteWithCode),
+            Err(error) => {
+                error!("{error}");
+                Err(InviteError::NoInviteWithCode)
+            }
+        }
+    }
+
+    async fn by_server(
+        serverid: &ServerId,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Invite>, InviteError> {
+        let models = entity::invites::Entity::find()
+            .filter(entity::invites::Column::ServerId.eq(serverid.id.as_str()))
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                InviteError::NoInviteWithCode
+            })?;
+
+        Ok(models.iter().map(Model::to_invite).collect())
+    }
+
+    async fn insert(&self, executor: &DatabaseConnection) -> Result<(), InviteError> {
+        let active_model = entity::invites::ActiveModel::try_from(self.clone())?;
+
+        insert(active_model, executor).await?;
+
+        Ok(())
+    }
+
+    async fn delete(self, executor: &DatabaseConnection) -> Result<(), InviteError> {
+        let active_model = entity::invites::ActiveModel::try_from(self)?;
+
+        delete(active_model, executor).await?;
+
+        Ok(())
+    }
+
+    /// Take one use of the invite to make user join its server.
+    ///
+    /// Membership is checked, uses are incremented and the member is inserted
+    /// in a single transaction, an invite is never consumed by a failed join.
+    /// Uses are incremented in a single conditional update so that
+    /// concurrent joins cannot go over `max_uses`.
+    async fn use_invite(
+        &mut self,
+        server: &mut Server,
+        user: &mut User,
+        executor: &DatabaseConnection,
+    ) -> Result<(), InviteError> {
+        if self.is_expired() {
+            return Err(InviteError::Expired);
+        }
+
+        let userid = user.id.0.get_id_cloned()?;
+        let member = entity::members::Model::new_activemodel(&user.id, server.id.clone())?;
+        let used = uses::ActiveModel {
+            code: Set(self.code.clone()),
+            server_id: Set(self.server_id.id.clone()),
+            user_id: Set(userid),
+            used_at: Set(Date::now().0.naive_utc()),
+            ..Default::default()
+        };
+
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{error}");
+            InviteError::CannotUse
+        })?;
+
+        let is_member = entity::members::Entity::find_by_id((server.id.id.clone(), userid))
+            .one(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                InviteError::CannotUse
+            })?
+            .is_some();
+
+        if is_member {
+            return Err(InviteError::AlreadyMember);
+        }
+
+        let result = entity::invites::Entity::update_many()
+            .col_expr(
+                entity::invites::Column::Uses,
+                Expr::col(entity::invites::Column::Uses).add(1),
+            )
+            .filter(entity::invites::Column::Code.eq(self.code.as_str()))
+            .filter(
+                Condition::any()
+                    .add(entity::invites::Column::MaxUses.is_null())
+                    .add(
+                        Expr::col(entity::invites::Column::Uses)
+                            .lt(Expr::col(entity::invites::Column::MaxUses)),
+                    ),
+            )
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                InviteError::CannotUse
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(InviteError::MaxUsesReached);
+        }
+
+        uses::Entity::insert(used)
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                InviteError::CannotUse
+            })?;
+
+        entity::members::Entity::insert(member)
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                InviteError::CannotUse
+            })?;
+
+        transaction.commit().await.map_err(|error| {
+            error!("{error}");
+            InviteError::CannotUse
+        })?;
+
+        self.uses += 1;
+        user.insert_server(&server.id);
+        server.members.members.push(user.id.clone());
+
+        Ok(())
+    }
+}
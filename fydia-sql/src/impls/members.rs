@@ -1,12 +1,20 @@
 use super::insert;
 use entity::members::{Column, Model};
 use fydia_struct::{
+    member::{Member, MemberProfile, MemberQuery, MembersPage},
+    roles::RoleId,
     server::{Members, MembersError, ServerId},
     user::UserId,
+    utils::Id,
 };
 use fydia_utils::async_trait;
-use sea_orm::{ColumnTrait, DatabaseConnection};
+use sea_orm::{
+    sea_query::{Expr, LikeExpr},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
 use shared::sea_orm;
+use std::collections::HashMap;
 
 #[async_trait::async_trait]
 pub trait SqlMembers {
@@ -23,6 +31,41 @@ pub trait SqlMembers {
         userid: &UserId,
         executor: &DatabaseConnection,
     ) -> Result<(), MembersError>;
+    /// Remove user from server with all roles of user in this server.
+    ///
+    /// Return the server avatar of user that has to be removed from the storage.
+    async fn delete(
+        serverid: &ServerId,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<Option<String>, MembersError>;
+    async fn page(
+        serverid: &ServerId,
+        query: &MemberQuery,
+        executor: &DatabaseConnection,
+    ) -> Result<MembersPage, MembersError>;
+    async fn by_id(
+        serverid: &ServerId,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<Member, MembersError>;
+    async fn profile(
+        serverid: &ServerId,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<MemberProfile, MembersError>;
+    async fn update_nickname(
+        serverid: &ServerId,
+        userid: &UserId,
+        nickname: Option<String>,
+        executor: &DatabaseConnection,
+    ) -> Result<(), MembersError>;
+    async fn update_avatar(
+        serverid: &ServerId,
+        userid: &UserId,
+        avatar: Option<String>,
+        executor: &DatabaseConnection,
+    ) -> Result<(), MembersError>;
 }
 
 #[async_trait::async_trait]
@@ -65,4 +108,245 @@ impl SqlMembers for Members {
 
         Ok(())
     }
+
+    async fn delete(
+        server: &ServerId,
+        user: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<Option<String>, MembersError> {
+        let userid = user.0.get_id_cloned()?;
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{error}");
+            MembersError::CannotRemove
+        })?;
+
+        let member = remove_member(server, userid, &transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                MembersError::CannotRemove
+            })?
+            .ok_or(MembersError::NotMember)?;
+
+        transaction.commit().await.map_err(|error| {
+            error!("{error}");
+            MembersError::CannotRemove
+        })?;
+
+        Ok(member.avatar)
+    }
+
+    async fn page(
+        server: &ServerId,
+        query: &MemberQuery,
+        executor: &DatabaseConnection,
+    ) -> Result<MembersPage, MembersError> {
+        let mut select = entity::members::Entity::find()
+            .find_also_related(entity::user::Entity)
+            .filter(Column::Serverid.eq(server.id.as_str()))
+            .order_by_asc(Column::Userid)
+            .limit(query.limit + 1);
+
+        if let Some(after) = &query.after {
+            select = select.filter(Column::Userid.gt(after.0.get_id_cloned()?));
+        }
+
+        if let Some(search) = &query.search {
+            select = select.filter(
+                Expr::col((entity::user::Entity, entity::user::Column::Name))
+                    .like(LikeExpr::str(&format!("{}%", escape_like(search))).escape('\\')),
+            );
+        }
+
+        let mut models = select.all(executor).await.map_err(|error| {
+            error!("{error}");
+            MembersError::CannotGetUsers
+        })?;
+
+        let more = models.len() as u64 > query.limit;
+        models.truncate(query.limit as usize);
+
+        let userids = models
+            .iter()
+            .map(|(member, _)| member.userid)
+            .collect::<Vec<_>>();
+
+        let mut roles = roles_of(server, userids, executor).await?;
+
+        let members = models
+            .into_iter()
+            .filter_map(|(member, user)| {
+                user.map(|user| Member {
+                    id: member.to_userid(),
+                    name: user.name,
+                    roles: roles.remove(&member.userid).unwrap_or_default(),
+                    profile: member.to_profile(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let next = if more {
+            members.last().map(|member| member.id.clone())
+        } else {
+            None
+        };
+
+        Ok(MembersPage { members, next })
+    }
+
+    async fn by_id(
+        server: &ServerId,
+        user: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<Member, MembersError> {
+        let userid = user.0.get_id_cloned()?;
+
+        let (member, user) = entity::members::Entity::find_by_id((server.id.clone(), userid))
+            .find_also_related(entity::user::Entity)
+            .one(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                MembersError::CannotGetUsers
+            })?
+            .ok_or(MembersError::NotMember)?;
+
+        let user = user.ok_or(MembersError::CannotGetUsers)?;
+        let mut roles = roles_of(server, vec![userid], executor).await?;
+
+        Ok(Member {
+            id: member.to_userid(),
+            name: user.name,
+            roles: roles.remove(&userid).unwrap_or_default(),
+            profile: member.to_profile(),
+        })
+    }
+
+    async fn profile(
+        server: &ServerId,
+        user: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<MemberProfile, MembersError> {
+        let userid = user.0.get_id_cloned()?;
+
+        entity::members::Entity::find_by_id((server.id.clone(), userid))
+            .one(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                MembersError::CannotGetUsers
+            })?
+            .map(|member| member.to_profile())
+            .ok_or(MembersError::NotMember)
+    }
+
+    async fn update_nickname(
+        server: &ServerId,
+        user: &UserId,
+        nickname: Option<String>,
+        executor: &DatabaseConnection,
+    ) -> Result<(), MembersError> {
+        update_column(server, user, Column::Nickname, nickname, executor).await
+    }
+
+    async fn update_avatar(
+        server: &ServerId,
+        user: &UserId,
+        avatar: Option<String>,
+        executor: &DatabaseConnection,
+    ) -> Result<(), MembersError> {
+        update_column(server, user, Column::Avatar, avatar, executor).await
+    }
+}
+
+/// Set a nullable column of a member
+async fn update_column(
+    server: &ServerId,
+    user: &UserId,
+    column: Column,
+    value: Option<String>,
+    executor: &DatabaseConnection,
+) -> Result<(), MembersError> {
+    let userid = user.0.get_id_cloned()?;
+
+    let result = entity::members::Entity::update_many()
+        .col_expr(column, Expr::value(value))
+        .filter(Column::Serverid.eq(server.id.as_str()))
+        .filter(Column::Userid.eq(userid))
+        .exec(executor)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            MembersError::CannotUpdate
+        })?;
+
+    if result.rows_affected == 0 {
+        return Err(MembersError::NotMember);
+    }
+
+    Ok(())
+}
+
+/// Return ids of roles of each user in a server
+async fn roles_of(
+    server: &ServerId,
+    userids: Vec<u32>,
+    executor: &DatabaseConnection,
+) -> Result<HashMap<u32, Vec<RoleId>>, MembersError> {
+    let mut roles: HashMap<u32, Vec<RoleId>> = HashMap::new();
+
+    for assignation in entity::roles::assignation::Entity::find()
+        .filter(entity::roles::assignation::Column::ServerId.eq(server.id.as_str()))
+        .filter(entity::roles::assignation::Column::UserId.is_in(userids))
+        .order_by_asc(entity::roles::assignation::Column::RoleId)
+        .all(executor)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            MembersError::CannotGetUsers
+        })?
+    {
+        roles
+            .entry(assignation.user_id)
+            .or_default()
+            .push(Id::Id(assignation.role_id));
+    }
+
+    Ok(roles)
+}
+
+/// Escape wildcards of `LIKE` with a backslash
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Remove the user and its roles from the server, return the removed member if there was one
+pub(crate) async fn remove_member<C: ConnectionTrait>(
+    server: &ServerId,
+    userid: u32,
+    executor: &C,
+) -> Result<Option<Model>, DbErr> {
+    let Some(member) = entity::members::Entity::find_by_id((server.id.clone(), userid))
+        .one(executor)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    entity::roles::assignation::Entity::delete_many()
+        .filter(entity::roles::assignation::Column::ServerId.eq(server.id.as_str()))
+        .filter(entity::roles::assignation::Column::UserId.eq(userid))
+        .exec(executor)
+        .await?;
+
+    entity::members::Entity::delete_many()
+        .filter(Column::Serverid.eq(server.id.as_str()))
+        .filter(Column::Userid.eq(userid))
+        .exec(executor)
+        .await?;
+
+    Ok(Some(member))
 }
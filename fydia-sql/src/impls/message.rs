@@ -1,16 +1,30 @@
 #![allow(irrefutable_let_patterns)]
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
-use super::{basic_model::BasicModel, delete, insert};
+use super::{
+    basic_model::{BasicModel, ModelError},
+    insert,
+    reaction::SqlReaction,
+    user::SqlUser,
+};
 use entity::messages::Model;
 use fydia_struct::{
     channel::ChannelId,
-    messages::{Message, MessageError},
+    member::MemberProfile,
+    messages::{
+        Date, Message, MessageCursor, MessageError, MessageQuery, MessageRevision, MessageSnapshot,
+        MessageType, MessagesPage,
+    },
+    reaction::Reaction,
+    user::User,
 };
 use fydia_utils::async_trait;
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    prelude::DateTime as NaiveDateTime, ActiveValue::NotSet, ColumnTrait, Condition,
+    DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+    Set, TransactionTrait,
 };
 use shared::sea_orm;
 
@@ -22,18 +36,26 @@ pub trait SqlMessage {
     ) -> Result<Vec<Message>, MessageError>;
     async fn by_channel(
         channel_id: ChannelId,
+        query: &MessageQuery,
         executor: &DatabaseConnection,
-    ) -> Result<Vec<Message>, MessageError>;
+    ) -> Result<MessagesPage, MessageError>;
     async fn by_id(
         message_id: &str,
         executor: &DatabaseConnection,
     ) -> Result<Message, MessageError>;
     async fn insert(&self, executor: &DatabaseConnection) -> Result<(), MessageError>;
+    /// Replace content of the message and keep the previous one as a revision,
+    /// return the date of the edit
     async fn update(
         &mut self,
         content: &str,
         executor: &DatabaseConnection,
-    ) -> Result<(), MessageError>;
+    ) -> Result<Date, MessageError>;
+    /// Return previous contents of the message, from the oldest to the newest
+    async fn revisions(
+        &self,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<MessageRevision>, MessageError>;
     async fn delete(mut self, executor: &DatabaseConnection) -> Result<(), MessageError>;
 }
 
@@ -50,11 +72,15 @@ impl SqlMessage for Message {
             .paginate(executor, 50);
 
         while let Ok(Some(e)) = query.fetch_and_next().await {
+            let mut page = Vec::with_capacity(e.len());
             for i in e {
                 if let Ok(message) = i.to_struct(executor).await {
-                    messages.push(message);
+                    page.push(message);
                 }
             }
+
+            attach_details(&mut page, executor).await?;
+            messages.append(&mut page);
         }
 
         Ok(messages)
@@ -62,23 +88,24 @@ impl SqlMessage for Message {
 
     async fn by_channel(
         channel_id: ChannelId,
+        query: &MessageQuery,
         executor: &DatabaseConnection,
-    ) -> Result<Vec<Message>, MessageError> {
-        let mut messages = Vec::new();
-        let query = entity::messages::Entity::find()
-            .filter(entity::messages::Column::ChannelId.eq(channel_id.id))
-            .order_by(entity::messages::Column::Timestamp, sea_orm::Order::Asc)
-            .paginate(executor, 50);
+    ) -> Result<MessagesPage, MessageError> {
+        let (models, next) = paginate::<entity::messages::Entity>(
+            Condition::all().add(entity::messages::Column::ChannelId.eq(channel_id.id.as_str())),
+            query,
+            executor,
+        )
+        .await?;
 
-        if let Ok(models) = query.fetch().await {
-            for i in models {
-                if let Ok(message) = i.to_struct(executor).await {
-                    messages.push(message);
-                }
-            }
+        let mut messages = Vec::with_capacity(models.len());
+        for i in models {
+            messages.push(i.to_struct(executor).await?);
         }
 
-        Ok(messages)
+        attach_details(&mut messages, executor).await?;
+
+        Ok(MessagesPage { messages, next })
     }
 
     async fn by_id(
@@ -87,9 +114,12 @@ impl SqlMessage for Message {
     ) -> Result<Message, MessageError> {
         let message = Model::get_model_by_id(message_id, executor).await?;
 
-        let messages = message.to_struct(executor).await?;
+        let mut messages = [message.to_struct(executor).await?];
 
-        Ok(messages)
+        attach_details(&mut messages, executor).await?;
+
+        let [message] = messages;
+        Ok(message)
     }
 
     async fn insert(&self, executor: &DatabaseConnection) -> Result<(), MessageError> {
@@ -104,29 +134,451 @@ impl SqlMessage for Message {
         &mut self,
         content: &str,
         executor: &DatabaseConnection,
-    ) -> Result<(), MessageError> {
-        let model = entity::messages::ActiveModel::try_from(self.clone())?;
+    ) -> Result<Date, MessageError> {
+        let mut message = self.clone();
+        let revision = message.edit(content)?;
+
+        let revision_model = entity::message_revisions::ActiveModel {
+            id: NotSet,
+            message_id: Set(message.id.clone()),
+            content: Set(revision.content),
+            edited_at: Set(revision.edited_at.0.naive_utc()),
+        };
+        let model = entity::messages::ActiveModel::try_from(message.clone())?;
+
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{error}");
+            MessageError::CannotUpdate
+        })?;
+
+        entity::message_revisions::Entity::insert(revision_model)
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                MessageError::CannotUpdate
+            })?;
 
         entity::messages::Entity::update(model)
-            .filter(entity::messages::Column::Id.eq(self.id.as_str()))
-            .exec(executor)
+            .filter(entity::messages::Column::Id.eq(message.id.as_str()))
+            .exec(&transaction)
             .await
-            .map_err(|_f| MessageError::CannotIntoActiveModel)?;
+            .map_err(|error| {
+                error!("{error}");
+                MessageError::CannotUpdate
+            })?;
 
-        self.content = content.to_string();
+        transaction.commit().await.map_err(|error| {
+            error!("{error}");
+            MessageError::CannotUpdate
+        })?;
 
-        Ok(())
+        *self = message;
+
+        Ok(revision.edited_at)
+    }
+
+    async fn revisions(
+        &self,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<MessageRevision>, MessageError> {
+        let revisions = entity::message_revisions::Entity::find()
+            .filter(entity::message_revisions::Column::MessageId.eq(self.id.as_str()))
+            .order_by_asc(entity::message_revisions::Column::EditedAt)
+            .order_by_asc(entity::message_revisions::Column::Id)
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                MessageError::CannotGetRevisions
+            })?
+            .into_iter()
+            .map(|model| MessageRevision {
+                content: model.content,
+                edited_at: Date::parse_from_naivetime(model.edited_at),
+            })
+            .collect();
+
+        Ok(revisions)
     }
 
     async fn delete(mut self, executor: &DatabaseConnection) -> Result<(), MessageError> {
-        let model = Model::get_model_by_id(&self.id, executor).await?;
+        let cannot_delete = |error: DbErr| {
+            error!("{error}");
+            MessageError::CannotDelete
+        };
+
+        let transaction = executor.begin().await.map_err(cannot_delete)?;
 
-        let active_model: entity::messages::ActiveModel = model.clone().into();
+        entity::reactions::Entity::delete_many()
+            .filter(entity::reactions::Column::MessageId.eq(self.id.as_str()))
+            .exec(&transaction)
+            .await
+            .map_err(cannot_delete)?;
+
+        entity::pins::Entity::delete_by_id(self.id.clone())
+            .exec(&transaction)
+            .await
+            .map_err(cannot_delete)?;
+
+        entity::message_revisions::Entity::delete_many()
+            .filter(entity::message_revisions::Column::MessageId.eq(self.id.as_str()))
+            .exec(&transaction)
+            .await
+            .map_err(cannot_delete)?;
+
+        let deleted = entity::messages::Entity::delete_by_id(self.id.clone())
+            .exec(&transaction)
+            .await
+            .map_err(cannot_delete)?;
 
-        delete(active_model, executor).await?;
+        if deleted.rows_affected == 0 {
+            return Err(MessageError::CannotDelete);
+        }
+
+        transaction.commit().await.map_err(cannot_delete)?;
 
         drop(self);
 
         Ok(())
     }
 }
+
+/// Attach member profiles of authors, reactions and replied messages to `messages`.
+///
+/// Each of them is fetched with one query for all messages instead of once per message.
+pub(crate) async fn attach_details(
+    messages: &mut [Message],
+    executor: &DatabaseConnection,
+) -> Result<(), MessageError> {
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    attach_profiles(messages, executor).await?;
+    Reaction::attach(messages, executor)
+        .await
+        .map_err(ModelError::from)?;
+    attach_snapshots(messages, executor).await?;
+
+    Ok(())
+}
+
+/// Set the member profile of authors in the server of the channel
+async fn attach_profiles(
+    messages: &mut [Message],
+    executor: &DatabaseConnection,
+) -> Result<(), ModelError> {
+    let mut channels = messages
+        .iter()
+        .map(|message| message.channel_id.id.clone())
+        .collect::<Vec<String>>();
+    channels.sort_unstable();
+    channels.dedup();
+
+    let servers: HashMap<String, String> = entity::channels::Entity::find()
+        .select_only()
+        .column(entity::channels::Column::Id)
+        .column(entity::channels::Column::ServerId)
+        .filter(entity::channels::Column::Id.is_in(channels))
+        .into_tuple::<(String, String)>()
+        .all(executor)
+        .await?
+        .into_iter()
+        .collect();
+
+    let authors = messages
+        .iter()
+        .filter_map(|message| message.author_id.id.0.get_id_cloned().ok())
+        .collect::<Vec<u32>>();
+
+    let profiles: HashMap<(String, u32), MemberProfile> = entity::members::Entity::find()
+        .filter(entity::members::Column::Serverid.is_in(servers.values().cloned()))
+        .filter(entity::members::Column::Userid.is_in(authors))
+        .all(executor)
+        .await?
+        .into_iter()
+        .map(|member| {
+            (
+                (member.serverid.clone(), member.userid),
+                member.to_profile(),
+            )
+        })
+        .collect();
+
+    for message in messages.iter_mut() {
+        let (Some(server), Ok(author)) = (
+            servers.get(&message.channel_id.id),
+            message.author_id.id.0.get_id_cloned(),
+        ) else {
+            continue;
+        };
+
+        message.author_member = profiles.get(&(server.clone(), author)).cloned();
+    }
+
+    Ok(())
+}
+
+/// Set a snapshot of replied messages, replies to a deleted message keep `None`
+async fn attach_snapshots(
+    messages: &mut [Message],
+    executor: &DatabaseConnection,
+) -> Result<(), ModelError> {
+    let replies = messages
+        .iter()
+        .filter_map(|message| message.reply_to.clone())
+        .collect::<Vec<String>>();
+
+    if replies.is_empty() {
+        return Ok(());
+    }
+
+    let models = entity::messages::Entity::find()
+        .filter(entity::messages::Column::Id.is_in(replies))
+        .all(executor)
+        .await?;
+
+    // Authors of the page are already loaded
+    let mut authors: HashMap<u32, User> = messages
+        .iter()
+        .filter_map(|message| {
+            let id = message.author_id.id.0.get_id_cloned().ok()?;
+            Some((id, message.author_id.clone()))
+        })
+        .collect();
+
+    let mut snapshots = HashMap::new();
+    for model in models {
+        let author = if let Some(author) = authors.get(&model.author_id) {
+            author.clone()
+        } else {
+            let author = User::by_id(model.author_id, executor).await?;
+            authors.insert(model.author_id, author.clone());
+            author
+        };
+
+        snapshots.insert(
+            model.id.clone(),
+            MessageSnapshot::new(
+                model.id,
+                &model.content.unwrap_or_default(),
+                MessageType::from_string(&model.message_type)?,
+                author,
+            ),
+        );
+    }
+
+    for message in messages.iter_mut() {
+        message.referenced_message = message
+            .reply_to
+            .as_ref()
+            .and_then(|reply_to| snapshots.get(reply_to))
+            .cloned()
+            .map(Box::new);
+    }
+
+    Ok(())
+}
+
+/// Table of messages which can be paginated with a `MessageQuery`
+pub trait MessagesTable: EntityTrait {
+    fn timestamp_column() -> Self::Column;
+    fn id_column() -> Self::Column;
+    fn cursor_of(model: &Self::Model) -> (NaiveDateTime, String);
+}
+
+impl MessagesTable for entity::messages::Entity {
+    fn timestamp_column() -> Self::Column {
+        entity::messages::Column::Timestamp
+    }
+
+    fn id_column() -> Self::Column {
+        entity::messages::Column::Id
+    }
+
+    fn cursor_of(model: &Self::Model) -> (NaiveDateTime, String) {
+        (model.timestamp, model.id.clone())
+    }
+}
+
+impl MessagesTable for entity::direct_message_messages::Entity {
+    fn timestamp_column() -> Self::Column {
+        entity::direct_message_messages::Column::Timestamp
+    }
+
+    fn id_column() -> Self::Column {
+        entity::direct_message_messages::Column::Id
+    }
+
+    fn cursor_of(model: &Self::Model) -> (NaiveDateTime, String) {
+        (model.timestamp, model.id.clone())
+    }
+}
+
+enum Direction {
+    Older,
+    Newer,
+}
+
+/// Return the page of models of `E` matching `filter` selected by `query`
+/// with the cursor of the next page.
+///
+/// # Errors
+/// Return an error if:
+/// * cursor isn't a message matching `filter`
+/// * database is unreachable
+pub async fn paginate<E: MessagesTable>(
+    filter: Condition,
+    query: &MessageQuery,
+    executor: &DatabaseConnection,
+) -> Result<(Vec<E::Model>, Option<String>), MessageError> {
+    let result = match &query.cursor {
+        MessageCursor::Latest => {
+            fetch_page::<E>(filter, None, Direction::Older, query.limit, executor).await?
+        }
+        MessageCursor::Before(id) => {
+            let cursor = cursor_model::<E>(filter.clone(), id, executor).await?;
+            fetch_page::<E>(
+                filter,
+                Some(E::cursor_of(&cursor)),
+                Direction::Older,
+                query.limit,
+                executor,
+            )
+            .await?
+        }
+        MessageCursor::After(id) => {
+            let cursor = cursor_model::<E>(filter.clone(), id, executor).await?;
+            fetch_page::<E>(
+                filter,
+                Some(E::cursor_of(&cursor)),
+                Direction::Newer,
+                query.limit,
+                executor,
+            )
+            .await?
+        }
+        MessageCursor::Around(id) => {
+            let cursor = cursor_model::<E>(filter.clone(), id, executor).await?;
+            let older_limit = query.limit / 2;
+            let newer_limit = query.limit - older_limit - 1;
+
+            // Next cursor of an around page points to older messages
+            let (mut models, next) = fetch_page::<E>(
+                filter.clone(),
+                Some(E::cursor_of(&cursor)),
+                Direction::Older,
+                older_limit,
+                executor,
+            )
+            .await?;
+
+            let (newer, _) = fetch_page::<E>(
+                filter,
+                Some(E::cursor_of(&cursor)),
+                Direction::Newer,
+                newer_limit,
+                executor,
+            )
+            .await?;
+
+            models.push(cursor);
+            models.extend(newer);
+
+            (models, next)
+        }
+    };
+
+    Ok(result)
+}
+
+/// Get the model used as cursor and check that it matches the filter
+async fn cursor_model<E: MessagesTable>(
+    filter: Condition,
+    message_id: &str,
+    executor: &DatabaseConnection,
+) -> Result<E::Model, MessageError> {
+    E::find()
+        .filter(filter)
+        .filter(E::id_column().eq(message_id))
+        .one(executor)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            MessageError::CannotGetById
+        })?
+        .ok_or(MessageError::CannotGetById)
+}
+
+/// Fetch `limit` models older or newer than the cursor.
+///
+/// Models are ordered by (timestamp, id) so messages sent in the same second
+/// are never skipped. Returned models are sorted from the oldest to the newest
+/// with the cursor of the next page if more models are available in this direction.
+async fn fetch_page<E: MessagesTable>(
+    filter: Condition,
+    cursor: Option<(NaiveDateTime, String)>,
+    direction: Direction,
+    limit: u64,
+    executor: &DatabaseConnection,
+) -> Result<(Vec<E::Model>, Option<String>), MessageError> {
+    if limit == 0 {
+        return Ok((Vec::new(), None));
+    }
+
+    let mut query = E::find().filter(filter);
+
+    let order = match direction {
+        Direction::Older => sea_orm::Order::Desc,
+        Direction::Newer => sea_orm::Order::Asc,
+    };
+
+    if let Some((timestamp, id)) = cursor {
+        let (after_timestamp, after_id) = match direction {
+            Direction::Older => (
+                E::timestamp_column().lt(timestamp),
+                E::id_column().lt(id.as_str()),
+            ),
+            Direction::Newer => (
+                E::timestamp_column().gt(timestamp),
+                E::id_column().gt(id.as_str()),
+            ),
+        };
+
+        query = query.filter(
+            Condition::any().add(after_timestamp).add(
+                Condition::all()
+                    .add(E::timestamp_column().eq(timestamp))
+                    .add(after_id),
+            ),
+        );
+    }
+
+    let mut models = query
+        .order_by(E::timestamp_column(), order.clone())
+        .order_by(E::id_column(), order)
+        .limit(limit + 1)
+        .all(executor)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            MessageError::CannotGetById
+        })?;
+
+    let more = models.len() as u64 > limit;
+    models.truncate(limit as usize);
+
+    // Before reordering, the last model is the farthest from the cursor
+    let next = if more {
+        models.last().map(|i| E::cursor_of(i).1)
+    } else {
+        None
+    };
+
+    if matches!(direction, Direction::Older) {
+        models.reverse();
+    }
+
+    Ok((models, next))
+}
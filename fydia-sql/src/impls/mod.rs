@@ -4,15 +4,21 @@ use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, InsertResult, I
 use shared::sea_orm;
 use shared::sea_orm::Iterable;
 
+pub mod ban;
 pub mod basic_model;
 pub mod channel;
 pub mod direct_message;
 pub mod emoji;
+pub mod invite;
 pub mod members;
 pub mod message;
 pub mod permission;
+pub mod pin;
+pub mod reaction;
+pub mod read_state;
 pub mod role;
 pub mod server;
+pub mod thread;
 pub mod token;
 pub mod user;
 
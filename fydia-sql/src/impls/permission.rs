@@ -4,12 +4,13 @@ use super::{
     basic_model::BasicModel,
     channel::SqlChannelId,
     delete, get_set_column, insert,
+    role::SqlRoles,
     user::{SqlUser, UserFrom},
 };
 use fydia_struct::{
     channel::ChannelId,
     permission::{Permission, PermissionError, Permissions},
-    roles::RoleId,
+    roles::{Role, RoleId},
     server::ServerId,
     sqlerror::{GenericError, GenericSqlError},
     user::UserId,
@@ -31,6 +32,8 @@ pub trait PermissionSql {
         user: &UserId,
         db: &DatabaseConnection,
     ) -> Result<Permission, PermissionError>;
+    /// Return server permissions of user roles with overwrites of the channel
+    /// for these roles and for the user
     async fn of_user_with_role_in_channel(
         channelid: &ChannelId,
         user: &UserId,
@@ -58,7 +61,11 @@ impl PermissionSql for Permission {
         db: &DatabaseConnection,
     ) -> Result<Permissions, PermissionError> {
         let user = user.to_user(db).await?;
-        let roles = user.roles(serverid, db).await?;
+        let mut roles = user.roles(serverid, db).await?;
+        if let Ok(everyone) = Role::everyone_by_server_id(serverid, db).await {
+            roles.push(everyone);
+        }
+
         let mut vec = Vec::new();
 
         for role in roles {
@@ -109,11 +116,16 @@ impl PermissionSql for Permission {
     ) -> Result<Permissions, PermissionError> {
         let channel = channelid.channel(db).await?;
         let user = user.to_user(db).await?;
-        let roles = user.roles(&channel.parent_id, db).await?;
+        let mut roles = user.roles(&channel.parent_id, db).await?;
+        if let Ok(everyone) = Role::everyone_by_server_id(&channel.parent_id, db).await {
+            roles.push(everyone);
+        }
 
         let mut vec = Vec::new();
 
         for i in &roles {
+            vec.push(Permission::role(i.id.clone(), None, i.server_permission));
+
             if let Ok(perm) = Self::of_role_in_channel(channelid, &i.id, db).await {
                 vec.push(perm);
             }
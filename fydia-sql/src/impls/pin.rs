@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use super::{basic_model::BasicModel, message::attach_details};
+use entity::pins::{ActiveModel, Column, Entity};
+use fydia_struct::{
+    channel::ChannelId,
+    messages::{Date, Message},
+    pin::{Pin, PinError},
+    user::UserId,
+};
+use fydia_utils::async_trait;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use shared::sea_orm;
+
+#[async_trait::async_trait]
+pub trait SqlPin {
+    /// Return pins of a channel, most recently pinned first
+    async fn of_channel(
+        channel_id: &ChannelId,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Pin>, PinError>;
+    /// Insert a pin if message isn't pinned and channel has less than `max` pins
+    async fn insert(&self, max: u64, executor: &DatabaseConnection) -> Result<(), PinError>;
+    async fn delete(message_id: &str, executor: &DatabaseConnection) -> Result<(), PinError>;
+}
+
+#[async_trait::async_trait]
+impl SqlPin for Pin {
+    async fn of_channel(
+        channel_id: &ChannelId,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Pin>, PinError> {
+        let models = Entity::find()
+            .filter(Column::ChannelId.eq(channel_id.id.as_str()))
+            .order_by_desc(Column::PinnedAt)
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                PinError::CannotGet
+            })?;
+
+        let message_models = entity::messages::Entity::find()
+            .filter(
+                entity::messages::Column::Id
+                    .is_in(models.iter().map(|model| model.message_id.as_str())),
+            )
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                PinError::CannotGet
+            })?;
+
+        let mut messages = Vec::with_capacity(message_models.len());
+        for model in message_models {
+            messages.push(model.to_struct(executor).await.map_err(|error| {
+                error!("{error}");
+                PinError::CannotGet
+            })?);
+        }
+
+        attach_details(&mut messages, executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                PinError::CannotGet
+            })?;
+
+        let mut messages = messages
+            .into_iter()
+            .map(|message| (message.id.clone(), message))
+            .collect::<HashMap<String, Message>>();
+
+        let mut pins = Vec::with_capacity(models.len());
+        for model in models {
+            let message = messages
+                .remove(&model.message_id)
+                .ok_or(PinError::CannotGet)?;
+
+            pins.push(Pin {
+                message: Box::new(message),
+                pinned_by: UserId::new(model.pinned_by),
+                pinned_at: Date::parse_from_naivetime(model.pinned_at),
+            });
+        }
+
+        Ok(pins)
+    }
+
+    async fn insert(&self, max: u64, executor: &DatabaseConnection) -> Result<(), PinError> {
+        let model = ActiveModel {
+            message_id: Set(self.message.id.clone()),
+            channel_id: Set(self.message.channel_id.id.clone()),
+            pinned_by: Set(self.pinned_by.0.get_id_cloned()?),
+            pinned_at: Set(self.pinned_at.0.naive_utc()),
+        };
+
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{error}");
+            PinError::CannotInsert
+        })?;
+
+        let pinned = Entity::find_by_id(self.message.id.clone())
+            .one(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                PinError::CannotGet
+            })?
+            .is_some();
+
+        if pinned {
+            return Err(PinError::AlreadyPinned);
+        }
+
+        let count = Entity::find()
+            .filter(Column::ChannelId.eq(self.message.channel_id.id.as_str()))
+            .count(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                PinError::CannotGet
+            })?;
+
+        if count >= max {
+            return Err(PinError::TooManyPins);
+        }
+
+        Entity::insert(model)
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                PinError::CannotInsert
+            })?;
+
+        transaction.commit().await.map_err(|error| {
+            error!("{error}");
+            PinError::CannotInsert
+        })
+    }
+
+    async fn delete(message_id: &str, executor: &DatabaseConnection) -> Result<(), PinError> {
+        let result = Entity::delete_by_id(message_id.to_string())
+            .exec(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                PinError::CannotDelete
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(PinError::NotPinned);
+        }
+
+        Ok(())
+    }
+}
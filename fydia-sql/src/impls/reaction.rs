@@ -0,0 +1,189 @@
+use entity::reactions::{emoji_key, Column, Entity};
+use fydia_struct::{
+    messages::{Date, Message},
+    reaction::{Reaction, ReactionEmoji, ReactionError},
+    user::UserId,
+};
+use fydia_utils::async_trait;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set};
+use shared::sea_orm;
+
+#[async_trait::async_trait]
+pub trait SqlReaction {
+    /// Set reactions of messages counted by emoji, in order of their first use
+    async fn attach(
+        messages: &mut [Message],
+        executor: &DatabaseConnection,
+    ) -> Result<(), ReactionError>;
+    /// Set `me` on reactions of messages that user added
+    async fn mark_own(
+        messages: &mut [Message],
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<(), ReactionError>;
+    async fn add(
+        message_id: &str,
+        userid: &UserId,
+        emoji: &ReactionEmoji,
+        executor: &DatabaseConnection,
+    ) -> Result<(), ReactionError>;
+    async fn remove(
+        message_id: &str,
+        userid: &UserId,
+        emoji: &ReactionEmoji,
+        executor: &DatabaseConnection,
+    ) -> Result<(), ReactionError>;
+}
+
+#[async_trait::async_trait]
+impl SqlReaction for Reaction {
+    async fn attach(
+        messages: &mut [Message],
+        executor: &DatabaseConnection,
+    ) -> Result<(), ReactionError> {
+        let ids = messages
+            .iter()
+            .map(|message| message.id.clone())
+            .collect::<Vec<String>>();
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let models = Entity::find()
+            .filter(Column::MessageId.is_in(ids))
+            .order_by_asc(Column::CreatedAt)
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                ReactionError::CannotGet
+            })?;
+
+        for model in models {
+            let emoji = model.to_emoji();
+            for message in messages
+                .iter_mut()
+                .filter(|message| message.id == model.message_id)
+            {
+                match message
+                    .reactions
+                    .iter_mut()
+                    .find(|reaction| reaction.emoji == emoji)
+                {
+                    Some(reaction) => reaction.count += 1,
+                    None => message.reactions.push(Reaction {
+                        emoji: emoji.clone(),
+                        count: 1,
+                        me: false,
+                    }),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn mark_own(
+        messages: &mut [Message],
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<(), ReactionError> {
+        let ids = messages
+            .iter()
+            .filter(|message| !message.reactions.is_empty())
+            .map(|message| message.id.clone())
+            .collect::<Vec<String>>();
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let models = Entity::find()
+            .filter(Column::MessageId.is_in(ids))
+            .filter(Column::UserId.eq(userid.0.get_id_cloned()?))
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                ReactionError::CannotGet
+            })?;
+
+        for model in models {
+            let emoji = model.to_emoji();
+            if let Some(reaction) = messages
+                .iter_mut()
+                .filter(|message| message.id == model.message_id)
+                .flat_map(|message| message.reactions.iter_mut())
+                .find(|reaction| reaction.emoji == emoji)
+            {
+                reaction.me = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn add(
+        message_id: &str,
+        userid: &UserId,
+        emoji: &ReactionEmoji,
+        executor: &DatabaseConnection,
+    ) -> Result<(), ReactionError> {
+        let key = emoji_key(emoji)?;
+        let user_id = userid.0.get_id_cloned()?;
+
+        let exists = Entity::find_by_id((message_id.to_string(), user_id, key.clone()))
+            .one(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                ReactionError::CannotGet
+            })?
+            .is_some();
+
+        if exists {
+            return Err(ReactionError::AlreadyReacted);
+        }
+
+        Entity::insert(entity::reactions::ActiveModel {
+            message_id: Set(message_id.to_string()),
+            user_id: Set(user_id),
+            emoji: Set(key),
+            created_at: Set(Date::now().0.naive_utc()),
+        })
+        .exec(executor)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            ReactionError::CannotInsert
+        })?;
+
+        Ok(())
+    }
+
+    async fn remove(
+        message_id: &str,
+        userid: &UserId,
+        emoji: &ReactionEmoji,
+        executor: &DatabaseConnection,
+    ) -> Result<(), ReactionError> {
+        let result = Entity::delete_by_id((
+            message_id.to_string(),
+            userid.0.get_id_cloned()?,
+            emoji_key(emoji)?,
+        ))
+        .exec(executor)
+        .await
+        .map_err(|error| {
+            error!("{error}");
+            ReactionError::CannotDelete
+        })?;
+
+        if result.rows_affected == 0 {
+            return Err(ReactionError::NoReaction);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use std::convert::TryFrom;
+
+use fydia_struct::{
+    channel::ChannelId,
+    messages::{MessageError, ReadState},
+    user::UserId,
+};
+use fydia_utils::async_trait;
+use migration::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use shared::sea_orm;
+
+#[async_trait::async_trait]
+pub trait SqlReadState {
+    async fn of_user_in_channel(
+        userid: &UserId,
+        channelid: &ChannelId,
+        executor: &DatabaseConnection,
+    ) -> Result<Option<ReadState>, MessageError>;
+    async fn upsert(&self, executor: &DatabaseConnection) -> Result<(), MessageError>;
+}
+
+#[async_trait::async_trait]
+impl SqlReadState for ReadState {
+    async fn of_user_in_channel(
+        userid: &UserId,
+        channelid: &ChannelId,
+        executor: &DatabaseConnection,
+    ) -> Result<Option<ReadState>, MessageError> {
+        let model = entity::read_states::Entity::find()
+            .filter(entity::read_states::Column::UserId.eq(userid.0.get_id_cloned()?))
+            .filter(entity::read_states::Column::ChannelId.eq(channelid.id.as_str()))
+            .one(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                MessageError::CannotGetById
+            })?;
+
+        Ok(model
+            .as_ref()
+            .map(entity::read_states::Model::to_read_state))
+    }
+
+    async fn upsert(&self, executor: &DatabaseConnection) -> Result<(), MessageError> {
+        let active_model = entity::read_states::ActiveModel::try_from(self)?;
+
+        entity::read_states::Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([
+                    entity::read_states::Column::UserId,
+                    entity::read_states::Column::ChannelId,
+                ])
+                .update_columns([
+                    entity::read_states::Column::MessageId,
+                    entity::read_states::Column::UpdatedAt,
+                ])
+                .to_owned(),
+            )
+            .exec(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                MessageError::CannotUpdateReadState
+            })?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use entity::roles::assignation;
 use fydia_struct::{
+    permission::PermissionValue,
     roles::{Role, RoleError},
     server::ServerId,
     sqlerror::GenericSqlError,
@@ -9,7 +10,10 @@ use fydia_struct::{
 
 use super::{delete, from_dberr_and_activemodel, get_set_column, insert};
 use fydia_utils::async_trait;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
 use shared::sea_orm;
 #[async_trait::async_trait]
 pub trait SqlRoles {
@@ -22,6 +26,10 @@ pub trait SqlRoles {
         shortid: &ServerId,
         executor: &DatabaseConnection,
     ) -> Result<Role, RoleError>;
+    async fn everyone_by_server_id(
+        shortid: &ServerId,
+        executor: &DatabaseConnection,
+    ) -> Result<Role, RoleError>;
     async fn insert(&mut self, executor: &DatabaseConnection) -> Result<(), RoleError>;
     async fn update_name<'a, T: Into<String> + Send>(
         &mut self,
@@ -33,12 +41,28 @@ pub trait SqlRoles {
         color: T,
         executor: &DatabaseConnection,
     ) -> Result<(), RoleError>;
+    async fn update_permission(
+        &mut self,
+        server_permission: u64,
+        executor: &DatabaseConnection,
+    ) -> Result<(), RoleError>;
     async fn delete(&self, executor: &DatabaseConnection) -> Result<(), RoleError>;
+    /// Save positions of roles in one transaction
+    async fn update_positions(
+        roles: &[Role],
+        executor: &DatabaseConnection,
+    ) -> Result<(), RoleError>;
     async fn add_user(
         &self,
         userid: &UserId,
         executor: &DatabaseConnection,
     ) -> Result<(), RoleError>;
+    async fn remove_user(
+        &self,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<(), RoleError>;
+    async fn users(&self, executor: &DatabaseConnection) -> Result<Vec<UserId>, RoleError>;
 }
 
 #[async_trait::async_trait]
@@ -50,6 +74,7 @@ impl SqlRoles for Role {
         let mut result = Vec::new();
         let query = entity::roles::Entity::find()
             .filter(entity::roles::Column::Serverid.eq(shortid))
+            .order_by_asc(entity::roles::Column::Position)
             .all(executor)
             .await;
 
@@ -83,22 +108,42 @@ impl SqlRoles for Role {
         }
     }
 
+    async fn everyone_by_server_id(
+        shortid: &ServerId,
+        executor: &DatabaseConnection,
+    ) -> Result<Self, RoleError> {
+        let query = entity::roles::Entity::find()
+            .filter(entity::roles::Column::Serverid.eq(shortid.id.as_str()))
+            .filter(entity::roles::Column::Position.eq(0))
+            .one(executor)
+            .await;
+
+        match query {
+            Ok(Some(model)) => Ok(model.to_role()),
+            Err(e) => {
+                error!("{}", e.to_string());
+                Err(RoleError::NoRoleWithId)
+            }
+            _ => Err(RoleError::NoRoleWithId),
+        }
+    }
+
     async fn update_name<'a, T: Into<String> + Send>(
         &mut self,
         name: T,
         executor: &DatabaseConnection,
     ) -> Result<(), RoleError> {
         let name = name.into();
+        let id = self.id.get_id_cloned()?;
         let active_model = entity::roles::ActiveModel {
+            id: Set(id),
             name: Set(name.clone()),
             ..Default::default()
         };
-
-        let id = self.id.get_id_cloned()?;
         let set_column = get_set_column(&active_model);
 
         entity::roles::Entity::update(active_model)
-            .filter(entity::messages::Column::Id.eq(id))
+            .filter(entity::roles::Column::Id.eq(id))
             .exec(executor)
             .await
             .map_err(|f| {
@@ -116,16 +161,17 @@ impl SqlRoles for Role {
         executor: &DatabaseConnection,
     ) -> Result<(), RoleError> {
         let color = color.into();
+        let id = self.id.get_id_cloned()?;
         let active_model = entity::roles::ActiveModel {
+            id: Set(id),
             color: Set(color.clone()),
             ..Default::default()
         };
-        let id = self.id.get_id_cloned()?;
 
         let set_column = get_set_column(&active_model);
 
         entity::roles::Entity::update(active_model)
-            .filter(entity::messages::Column::Id.eq(id))
+            .filter(entity::roles::Column::Id.eq(id))
             .exec(executor)
             .await
             .map_err(|f| {
@@ -136,10 +182,75 @@ impl SqlRoles for Role {
 
         Ok(())
     }
+    async fn update_permission(
+        &mut self,
+        server_permission: u64,
+        executor: &DatabaseConnection,
+    ) -> Result<(), RoleError> {
+        let id = self.id.get_id_cloned()?;
+        let active_model = entity::roles::ActiveModel {
+            id: Set(id),
+            server_permission: Set(PermissionValue::to_database(server_permission)),
+            ..Default::default()
+        };
+
+        let set_column = get_set_column(&active_model);
+
+        entity::roles::Entity::update(active_model)
+            .filter(entity::roles::Column::Id.eq(id))
+            .exec(executor)
+            .await
+            .map_err(|f| {
+                GenericSqlError::CannotUpdate(from_dberr_and_activemodel(set_column, &f))
+            })?;
+
+        self.server_permission = server_permission;
+
+        Ok(())
+    }
+
     async fn insert(&mut self, executor: &DatabaseConnection) -> Result<(), RoleError> {
         let model = entity::roles::ActiveModel::from(self.clone());
 
-        let result = insert(model, executor).await?;
+        if self.is_everyone() {
+            let result = insert(model, executor).await?;
+            self.id = Id::Id(result.last_insert_id);
+
+            return Ok(());
+        }
+
+        // Roles at this position and above move up to make room
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{error}");
+            RoleError::CannotInsert
+        })?;
+
+        entity::roles::Entity::update_many()
+            .col_expr(
+                entity::roles::Column::Position,
+                Expr::col(entity::roles::Column::Position).add(1),
+            )
+            .filter(entity::roles::Column::Serverid.eq(self.server_id.id.as_str()))
+            .filter(entity::roles::Column::Position.gte(self.position))
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                RoleError::CannotInsert
+            })?;
+
+        let result = entity::roles::Entity::insert(model)
+            .exec(&transaction)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                RoleError::CannotInsert
+            })?;
+
+        transaction.commit().await.map_err(|error| {
+            error!("{error}");
+            RoleError::CannotInsert
+        })?;
 
         self.id = Id::Id(result.last_insert_id);
 
@@ -147,18 +258,77 @@ impl SqlRoles for Role {
     }
     async fn delete(&self, executor: &DatabaseConnection) -> Result<(), RoleError> {
         let id = self.id.get_id_cloned()?;
+        let cannot_delete = |error: DbErr| {
+            error!("{error}");
+            RoleError::CannotDelete
+        };
 
-        let Ok(Some(model)) = entity::roles::Entity::find_by_id(id)
-            .one(executor)
-            .await else {
-                return Err(RoleError::CannotDelete);
-            };
+        let transaction = executor.begin().await.map_err(cannot_delete)?;
 
-        let active_model: entity::roles::ActiveModel = model.into();
+        assignation::Entity::delete_many()
+            .filter(assignation::Column::RoleId.eq(id))
+            .exec(&transaction)
+            .await
+            .map_err(cannot_delete)?;
 
-        delete(active_model, executor).await?;
+        entity::permission::role::Entity::delete_many()
+            .filter(entity::permission::role::Column::Role.eq(id))
+            .exec(&transaction)
+            .await
+            .map_err(cannot_delete)?;
 
-        Ok(())
+        let deleted = entity::roles::Entity::delete_many()
+            .filter(entity::roles::Column::Id.eq(id))
+            .filter(entity::roles::Column::Serverid.eq(self.server_id.id.as_str()))
+            .exec(&transaction)
+            .await
+            .map_err(cannot_delete)?;
+
+        if deleted.rows_affected == 0 {
+            return Err(RoleError::CannotDelete);
+        }
+
+        // Keep positions without gaps
+        entity::roles::Entity::update_many()
+            .col_expr(
+                entity::roles::Column::Position,
+                Expr::col(entity::roles::Column::Position).sub(1),
+            )
+            .filter(entity::roles::Column::Serverid.eq(self.server_id.id.as_str()))
+            .filter(entity::roles::Column::Position.gt(self.position))
+            .exec(&transaction)
+            .await
+            .map_err(cannot_delete)?;
+
+        transaction.commit().await.map_err(cannot_delete)
+    }
+
+    async fn update_positions(
+        roles: &[Role],
+        executor: &DatabaseConnection,
+    ) -> Result<(), RoleError> {
+        let transaction = executor.begin().await.map_err(|error| {
+            error!("{error}");
+            RoleError::CannotReorder
+        })?;
+
+        for role in roles {
+            entity::roles::Entity::update_many()
+                .col_expr(entity::roles::Column::Position, Expr::value(role.position))
+                .filter(entity::roles::Column::Id.eq(role.id.get_id_cloned()?))
+                .filter(entity::roles::Column::Serverid.eq(role.server_id.id.as_str()))
+                .exec(&transaction)
+                .await
+                .map_err(|error| {
+                    error!("{error}");
+                    RoleError::CannotReorder
+                })?;
+        }
+
+        transaction.commit().await.map_err(|error| {
+            error!("{error}");
+            RoleError::CannotReorder
+        })
     }
 
     async fn add_user(
@@ -176,4 +346,37 @@ impl SqlRoles for Role {
 
         Ok(())
     }
+
+    async fn remove_user(
+        &self,
+        userid: &UserId,
+        executor: &DatabaseConnection,
+    ) -> Result<(), RoleError> {
+        let am = assignation::ActiveModel {
+            role_id: Set(self.id.get_id_cloned()?),
+            user_id: Set(userid.0.get_id_cloned()?),
+            server_id: Set(self.server_id.id.clone()),
+        };
+
+        delete(am, executor).await.map_err(|error| {
+            error!("{error}");
+            RoleError::CannotRemoveUser
+        })
+    }
+
+    async fn users(&self, executor: &DatabaseConnection) -> Result<Vec<UserId>, RoleError> {
+        let models = assignation::Entity::find()
+            .filter(assignation::Column::RoleId.eq(self.id.get_id_cloned()?))
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                RoleError::NoRoleWithId
+            })?;
+
+        Ok(models
+            .iter()
+            .map(|model| UserId::new(model.user_id))
+            .collect())
+    }
 }
@@ -1,17 +1,24 @@
 use entity::server::Model;
 use fydia_struct::{
     channel::{Channel, ChannelError},
+    file::File,
+    messages::MessageType,
+    roles::Role,
     server::{Channels, Members, MembersError, Server, ServerError, ServerId},
     user::{User, UserId},
 };
 use fydia_utils::async_trait;
-use sea_orm::{DatabaseConnection, Set};
+use migration::Query;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect, Set,
+    TransactionTrait,
+};
 use shared::sea_orm;
 use std::convert::TryFrom;
 
 use super::{
-    basic_model::BasicModel, channel::SqlChannel, delete, insert, members::SqlMembers, update,
-    user::UserFrom,
+    basic_model::BasicModel, channel::SqlChannel, insert, members::SqlMembers, role::SqlRoles,
+    update, user::UserFrom,
 };
 
 #[async_trait::async_trait]
@@ -20,7 +27,10 @@ pub trait SqlServer {
     async fn by_id(id: &ServerId, executor: &DatabaseConnection) -> Result<Server, ServerError>;
     async fn channels(&self, executor: &DatabaseConnection) -> Result<Channels, ServerError>;
     async fn insert(&mut self, executor: &DatabaseConnection) -> Result<(), ServerError>;
-    async fn delete(&self, executor: &DatabaseConnection) -> Result<(), ServerError>;
+    async fn delete(
+        &self,
+        executor: &DatabaseConnection,
+    ) -> Result<(Vec<File>, Vec<String>), ServerError>;
     async fn update_name<'a, T: Into<String> + Send>(
         &mut self,
         name: T,
@@ -68,15 +78,193 @@ impl SqlServer for Server {
 
         insert(active_channel, executor).await?;
 
+        Role::everyone(self.id.clone())
+            .insert(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                ServerError::CannotInsertEveryone
+            })?;
+
         self.join(&mut user, executor).await
     }
 
-    async fn delete(&self, executor: &DatabaseConnection) -> Result<(), ServerError> {
-        let active_channel = entity::server::ActiveModel::try_from(self.clone())?;
-
-        delete(active_channel, executor).await?;
-
-        Ok(())
+    /// Delete the server with its channels, messages, roles, permissions, members,
+    /// invites, bans, emojis, reactions, pins and message revisions in a single transaction.
+    ///
+    /// Return attachments of deleted messages and pictures of member avatars and emojis,
+    /// they have to be removed from the storage.
+    async fn delete(
+        &self,
+        executor: &DatabaseConnection,
+    ) -> Result<(Vec<File>, Vec<String>), ServerError> {
+        use entity::{
+            bans, channels, emojis, invites, members, message_revisions, messages, permission,
+            pins, reactions, read_states, roles, server,
+        };
+
+        let serverid = self.id.id.as_str();
+        let transaction = executor
+            .begin()
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        let channels: Vec<String> = channels::Entity::find()
+            .select_only()
+            .column(channels::Column::Id)
+            .filter(channels::Column::ServerId.eq(serverid))
+            .into_tuple()
+            .all(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        let files = messages::Entity::find()
+            .filter(messages::Column::ChannelId.is_in(channels.clone()))
+            .filter(messages::Column::MessageType.eq(MessageType::FILE.to_string()))
+            .all(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?
+            .into_iter()
+            .filter_map(|message| message.content.map(File::get))
+            .collect();
+
+        reactions::Entity::delete_many()
+            .filter(
+                reactions::Column::MessageId.in_subquery(
+                    Query::select()
+                        .column(messages::Column::Id)
+                        .from(messages::Entity)
+                        .and_where(messages::Column::ChannelId.is_in(channels.clone()))
+                        .to_owned(),
+                ),
+            )
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        message_revisions::Entity::delete_many()
+            .filter(
+                message_revisions::Column::MessageId.in_subquery(
+                    Query::select()
+                        .column(messages::Column::Id)
+                        .from(messages::Entity)
+                        .and_where(messages::Column::ChannelId.is_in(channels.clone()))
+                        .to_owned(),
+                ),
+            )
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        pins::Entity::delete_many()
+            .filter(pins::Column::ChannelId.is_in(channels.clone()))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        messages::Entity::delete_many()
+            .filter(messages::Column::ChannelId.is_in(channels.clone()))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        read_states::Entity::delete_many()
+            .filter(read_states::Column::ChannelId.is_in(channels.clone()))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        permission::role::Entity::delete_many()
+            .filter(permission::role::Column::Channel.is_in(channels.clone()))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        permission::user::Entity::delete_many()
+            .filter(permission::user::Column::Channel.is_in(channels))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        channels::Entity::delete_many()
+            .filter(channels::Column::ServerId.eq(serverid))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        roles::assignation::Entity::delete_many()
+            .filter(roles::assignation::Column::ServerId.eq(serverid))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        roles::Entity::delete_many()
+            .filter(roles::Column::Serverid.eq(serverid))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        let avatars: Vec<Option<String>> = members::Entity::find()
+            .select_only()
+            .column(members::Column::Avatar)
+            .filter(members::Column::Serverid.eq(serverid))
+            .into_tuple()
+            .all(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        members::Entity::delete_many()
+            .filter(members::Column::Serverid.eq(serverid))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        invites::uses::Entity::delete_many()
+            .filter(invites::uses::Column::ServerId.eq(serverid))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        invites::Entity::delete_many()
+            .filter(invites::Column::ServerId.eq(serverid))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        bans::Entity::delete_many()
+            .filter(bans::Column::ServerId.eq(serverid))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        let emojis: Vec<String> = emojis::Entity::find()
+            .select_only()
+            .column(emojis::Column::Path)
+            .filter(emojis::Column::ServerId.eq(serverid))
+            .into_tuple()
+            .all(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        emojis::Entity::delete_many()
+            .filter(emojis::Column::ServerId.eq(serverid))
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        server::Entity::delete_by_id(serverid)
+            .exec(&transaction)
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|error| cannot_delete(&error))?;
+
+        let pictures = avatars.into_iter().flatten().chain(emojis).collect();
+
+        Ok((files, pictures))
     }
 
     async fn update_name<'a, T: Into<String> + Send>(
@@ -134,6 +322,11 @@ impl SqlServer for Server {
     }
 }
 
+fn cannot_delete(error: &DbErr) -> ServerError {
+    error!("{error}");
+    ServerError::CannotDelete
+}
+
 #[async_trait::async_trait]
 pub trait SqlServerId {
     async fn get(&self, executor: &DatabaseConnection) -> Result<Server, ServerError>;
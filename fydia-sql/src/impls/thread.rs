@@ -0,0 +1,229 @@
+use super::basic_model::BasicModel;
+use entity::channels::{ActiveModel, Column, Entity};
+use fydia_struct::{
+    channel::{Channel, ChannelError, ChannelId, ThreadMetadata, DEFAULT_AUTO_ARCHIVE},
+    messages::Date,
+};
+use fydia_utils::async_trait;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use shared::sea_orm;
+use std::convert::TryFrom;
+
+#[async_trait::async_trait]
+pub trait SqlThread {
+    /// Return threads of a channel, archived or active ones
+    async fn threads_of(
+        channel_id: &ChannelId,
+        archived: bool,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Channel>, ChannelError>;
+    /// Insert a thread if no thread started from the same message
+    async fn insert_thread(&self, executor: &DatabaseConnection) -> Result<(), ChannelError>;
+    /// Update archive state and minutes before archive of a thread
+    async fn update_thread(
+        &mut self,
+        archived: Option<bool>,
+        auto_archive: Option<u32>,
+        executor: &DatabaseConnection,
+    ) -> Result<(), ChannelError>;
+    /// Mark a new message in a thread, an archived thread is unarchived
+    async fn bump_thread(&mut self, executor: &DatabaseConnection) -> Result<(), ChannelError>;
+    /// Archive all threads inactive since their `auto_archive` and return the archived ones
+    async fn archive_inactive(executor: &DatabaseConnection) -> Result<Vec<Channel>, ChannelError>;
+}
+
+#[async_trait::async_trait]
+impl SqlThread for Channel {
+    async fn threads_of(
+        channel_id: &ChannelId,
+        archived: bool,
+        executor: &DatabaseConnection,
+    ) -> Result<Vec<Channel>, ChannelError> {
+        let models = Entity::find()
+            .filter(Column::ParentChannel.eq(channel_id.id.as_str()))
+            .filter(Column::Archived.eq(archived))
+            .order_by_desc(Column::LastActivity)
+            .all(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                ChannelError::CannotGetFromDatabase
+            })?;
+
+        let mut threads = Vec::new();
+        for model in models {
+            threads.push(model.to_struct(executor).await?);
+        }
+
+        Ok(threads)
+    }
+
+    async fn insert_thread(&self, executor: &DatabaseConnection) -> Result<(), ChannelError> {
+        let thread = self.thread.as_ref().ok_or(ChannelError::NotAThread)?;
+
+        let exists = Entity::find()
+            .filter(Column::ThreadMessage.eq(thread.message_id.as_str()))
+            .one(executor)
+            .await
+            .map_err(|error| {
+                error!("{error}");
+                ChannelError::CannotGetFromDatabase
+            })?
+            .is_some();
+
+        if exists {
+            return Err(ChannelError::ThreadExists);
+        }
+
+        super::insert(ActiveModel::try_from(self)?, executor).await?;
+
+        Ok(())
+    }
+
+    async fn update_thread(
+        &mut self,
+        archived: Option<bool>,
+        auto_archive: Option<u32>,
+        executor: &DatabaseConnection,
+    ) -> Result<(), ChannelError> {
+        let mut thread = self.thread.clone().ok_or(ChannelError::NotAThread)?;
+
+        if let Some(auto_archive) = auto_archive {
+            ThreadMetadata::check_auto_archive(auto_archive)?;
+            thread.auto_archive = auto_archive;
+        }
+
+        if let Some(archived) = archived {
+            // An unarchived thread has a new period of inactivity before being archived again
+            if thread.archived && !archived {
+                thread.last_activity = Date::now();
+            }
+            thread.archived = archived;
+        }
+
+        save_thread(&self.id, &thread, executor).await?;
+        self.thread = Some(thread);
+
+        Ok(())
+    }
+
+    async fn bump_thread(&mut self, executor: &DatabaseConnection) -> Result<(), ChannelError> {
+        let mut thread = self.thread.clone().ok_or(ChannelError::NotAThread)?;
+
+        thread.archived = false;
+        thread.last_activity = Date::now();
+
+        save_thread(&self.id, &thread, executor).await?;
+        self.thread = Some(thread);
+
+        Ok(())
+    }
+
+    async fn archive_inactive(executor: &DatabaseConnection) -> Result<Vec<Channel>, ChannelError> {
+        let cannot_archive = |error: DbErr| {
+            error!("{error}");
+            ChannelError::CannotUpdateThread
+        };
+
+        let now = Date::now();
+        let transaction = executor.begin().await.map_err(cannot_archive)?;
+
+        // Inactivity depends on `auto_archive`, threads are archived by value of it
+        let auto_archives: Vec<Option<u32>> = Entity::find()
+            .select_only()
+            .column(Column::AutoArchive)
+            .distinct()
+            .filter(Column::ParentChannel.is_not_null())
+            .filter(Column::Archived.eq(false))
+            .into_tuple()
+            .all(&transaction)
+            .await
+            .map_err(cannot_archive)?;
+
+        let mut ids = Vec::new();
+        for auto_archive in auto_archives {
+            let (same_auto_archive, minutes) = match auto_archive {
+                Some(minutes) => (Column::AutoArchive.eq(minutes), minutes),
+                None => (Column::AutoArchive.is_null(), DEFAULT_AUTO_ARCHIVE),
+            };
+
+            let inactive = Condition::all()
+                .add(Column::ParentChannel.is_not_null())
+                .add(Column::Archived.eq(false))
+                .add(same_auto_archive)
+                .add(
+                    Column::LastActivity
+                        .lte(ThreadMetadata::inactive_before(minutes, &now).0.naive_utc()),
+                );
+
+            let inactive_ids: Vec<String> = Entity::find()
+                .select_only()
+                .column(Column::Id)
+                .filter(inactive.clone())
+                .into_tuple()
+                .all(&transaction)
+                .await
+                .map_err(cannot_archive)?;
+
+            if inactive_ids.is_empty() {
+                continue;
+            }
+
+            // A thread bumped since the select doesn't match anymore and stays active
+            Entity::update_many()
+                .col_expr(Column::Archived, Expr::value(true))
+                .filter(inactive)
+                .filter(Column::Id.is_in(inactive_ids.clone()))
+                .exec(&transaction)
+                .await
+                .map_err(cannot_archive)?;
+
+            ids.extend(inactive_ids);
+        }
+
+        let models = Entity::find()
+            .filter(Column::Id.is_in(ids))
+            .filter(Column::Archived.eq(true))
+            .all(&transaction)
+            .await
+            .map_err(cannot_archive)?;
+
+        transaction.commit().await.map_err(cannot_archive)?;
+
+        let mut archived = Vec::with_capacity(models.len());
+        for model in models {
+            match model.to_struct(executor).await {
+                Ok(channel) => archived.push(channel),
+                Err(error) => error!("Cannot read archived thread {}: {error}", model.id),
+            }
+        }
+
+        Ok(archived)
+    }
+}
+
+/// Save archive state, minutes before archive and last activity of a thread
+async fn save_thread(
+    id: &ChannelId,
+    thread: &ThreadMetadata,
+    executor: &DatabaseConnection,
+) -> Result<(), ChannelError> {
+    Entity::update(ActiveModel {
+        id: Set(id.id.clone()),
+        archived: Set(thread.archived),
+        auto_archive: Set(Some(thread.auto_archive)),
+        last_activity: Set(Some(thread.last_activity.0.naive_utc())),
+        ..Default::default()
+    })
+    .exec(executor)
+    .await
+    .map_err(|error| {
+        error!("{error}");
+        ChannelError::CannotUpdateThread
+    })?;
+
+    Ok(())
+}
@@ -61,6 +61,11 @@ pub trait SqlUser {
         clear_password: &str,
         executor: &DatabaseConnection,
     ) -> Result<(), UserError>;
+    async fn update_avatar(
+        &mut self,
+        avatar: Option<String>,
+        executor: &DatabaseConnection,
+    ) -> Result<(), UserError>;
     async fn insert(mut self, executor: &DatabaseConnection) -> Result<User, UserError>;
     async fn delete(mut self, executor: &DatabaseConnection) -> Result<(), UserError>;
     async fn permission_of_channel(
@@ -187,6 +192,25 @@ impl SqlUser for User {
         Ok(())
     }
 
+    async fn update_avatar(
+        &mut self,
+        avatar: Option<String>,
+        executor: &DatabaseConnection,
+    ) -> Result<(), UserError> {
+        let mut active_model: UserActiveModel =
+            Model::get_model_by(&[Column::Id.eq(self.id.0.get_id_cloned()?)], executor)
+                .await?
+                .into();
+
+        active_model.avatar = Set(avatar.clone());
+
+        update(active_model, executor).await?;
+
+        self.avatar = avatar;
+
+        Ok(())
+    }
+
     async fn insert(mut self, executor: &DatabaseConnection) -> Result<Self, UserError> {
         if self.token.is_null() {
             self.token = Token::new(generate_string(30));
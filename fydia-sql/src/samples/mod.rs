@@ -1,7 +1,7 @@
 use fydia_struct::{
     channel::{Channel, ChannelId},
     instance::Instance,
-    messages::{Date, Message},
+    messages::{Date, Message, MessageQuery},
     roles::Role,
     server::{Server, ServerId},
     user::{Token, User},
@@ -79,8 +79,14 @@ pub async fn insert_samples(db: &DbConnection) -> Result<(), String> {
         }
     }
 
-    if let Ok(message) = Message::by_channel(ChannelId::new("channel_default_id"), db).await {
-        if message.len() < 5 {
+    if let Ok(page) = Message::by_channel(
+        ChannelId::new("channel_default_id"),
+        &MessageQuery::default(),
+        db,
+    )
+    .await
+    {
+        if page.messages.len() < 5 {
             for _ in 0..=5 {
                 let message = Message::new(
                     "Message",
@@ -103,8 +109,9 @@ pub async fn insert_samples(db: &DbConnection) -> Result<(), String> {
         id: Id::Unset,
         server_id: server.id,
         name: String::from("default_role"),
-        color: String::from("ffffff"),
+        color: String::from("#ffffff"),
         server_permission: 4,
+        position: 1,
     };
 
     role.insert(db).await.unwrap();
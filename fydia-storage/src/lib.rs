@@ -0,0 +1,144 @@
+//! Fydia-storage stores attachments, icons and avatars.
+//!
+//! Every file goes through a `StorageBackend` chosen from `StorageConfig`,
+//! so the router never touches the filesystem directly.
+#![warn(missing_debug_implementations, rust_2018_idioms, missing_docs)]
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+
+#[macro_use]
+extern crate log;
+
+pub mod local;
+pub mod s3;
+
+#[cfg(test)]
+mod test;
+
+use bytes::Bytes;
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
+use fydia_config::StorageConfig;
+use fydia_struct::file::ByteRange;
+use fydia_utils::async_trait::async_trait;
+use std::{fmt::Debug, sync::Arc};
+use thiserror::Error;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+/// Stream of bytes read from or written to a `StorageBackend`
+pub type ByteStream = BoxStream<'static, Result<Bytes, StorageError>>;
+
+/// `StorageMetadata` describes a stored object
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageMetadata {
+    pub size: u64,
+}
+
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+/// `StorageError` represents all errors of `StorageBackend`
+pub enum StorageError {
+    #[error("Object doesn't exist")]
+    NotFound,
+    #[error("Key isn't valid")]
+    InvalidKey,
+    #[error("Object is too large")]
+    TooLarge,
+    #[error("Cannot read body: {0}")]
+    Body(String),
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Backend error: {0}")]
+    Backend(String),
+}
+
+/// Check that `key` is a single name that cannot leave storage
+///
+/// # Errors
+/// Return an error if key is empty, hidden or contains a separator
+pub fn check_key(key: &str) -> Result<&str, StorageError> {
+    if key.is_empty() || key.starts_with('.') || key.contains(['/', '\\']) {
+        return Err(StorageError::InvalidKey);
+    }
+
+    Ok(key)
+}
+
+/// `StorageBackend` is where files are stored
+#[async_trait]
+pub trait StorageBackend: Send + Sync + Debug {
+    /// Write `body` at `key` and return the number of bytes written.
+    /// An existing object is replaced.
+    ///
+    /// # Errors
+    /// Return an error if:
+    /// * key isn't valid
+    /// * body returns an error
+    /// * object cannot be written
+    async fn put(&self, key: &str, body: ByteStream) -> Result<u64, StorageError>;
+
+    /// Read the object at `key`, or only `range` of it
+    ///
+    /// # Errors
+    /// Return an error if:
+    /// * key isn't valid
+    /// * object doesn't exist or cannot be read
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> Result<ByteStream, StorageError>;
+
+    /// Delete the object at `key`
+    ///
+    /// # Errors
+    /// Return an error if:
+    /// * key isn't valid
+    /// * object doesn't exist or cannot be deleted
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Return metadata of the object at `key`
+    ///
+    /// # Errors
+    /// Return an error if:
+    /// * key isn't valid
+    /// * object doesn't exist
+    async fn stat(&self, key: &str) -> Result<StorageMetadata, StorageError>;
+
+    /// Write a buffer at `key`
+    ///
+    /// # Errors
+    /// Return an error if object cannot be written
+    async fn put_bytes(&self, key: &str, bytes: Vec<u8>) -> Result<u64, StorageError> {
+        let body = futures::stream::once(async move { Ok(Bytes::from(bytes)) }).boxed();
+        self.put(key, body).await
+    }
+
+    /// Read the whole object at `key` in a buffer
+    ///
+    /// # Errors
+    /// Return an error if object doesn't exist or cannot be read
+    async fn get_bytes(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.get(key, None)
+            .await?
+            .try_fold(Vec::new(), |mut buffer, chunk| async move {
+                buffer.extend_from_slice(&chunk);
+                Ok(buffer)
+            })
+            .await
+    }
+}
+
+/// Return the `StorageBackend` described by `config`
+pub fn from_config(config: &StorageConfig) -> Arc<dyn StorageBackend> {
+    match config {
+        StorageConfig::Local { path } => Arc::new(LocalStorage::new(path)),
+        StorageConfig::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key,
+            secret_key,
+        } => Arc::new(S3Storage::new(
+            endpoint, bucket, region, access_key, secret_key,
+        )),
+    }
+}
@@ -0,0 +1,103 @@
+//! Store objects in a directory of the local filesystem
+
+use crate::{check_key, ByteStream, StorageBackend, StorageError, StorageMetadata};
+use futures::{StreamExt, TryStreamExt};
+use fydia_struct::file::ByteRange;
+use fydia_utils::async_trait::async_trait;
+use std::{
+    io::{ErrorKind, SeekFrom},
+    path::PathBuf,
+};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+/// `LocalStorage` stores every object as a file of `root`
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Take the directory where objects are stored.
+    /// The directory is created on first write.
+    pub fn new<T: Into<PathBuf>>(root: T) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Return path of `key`
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        Ok(self.root.join(check_key(key)?))
+    }
+}
+
+fn not_found(error: std::io::Error) -> StorageError {
+    if error.kind() == ErrorKind::NotFound {
+        StorageError::NotFound
+    } else {
+        StorageError::Io(error)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn put(&self, key: &str, mut body: ByteStream) -> Result<u64, StorageError> {
+        let path = self.path(key)?;
+        tokio::fs::create_dir_all(&self.root).await?;
+
+        let mut file = tokio::fs::File::create(&path).await?;
+        let mut size = 0;
+
+        let result = async {
+            while let Some(chunk) = body.try_next().await? {
+                file.write_all(&chunk).await?;
+                size += chunk.len() as u64;
+            }
+
+            file.flush().await.map_err(StorageError::from)
+        }
+        .await;
+
+        if let Err(error) = result {
+            drop(file);
+            if let Err(error) = tokio::fs::remove_file(&path).await {
+                error!("{error}");
+            }
+
+            return Err(error);
+        }
+
+        Ok(size)
+    }
+
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> Result<ByteStream, StorageError> {
+        let mut file = tokio::fs::File::open(self.path(key)?)
+            .await
+            .map_err(not_found)?;
+
+        let stream = match range {
+            Some(range) => {
+                file.seek(SeekFrom::Start(range.start)).await?;
+                ReaderStream::new(file.take(range.length())).boxed()
+            }
+            None => ReaderStream::new(file).boxed(),
+        };
+
+        Ok(stream.map_err(StorageError::from).boxed())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        tokio::fs::remove_file(self.path(key)?)
+            .await
+            .map_err(not_found)
+    }
+
+    async fn stat(&self, key: &str) -> Result<StorageMetadata, StorageError> {
+        let metadata = tokio::fs::metadata(self.path(key)?)
+            .await
+            .map_err(not_found)?;
+
+        Ok(StorageMetadata {
+            size: metadata.len(),
+        })
+    }
+}
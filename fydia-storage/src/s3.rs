@@ -0,0 +1,438 @@
+//! Store objects in a bucket of a S3 compatible service
+//!
+//! Requests use path-style addressing (`{endpoint}/{bucket}/{key}`) so
+//! self-hosted services like `MinIO` work without DNS configuration.
+
+use crate::{check_key, ByteStream, StorageBackend, StorageError, StorageMetadata};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use fydia_struct::file::ByteRange;
+use fydia_utils::async_trait::async_trait;
+use fydia_utils::http::header::{CONTENT_LENGTH, ETAG, RANGE};
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::{Method, Response, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::fmt::Debug;
+
+/// Size of a part of multipart upload.
+/// Objects smaller than one part are sent with a single request.
+pub const PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// Characters that are not encoded in a canonical request
+const URI_ENCODE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// `S3Storage` stores every object in `bucket`
+#[derive(Clone)]
+pub struct S3Storage {
+    client: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl Debug for S3Storage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Storage")
+            .field("endpoint", &self.endpoint)
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("access_key", &self.access_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Storage {
+    /// Take endpoint (`http://127.0.0.1:9000`), bucket, region and credentials
+    pub fn new<T: Into<String>>(
+        endpoint: T,
+        bucket: T,
+        region: T,
+        access_key: T,
+        secret_key: T,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            bucket: bucket.into(),
+            region: region.into(),
+            access_key: access_key.into(),
+            secret_key: secret_key.into(),
+        }
+    }
+
+    /// Return the value of `Authorization` header.
+    ///
+    /// `headers` must be lowercase and sorted by name.
+    pub fn authorization(
+        &self,
+        method: &Method,
+        uri: &str,
+        query: &str,
+        headers: &[(String, String)],
+        payload_hash: &str,
+        date: &DateTime<Utc>,
+    ) -> String {
+        let amz_date = date.format("%Y%m%dT%H%M%SZ").to_string();
+        let scope = format!("{}/{}/s3/aws4_request", date.format("%Y%m%d"), self.region);
+
+        let canonical_headers = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+            .collect::<String>();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<&str>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "{method}\n{uri}\n{query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}"
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let key = [
+            date.format("%Y%m%d").to_string().as_str(),
+            self.region.as_str(),
+            "s3",
+            "aws4_request",
+        ]
+        .iter()
+        .fold(
+            format!("AWS4{}", self.secret_key).into_bytes(),
+            |key, data| hmac(&key, data),
+        );
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={}",
+            self.access_key,
+            hex::encode(hmac(&key, &string_to_sign))
+        )
+    }
+
+    /// Send a signed request on `key`
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, String)],
+        headers: &[(&str, String)],
+        body: Vec<u8>,
+    ) -> Result<Response, StorageError> {
+        let uri = format!(
+            "/{}/{}",
+            utf8_percent_encode(&self.bucket, URI_ENCODE),
+            utf8_percent_encode(check_key(key)?, URI_ENCODE)
+        );
+
+        let mut query = query
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "{}={}",
+                    utf8_percent_encode(name, URI_ENCODE),
+                    utf8_percent_encode(value, URI_ENCODE)
+                )
+            })
+            .collect::<Vec<String>>();
+        query.sort();
+        let query = query.join("&");
+
+        let url = if query.is_empty() {
+            format!("{}{uri}", self.endpoint)
+        } else {
+            format!("{}{uri}?{query}", self.endpoint)
+        };
+        let url = Url::parse(&url).map_err(|error| StorageError::Backend(error.to_string()))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(StorageError::Backend("Endpoint has no host".to_string())),
+        };
+
+        let date = Utc::now();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let mut signed_headers = vec![
+            ("host".to_string(), host),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            (
+                "x-amz-date".to_string(),
+                date.format("%Y%m%dT%H%M%SZ").to_string(),
+            ),
+        ];
+        signed_headers.extend(
+            headers
+                .iter()
+                .map(|(name, value)| (name.to_lowercase(), value.clone())),
+        );
+        signed_headers.sort();
+
+        let authorization =
+            self.authorization(&method, &uri, &query, &signed_headers, &payload_hash, &date);
+
+        let mut request = self.client.request(method, url);
+        for (name, value) in signed_headers
+            .into_iter()
+            .filter(|(name, _)| name != "host")
+        {
+            request = request.header(name, value);
+        }
+
+        let response = request
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| StorageError::Backend(error.to_string()))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::NOT_FOUND => Err(StorageError::NotFound),
+            status => Err(StorageError::Backend(format!(
+                "{status}: {}",
+                response.text().await.unwrap_or_default()
+            ))),
+        }
+    }
+
+    async fn create_multipart_upload(&self, key: &str) -> Result<String, StorageError> {
+        let body = self
+            .send(
+                Method::POST,
+                key,
+                &[("uploads", String::new())],
+                &[],
+                vec![],
+            )
+            .await?
+            .text()
+            .await
+            .map_err(|error| StorageError::Backend(error.to_string()))?;
+
+        xml_value(&body, "UploadId")
+            .map(ToString::to_string)
+            .ok_or_else(|| StorageError::Backend("No UploadId in response".to_string()))
+    }
+
+    /// Upload `first` then every part left in `body`
+    ///
+    /// Return `ETag` of all parts and size of object
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        first: Vec<u8>,
+        mut body: ByteStream,
+    ) -> Result<(Vec<String>, u64), StorageError> {
+        let mut etags = Vec::new();
+        let mut size = 0;
+        let (mut part, mut finished) = (first, false);
+
+        while !part.is_empty() {
+            size += part.len() as u64;
+
+            let response = self
+                .send(
+                    Method::PUT,
+                    key,
+                    &[
+                        ("partNumber", (etags.len() + 1).to_string()),
+                        ("uploadId", upload_id.to_string()),
+                    ],
+                    &[],
+                    part,
+                )
+                .await?;
+
+            let etag = response
+                .headers()
+                .get(ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .ok_or_else(|| StorageError::Backend("No ETag in response".to_string()))?;
+            etags.push(etag.to_string());
+
+            if finished {
+                break;
+            }
+
+            (part, finished) = read_part(&mut body).await?;
+        }
+
+        Ok((etags, size))
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<(), StorageError> {
+        let parts = etags
+            .iter()
+            .enumerate()
+            .map(|(n, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{etag}</ETag></Part>",
+                    n + 1
+                )
+            })
+            .collect::<String>();
+
+        let body = self
+            .send(
+                Method::POST,
+                key,
+                &[("uploadId", upload_id.to_string())],
+                &[],
+                format!("<CompleteMultipartUpload>{parts}</CompleteMultipartUpload>").into_bytes(),
+            )
+            .await?
+            .text()
+            .await
+            .map_err(|error| StorageError::Backend(error.to_string()))?;
+
+        // An error can be returned after a 200 status
+        if body.contains("<Error>") {
+            return Err(StorageError::Backend(body));
+        }
+
+        Ok(())
+    }
+
+    async fn abort_multipart_upload(&self, key: &str, upload_id: &str) {
+        if let Err(error) = self
+            .send(
+                Method::DELETE,
+                key,
+                &[("uploadId", upload_id.to_string())],
+                &[],
+                vec![],
+            )
+            .await
+        {
+            error!("{error}");
+        }
+    }
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    // Hmac accepts keys of any size
+    Hmac::<Sha256>::new_from_slice(key)
+        .map(|mac| {
+            mac.chain_update(data.as_bytes())
+                .finalize()
+                .into_bytes()
+                .to_vec()
+        })
+        .unwrap_or_default()
+}
+
+/// Return the text of the first `tag` element
+fn xml_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{tag}>"))? + start;
+
+    Some(&xml[start..end])
+}
+
+/// Read `body` until a part is full.
+///
+/// Return the part and `true` if `body` is finished
+async fn read_part(body: &mut ByteStream) -> Result<(Vec<u8>, bool), StorageError> {
+    let mut part = Vec::new();
+
+    while part.len() < PART_SIZE {
+        match body.try_next().await? {
+            Some(chunk) => part.extend_from_slice(&chunk),
+            None => return Ok((part, true)),
+        }
+    }
+
+    Ok((part, false))
+}
+
+#[async_trait]
+impl StorageBackend for S3Storage {
+    async fn put(&self, key: &str, mut body: ByteStream) -> Result<u64, StorageError> {
+        check_key(key)?;
+
+        let (first, finished) = read_part(&mut body).await?;
+        if finished {
+            let size = first.len() as u64;
+            self.send(Method::PUT, key, &[], &[], first).await?;
+
+            return Ok(size);
+        }
+
+        let upload_id = self.create_multipart_upload(key).await?;
+
+        let result = match self.upload_parts(key, &upload_id, first, body).await {
+            Ok((etags, size)) => self
+                .complete_multipart_upload(key, &upload_id, &etags)
+                .await
+                .map(|_| size),
+            Err(error) => Err(error),
+        };
+
+        if result.is_err() {
+            self.abort_multipart_upload(key, &upload_id).await;
+        }
+
+        result
+    }
+
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> Result<ByteStream, StorageError> {
+        let headers = range
+            .map(|range| {
+                vec![(
+                    RANGE.as_str(),
+                    format!("bytes={}-{}", range.start, range.end),
+                )]
+            })
+            .unwrap_or_default();
+
+        let response = self.send(Method::GET, key, &[], &headers, vec![]).await?;
+
+        Ok(
+            futures::stream::unfold(Some(response), |response| async move {
+                let mut response = response?;
+                match response.chunk().await {
+                    Ok(Some(chunk)) => Some((Ok::<Bytes, StorageError>(chunk), Some(response))),
+                    Ok(None) => None,
+                    Err(error) => Some((Err(StorageError::Backend(error.to_string())), None)),
+                }
+            })
+            .boxed(),
+        )
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.send(Method::DELETE, key, &[], &[], vec![])
+            .await
+            .map(|_| ())
+    }
+
+    async fn stat(&self, key: &str) -> Result<StorageMetadata, StorageError> {
+        let response = self.send(Method::HEAD, key, &[], &[], vec![]).await?;
+
+        let size = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|size| size.to_str().ok())
+            .and_then(|size| size.parse().ok())
+            .ok_or_else(|| StorageError::Backend("No Content-Length in response".to_string()))?;
+
+        Ok(StorageMetadata { size })
+    }
+}
@@ -0,0 +1,130 @@
+//! This module is related to server bans
+
+use crate::{
+    messages::Date,
+    server::ServerId,
+    sqlerror::{GenericError, GenericSqlError},
+    user::UserId,
+    utils::IdError,
+};
+use chrono::Duration;
+use fydia_utils::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Max lenght of the reason of a ban
+pub const BAN_REASON_MAX_LENGHT: usize = 512;
+
+/// `Ban` contains all value of a ban of a user from a server
+#[allow(missing_docs)]
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct Ban {
+    pub server_id: ServerId,
+    pub user_id: UserId,
+    pub moderator: UserId,
+    pub reason: Option<String>,
+    pub expires_at: Option<Date>,
+    pub created_at: Date,
+}
+
+impl Ban {
+    /// Create a new `Ban`.
+    ///
+    /// `expires_in` is the duration of the ban in seconds, a ban without expiry is permanent.
+    ///
+    /// # Errors
+    /// Return an error if:
+    /// * `reason` is longer than `BAN_REASON_MAX_LENGHT`
+    /// * `expires_in` is 0
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::{ban::Ban, server::ServerId, user::UserId};
+    ///
+    /// let ban = Ban::new(ServerId::new("server"), UserId::new(2), UserId::new(1), None, Some(3600)).unwrap();
+    /// assert!(!ban.is_expired());
+    /// assert!(Ban::new(ServerId::new("server"), UserId::new(2), UserId::new(1), None, Some(0)).is_err());
+    /// ```
+    pub fn new(
+        server_id: ServerId,
+        user_id: UserId,
+        moderator: UserId,
+        reason: Option<String>,
+        expires_in: Option<u32>,
+    ) -> Result<Self, BanError> {
+        if reason
+            .as_ref()
+            .is_some_and(|reason| reason.len() > BAN_REASON_MAX_LENGHT)
+        {
+            return Err(BanError::ReasonTooLong);
+        }
+
+        if expires_in == Some(0) {
+            return Err(BanError::InvalidExpiry);
+        }
+
+        let created_at = Date::now();
+        let expires_at = expires_in
+            .map(|seconds| Date::new(created_at.0 + Duration::seconds(i64::from(seconds))));
+
+        Ok(Self {
+            server_id,
+            user_id,
+            moderator,
+            reason,
+            expires_at,
+            created_at,
+        })
+    }
+
+    /// Return true if the ban is expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .as_ref()
+            .is_some_and(|expires_at| expires_at.0 <= Date::now().0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+/// `BanError` represents all errors of `Ban`
+pub enum BanError {
+    #[error("User isn't banned from this server")]
+    NotBanned,
+    #[error("User is banned from this server")]
+    Banned,
+    #[error("Reason of the ban is too long")]
+    ReasonTooLong,
+    #[error("Duration of the ban should be greater than 0")]
+    InvalidExpiry,
+    #[error("Cannot get bans")]
+    CannotGet,
+    #[error("Cannot insert the ban")]
+    CannotInsert,
+    #[error("Cannot delete the ban")]
+    CannotDelete,
+    #[error("Cannot convert ban in ActiveModel")]
+    CannotIntoActiveModel,
+}
+
+impl From<IdError> for BanError {
+    fn from(_: IdError) -> Self {
+        Self::CannotIntoActiveModel
+    }
+}
+
+impl From<GenericSqlError> for BanError {
+    fn from(value: GenericSqlError) -> Self {
+        match value {
+            GenericSqlError::CannotInsert(GenericError { error, .. })
+            | GenericSqlError::CannotUpdate(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotInsert
+            }
+            GenericSqlError::CannotDelete(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotDelete
+            }
+        }
+    }
+}
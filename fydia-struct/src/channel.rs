@@ -1,13 +1,15 @@
 //! All structs for channels
 
+use crate::messages::Date;
 use crate::server::{MembersError, ServerError, ServerId};
 use crate::sqlerror::{GenericError, GenericSqlError};
+use chrono::Duration;
 use fydia_utils::generate_string;
 use fydia_utils::serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use thiserror::Error;
 /// `ChannelType` reprensent which type of channel is.
-/// Voice, Text or `DirectMessage`
+/// Voice, Text, `DirectMessage` or Thread
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(crate = "fydia_utils::serde")]
@@ -15,6 +17,7 @@ pub enum ChannelType {
     Voice = 0,
     Text = 1,
     DirectMessage = 2,
+    Thread = 3,
 }
 
 impl ChannelType {
@@ -26,6 +29,10 @@ impl ChannelType {
     pub fn is_text(&self) -> bool {
         self == &ChannelType::Text
     }
+    /// Check if channel is a thread
+    pub fn is_thread(&self) -> bool {
+        self == &ChannelType::Thread
+    }
 }
 
 impl Display for ChannelType {
@@ -34,6 +41,7 @@ impl Display for ChannelType {
             ChannelType::Voice => write!(f, "VOICE"),
             ChannelType::Text => write!(f, "TEXT"),
             ChannelType::DirectMessage => write!(f, "DIRECT_MESSAGE"),
+            ChannelType::Thread => write!(f, "THREAD"),
         }
     }
 }
@@ -52,6 +60,7 @@ impl ChannelType {
         match toparse.into().to_uppercase().as_str() {
             "VOICE" => Self::Voice,
             "DIRECT_MESSAGE" => Self::DirectMessage,
+            "THREAD" => Self::Thread,
             _ => Self::Text,
         }
     }
@@ -69,6 +78,7 @@ impl ChannelType {
         match toparse {
             0 => Self::Voice,
             2 => Self::DirectMessage,
+            3 => Self::Thread,
             _ => Self::Text,
         }
     }
@@ -132,7 +142,7 @@ impl ChannelId {
 /// ```
 ///
 #[allow(missing_docs)]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(crate = "fydia_utils::serde")]
 pub struct Channel {
     pub id: ChannelId,
@@ -141,6 +151,8 @@ pub struct Channel {
     pub name: String,
     pub description: String,
     pub channel_type: ChannelType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread: Option<ThreadMetadata>,
 }
 
 impl Channel {
@@ -222,6 +234,138 @@ impl Channel {
 
         Ok(channel)
     }
+
+    /// Create a new thread in the text channel `parent` started from a message
+    ///
+    /// # Errors
+    /// Return an error if :
+    /// * name is empty
+    /// * parent isn't a text channel
+    /// * `auto_archive` isn't valid, see `ThreadMetadata::check_auto_archive`
+    ///
+    ///# Examples
+    ///```
+    /// use fydia_struct::channel::{Channel, ChannelType};
+    ///
+    /// let parent = Channel::new("general", "", ChannelType::Text).unwrap();
+    /// let thread = Channel::new_thread("side", &parent, "MESSAGEID", None).unwrap();
+    ///
+    /// assert!(thread.is_thread());
+    /// assert_eq!(thread.permission_channel(), &parent.id);
+    ///```
+    pub fn new_thread<T: Into<String>>(
+        name: T,
+        parent: &Channel,
+        message_id: T,
+        auto_archive: Option<u32>,
+    ) -> Result<Self, ChannelError> {
+        if !parent.channel_type.is_text() {
+            return Err(ChannelError::CannotHaveThreads);
+        }
+
+        let mut channel = Self::new_with_serverid(
+            name.into(),
+            String::new(),
+            parent.parent_id.clone(),
+            ChannelType::Thread,
+        )?;
+
+        channel.thread = Some(ThreadMetadata::new(
+            parent.id.clone(),
+            message_id,
+            auto_archive,
+        )?);
+
+        Ok(channel)
+    }
+
+    /// Return true if channel is a thread
+    pub fn is_thread(&self) -> bool {
+        self.thread.is_some()
+    }
+
+    /// Return the channel whose permission overwrites apply to this channel.
+    ///
+    /// Threads use overwrites of their parent channel.
+    pub fn permission_channel(&self) -> &ChannelId {
+        self.thread
+            .as_ref()
+            .map_or(&self.id, |thread| &thread.parent_channel)
+    }
+}
+
+/// Default minutes without message before a thread is archived
+pub const DEFAULT_AUTO_ARCHIVE: u32 = 1440;
+/// Max minutes without message before a thread is archived
+pub const MAX_AUTO_ARCHIVE: u32 = 10080;
+
+/// `ThreadMetadata` contains values of a thread.
+///
+/// A thread is archived after `auto_archive` minutes without new message.
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct ThreadMetadata {
+    pub parent_channel: ChannelId,
+    pub message_id: String,
+    pub archived: bool,
+    pub auto_archive: u32,
+    pub last_activity: Date,
+}
+
+impl ThreadMetadata {
+    /// Create a new active `ThreadMetadata`, `auto_archive` is `DEFAULT_AUTO_ARCHIVE` by default
+    ///
+    /// # Errors
+    /// Return an error if `auto_archive` isn't valid
+    pub fn new<T: Into<String>>(
+        parent_channel: ChannelId,
+        message_id: T,
+        auto_archive: Option<u32>,
+    ) -> Result<Self, ChannelError> {
+        let auto_archive = auto_archive.unwrap_or(DEFAULT_AUTO_ARCHIVE);
+        Self::check_auto_archive(auto_archive)?;
+
+        Ok(Self {
+            parent_channel,
+            message_id: message_id.into(),
+            archived: false,
+            auto_archive,
+            last_activity: Date::now(),
+        })
+    }
+
+    /// Check minutes before archive, between 1 and `MAX_AUTO_ARCHIVE`
+    ///
+    /// # Errors
+    /// Return an error if `auto_archive` isn't valid
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::channel::ThreadMetadata;
+    ///
+    /// assert!(ThreadMetadata::check_auto_archive(60).is_ok());
+    /// assert!(ThreadMetadata::check_auto_archive(0).is_err());
+    /// assert!(ThreadMetadata::check_auto_archive(10081).is_err());
+    /// ```
+    pub fn check_auto_archive(auto_archive: u32) -> Result<(), ChannelError> {
+        if !(1..=MAX_AUTO_ARCHIVE).contains(&auto_archive) {
+            return Err(ChannelError::InvalidAutoArchive);
+        }
+
+        Ok(())
+    }
+
+    /// Return the date at or before which the last activity of a thread
+    /// archived after `auto_archive` minutes makes it inactive
+    pub fn inactive_before(auto_archive: u32, now: &Date) -> Date {
+        Date(now.0 - Duration::minutes(i64::from(auto_archive)))
+    }
+
+    /// Return true if thread is active and had no message since `auto_archive` minutes
+    pub fn is_inactive(&self, now: &Date) -> bool {
+        !self.archived && self.last_activity.0 <= Self::inactive_before(self.auto_archive, now).0
+    }
 }
 
 impl Default for Channel {
@@ -232,6 +376,7 @@ impl Default for Channel {
             name: String::new(),
             description: String::new(),
             channel_type: ChannelType::Text,
+            thread: None,
         }
     }
 }
@@ -254,6 +399,18 @@ pub enum ChannelError {
     CannotGetById,
     #[error("Empty name")]
     EmptyName,
+    #[error("Only text channels can have threads")]
+    CannotHaveThreads,
+    #[error("A thread already started from this message")]
+    ThreadExists,
+    #[error("Minutes before archive should be between 1 and 10080")]
+    InvalidAutoArchive,
+    #[error("Channel isn't a thread")]
+    NotAThread,
+    #[error("Cannot update the thread")]
+    CannotUpdateThread,
+    #[error("Cannot delete the channel")]
+    CannotDelete,
     #[error("{0}")]
     GenericSqlError(Box<GenericSqlError>),
 }
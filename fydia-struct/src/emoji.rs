@@ -1,12 +1,137 @@
 //! This modules is related to emoji
 
+use crate::{
+    server::ServerId,
+    sqlerror::{GenericError, GenericSqlError},
+    user::UserId,
+    utils::{Id, IdError},
+};
 use fydia_utils::serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-/// `Emoji` is the struct that contains all information of an emoji
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Min lenght of the name of an emoji
+pub const EMOJI_NAME_MIN_LENGHT: usize = 2;
+/// Max lenght of the name of an emoji
+pub const EMOJI_NAME_MAX_LENGHT: usize = 32;
+/// Max number of emojis in a server
+pub const MAX_EMOJIS_PER_SERVER: usize = 50;
+
+/// `EmojiId` is the id of an `Emoji`
+pub type EmojiId = Id<u32>;
+
+/// `Emoji` is the struct that contains all information of an emoji.
+///
+/// `name` is used as `:name:` shortcode in messages of the server
+/// and `path` is the name of its picture in the storage.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(crate = "fydia_utils::serde")]
 pub struct Emoji {
-    id: i32,
-    name: String,
-    path: String,
+    pub id: EmojiId,
+    pub server_id: ServerId,
+    pub name: String,
+    #[serde(skip)]
+    pub path: String,
+    pub creator: UserId,
+}
+
+impl Emoji {
+    /// Create a new `Emoji` with an unset id
+    ///
+    /// # Errors
+    /// Return an error if name isn't valid, see `Emoji::check_name`
+    pub fn new<T: Into<String>>(
+        server_id: ServerId,
+        name: T,
+        path: String,
+        creator: UserId,
+    ) -> Result<Self, EmojiError> {
+        let name = name.into();
+        Self::check_name(&name)?;
+
+        Ok(Self {
+            id: Id::Unset,
+            server_id,
+            name,
+            path,
+            creator,
+        })
+    }
+
+    /// Check the name of an emoji.
+    ///
+    /// Name is between `EMOJI_NAME_MIN_LENGHT` and `EMOJI_NAME_MAX_LENGHT`
+    /// ascii letters, digits or underscores.
+    ///
+    /// # Errors
+    /// Return an error if name isn't valid
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::emoji::Emoji;
+    ///
+    /// assert!(Emoji::check_name("party_parrot2").is_ok());
+    /// assert!(Emoji::check_name("a").is_err());
+    /// assert!(Emoji::check_name("not:valid").is_err());
+    /// ```
+    pub fn check_name(name: &str) -> Result<(), EmojiError> {
+        if !(EMOJI_NAME_MIN_LENGHT..=EMOJI_NAME_MAX_LENGHT).contains(&name.len())
+            || !name
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || char == '_')
+        {
+            return Err(EmojiError::InvalidName);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+/// `EmojiError` represents all errors of `Emoji`
+pub enum EmojiError {
+    #[error("Name of an emoji is 2 to 32 letters, digits or underscores")]
+    InvalidName,
+    #[error("An emoji already has this name")]
+    NameTaken,
+    #[error("Server has too many emojis")]
+    TooManyEmojis,
+    #[error("No emoji with this id")]
+    NoEmojiWithId,
+    #[error("Cannot get emojis")]
+    CannotGet,
+    #[error("Cannot insert the emoji")]
+    CannotInsert,
+    #[error("Cannot update the emoji")]
+    CannotUpdate,
+    #[error("Cannot delete the emoji")]
+    CannotDelete,
+    #[error("Cannot convert emoji in ActiveModel")]
+    CannotIntoActiveModel,
+}
+
+impl From<IdError> for EmojiError {
+    fn from(_: IdError) -> Self {
+        Self::CannotIntoActiveModel
+    }
+}
+
+impl From<GenericSqlError> for EmojiError {
+    fn from(value: GenericSqlError) -> Self {
+        match value {
+            GenericSqlError::CannotInsert(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotInsert
+            }
+            GenericSqlError::CannotUpdate(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotUpdate
+            }
+            GenericSqlError::CannotDelete(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotDelete
+            }
+        }
+    }
 }
@@ -1,8 +1,17 @@
 //! This module is related to event
 
-use crate::channel::ChannelId;
+use crate::ban::Ban;
+use crate::channel::{Channel, ChannelId};
+use crate::emoji::{Emoji, EmojiId};
+use crate::member::Member;
+use crate::reaction::ReactionEmoji;
+use crate::roles::{Role, RoleId};
 use crate::server::ServerId;
-use crate::{messages::Message, user::UserId};
+use crate::utils::Id;
+use crate::{
+    messages::{Date, Message},
+    user::UserId,
+};
 use fydia_utils::serde::{Deserialize, Serialize};
 
 /// `Event` represent the message by websocket.
@@ -54,12 +63,42 @@ pub enum EventContent {
     MessageUpdate {
         message_id: String,
         update: Box<Message>,
+        edited_at: Date,
+    },
+    DirectMessage {
+        directmessage_id: Id<u32>,
+        content: Box<Message>,
     },
     UserChangeName(String),
     VocalChannelJoin(String),  // When user join a vocal channel
     VocalChannelLeave(String), // When user quit a vocal channel
-    ServerJoin(String),        // When a user join
-    ServerLeft(String),        // When a user quit
+    ServerJoin {
+        userid: UserId,
+        invite_code: String,
+    },
+    ServerLeft {
+        userid: UserId,
+    },
+    ServerUpdate {
+        name: String,
+        owner: UserId,
+        icon: String,
+    },
+    ServerDelete,
+    MemberKick {
+        userid: UserId,
+        moderator: UserId,
+    },
+    MemberBan {
+        ban: Box<Ban>,
+    },
+    MemberUnban {
+        userid: UserId,
+        moderator: UserId,
+    },
+    MemberUpdate {
+        member: Box<Member>,
+    },
     ChannelCreate(String),
     ChannelUpdate(String),
     ChannelDelete(String),
@@ -71,4 +110,70 @@ pub enum EventContent {
         userid: UserId,
         channelid: ChannelId,
     },
+    DirectMessageStartTyping {
+        userid: UserId,
+        directmessage_id: Id<u32>,
+    },
+    DirectMessageStopTyping {
+        userid: UserId,
+        directmessage_id: Id<u32>,
+    },
+    RoleCreate {
+        role: Box<Role>,
+    },
+    RoleUpdate {
+        role: Box<Role>,
+    },
+    RoleDelete {
+        role_id: RoleId,
+    },
+    RoleAssign {
+        role_id: RoleId,
+        userid: UserId,
+    },
+    RoleUnassign {
+        role_id: RoleId,
+        userid: UserId,
+    },
+    RolesReorder {
+        roles: Vec<Role>,
+    },
+    EmojiCreate {
+        emoji: Box<Emoji>,
+    },
+    EmojiUpdate {
+        emoji: Box<Emoji>,
+    },
+    EmojiDelete {
+        emoji_id: EmojiId,
+    },
+    ReactionAdd {
+        message_id: String,
+        userid: UserId,
+        emoji: ReactionEmoji,
+    },
+    ReactionRemove {
+        message_id: String,
+        userid: UserId,
+        emoji: ReactionEmoji,
+    },
+    Mention {
+        message_id: String,
+        channelid: ChannelId,
+        author: UserId,
+    },
+    ThreadCreate {
+        thread: Box<Channel>,
+    },
+    ThreadUpdate {
+        thread: Box<Channel>,
+    },
+    ThreadArchive {
+        channelid: ChannelId,
+    },
+    PinsUpdate {
+        channelid: ChannelId,
+        message_id: String,
+        pinned: bool,
+    },
 }
@@ -1,19 +1,13 @@
 //! This module is related to file
 
-use futures::prelude::*;
 use fydia_utils::generate_string;
 use fydia_utils::{
     serde::{Deserialize, Serialize},
     serde_json,
 };
-use std::{
-    fs::OpenOptions,
-    io::{self, BufRead, BufReader, Read, Write},
-};
 
 use crate::messages::Date;
-
-const PREFIX: &str = "./storage/";
+use thiserror::Error;
 
 /// `FileDescriptor` is used to describe a file.
 ///
@@ -80,18 +74,25 @@ impl FileDescriptor {
             Err(error) => Err(error.to_string()),
         }
     }
+
+    /// Deserialize `FileDescriptor` from Json
+    ///
+    /// # Errors
+    /// Return an error if :
+    /// * bytes aren't a valid `FileDescriptor`
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(bytes).map_err(|error| error.to_string())
+    }
 }
 
-/// `File` is used to stock file.
-///  
-/// `path` is the path of file.
+/// `File` is a file stored by a storage backend.
 ///
-/// `description` is the path of a `DescriptorFile`.
+/// `name` is the key of the file and its `FileDescriptor` is stored
+/// at `description_key`.
 #[allow(missing_docs)]
 #[derive(Debug)]
 pub struct File {
-    path: String,
-    description: Option<String>,
+    name: String,
 }
 
 impl Default for File {
@@ -106,165 +107,114 @@ impl File {
         Self::default()
     }
 
-    /// Return name of `File`
-    pub fn get_name(&self) -> String {
-        self.path
-            .strip_prefix(PREFIX)
-            .unwrap_or_default()
-            .to_string()
-    }
-
-    /// Create the `File` and the directory if it does not exist.
-    ///
-    /// # Errors
-    /// Return an error if :
-    /// * directory cannot be create
-    /// * file cannot be created
-    pub fn create(&self) -> Result<(), String> {
-        drop(std::fs::create_dir(PREFIX).map_err(|error| error.to_string()));
-        std::fs::File::create(&self.path)
-            .map_err(|f| f.to_string())
-            .map(|_| ())
-    }
-
-    /// Create the `File` with the `FileDescriptor`
-    /// and the directory if it does not exist.
-    ///
-    /// # Errors
-    /// Return an error if :
-    /// * directory cannot be create
-    /// * file cannot be created
-    /// * `FileDescriptor` cannot be created
-    /// * buffer cannot be written
-    pub fn create_with_description(&self, file_descriptor: &FileDescriptor) -> Result<(), String> {
-        self.create()?;
-
-        let mut file =
-            std::fs::File::create(format!("{}.json", &self.path)).map_err(|f| f.to_string())?;
-
-        file.write(file_descriptor.to_string()?.as_bytes())
-            .map(|_| ())
-            .map_err(|error| error.to_string())
-    }
-
-    /// Create the `File` and write buffer of `[u8]`
-    ///
-    /// # Errors
-    /// Return an error if :
-    /// * file cannot be created
-    /// * buffer cannot be written
-    pub fn create_and_write(&self, bytes: &[u8]) -> Result<(), String> {
-        self.create()?;
-        self.write(bytes)
-    }
-
-    /// Write a buffer of `[u8]` to `File`
-    ///
-    /// # Errors
-    /// Return an error if :
-    /// * file cannot be created
-    /// * file cannot be written
-    pub fn write(&self, bytes: &[u8]) -> Result<(), String> {
-        let mut file = OpenOptions::new()
-            .write(true)
-            .read(true)
-            .open(&self.path)
-            .map_err(|f| f.to_string())?;
-
-        file.write_all(bytes).map_err(|f| f.to_string())
-    }
-
     /// Take the name of file to return `File`
     pub fn get<T: Into<String>>(name_file: T) -> Self {
         Self {
-            path: format!("{PREFIX}{}", &name_file.into()),
-            description: None,
+            name: name_file.into(),
         }
     }
-    /// Take the name of file to return `File` with `Some(String)` in `File.description`
-    pub fn get_with_description<T: Into<String>>(path_to_file: T) -> Self {
-        let path = format!("{PREFIX}{}", &path_to_file.into());
-        let description = Some(format!("{path}.json",));
 
-        Self { path, description }
+    /// Return name of `File`
+    pub fn get_name(&self) -> String {
+        self.name.clone()
     }
 
-    /// Takes a buffer to write the value of file
+    /// Return the key of the `FileDescriptor` of `File`
     ///
-    /// # Errors
-    /// Return an error if :
-    /// * file cannot be read
-    pub fn read_file(&self, buf: &mut [u8]) -> Result<(), String> {
-        let mut file = std::fs::File::open(&self.path).map_err(|f| f.to_string())?;
-        file.read(buf)
-            .map(|_| ())
-            .map_err(|error| error.to_string())
-    }
-
-    /// Reads the file and returns its value as `Vec<u8>`
+    ///# Examples
+    ///```
+    /// use fydia_struct::file::File;
     ///
-    /// # Errors
-    /// Return an error if :
-    /// * file cannot be read
-    pub fn get_value(&self) -> Result<Vec<u8>, String> {
-        let file = std::fs::File::open(&self.path).map_err(|f| f.to_string())?;
-        let mut buf = BufReader::new(file);
-        Ok(buf.fill_buf().map_err(|f| f.to_string())?.to_vec())
+    /// assert_eq!(File::get("name").description_key(), "name.json");
+    ///```
+    pub fn description_key(&self) -> String {
+        format!("{}.json", self.name)
     }
+}
 
-    /// Reads the file and returns its value as `Stream<u8>`
-    ///
-    /// # Errors
-    /// Return an error if :
-    /// * file cannot be read
-    pub fn async_get_value(&self) -> Result<impl Stream<Item = Result<u8, io::Error>>, String> {
-        let file = std::fs::File::open(&self.path).map_err(|f| f.to_string())?;
-        let buf = BufReader::new(file);
-        Ok(stream::iter(buf.bytes()))
-    }
+/// `ByteRange` is an inclusive range of bytes requested with a `Range` header
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
 
-    /// Get the `FileDescription` of the `File`
+impl ByteRange {
+    /// Parse a `Range` header for a file of `size` bytes.
+    /// Only one range is supported.
     ///
     /// # Errors
     /// Return an error if :
-    /// * `FileDescription` isn't exist or doesn't exist
-    pub fn get_description(&self) -> Result<FileDescriptor, String> {
-        let value = self.get_value_of_description()?;
-        let string = String::from_utf8(value).map_err(|f| f.to_string())?;
+    /// * header isn't a valid single bytes range
+    /// * range is outside of the file
+    ///
+    ///# Examples
+    ///```
+    /// use fydia_struct::file::ByteRange;
+    ///
+    /// assert_eq!(ByteRange::parse("bytes=-10", 100).unwrap(), ByteRange { start: 90, end: 99 });
+    ///```
+    pub fn parse(header: &str, size: u64) -> Result<Self, ByteRangeError> {
+        let (start, end) = header
+            .strip_prefix("bytes=")
+            .filter(|range| !range.contains(','))
+            .and_then(|range| range.trim().split_once('-'))
+            .ok_or(ByteRangeError::Invalid)?;
+
+        let parse = |value: &str| value.parse::<u64>().or(Err(ByteRangeError::Invalid));
+
+        let range = match (start.is_empty(), end.is_empty()) {
+            (true, true) => return Err(ByteRangeError::Invalid),
+            (true, false) => {
+                let suffix = parse(end)?;
+                if suffix == 0 || size == 0 {
+                    return Err(ByteRangeError::Unsatisfiable);
+                }
+
+                Self {
+                    start: size.saturating_sub(suffix),
+                    end: size - 1,
+                }
+            }
+            (false, true) => Self {
+                start: parse(start)?,
+                end: size.saturating_sub(1),
+            },
+            (false, false) => Self {
+                start: parse(start)?,
+                end: parse(end)?.min(size.saturating_sub(1)),
+            },
+        };
+
+        if range.start >= size {
+            return Err(ByteRangeError::Unsatisfiable);
+        }
 
-        serde_json::from_str::<FileDescriptor>(&string).map_err(|f| f.to_string())
-    }
+        if range.start > range.end {
+            return Err(ByteRangeError::Invalid);
+        }
 
-    /// Return value of `FileDescription` as `Vec<u8>`
-    ///
-    /// # Errors
-    /// Return an error if :
-    /// * `FileDescription` isn't exist or doesn't exist
-    pub fn get_value_of_description(&self) -> Result<Vec<u8>, String> {
-        let desc = self
-            .description
-            .as_ref()
-            .ok_or_else(|| "No file".to_string())?;
+        Ok(range)
+    }
 
-        let file = std::fs::File::open(desc).map_err(|f| f.to_string())?;
-        let mut buf = BufReader::new(file);
-        Ok(buf.fill_buf().map_err(|f| f.to_string())?.to_vec())
+    /// Return the number of bytes in range
+    pub fn length(&self) -> u64 {
+        self.end - self.start + 1
     }
 
-    /// Return value of `FileDescription` as `Stream<u8>`
-    ///
-    /// # Errors
-    /// Return an error if:
-    /// * `FileDescription` cannot be read or doesn't exist
-    pub fn async_get_value_of_description(
-        &self,
-    ) -> Result<impl Stream<Item = Result<u8, io::Error>>, String> {
-        let desc = self
-            .description
-            .as_ref()
-            .ok_or_else(|| "No file".to_string())?;
-        let file = std::fs::File::open(desc).map_err(|f| f.to_string())?;
-        let buf = BufReader::new(file);
-        Ok(stream::iter(buf.bytes()))
+    /// Return the value of `Content-Range` header
+    pub fn content_range(&self, size: u64) -> String {
+        format!("bytes {}-{}/{size}", self.start, self.end)
     }
 }
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[allow(missing_docs)]
+/// `ByteRangeError` represents all errors of `ByteRange`
+pub enum ByteRangeError {
+    #[error("Range header isn't valid")]
+    Invalid,
+    #[error("Range isn't satisfiable")]
+    Unsatisfiable,
+}
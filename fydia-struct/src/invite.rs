@@ -0,0 +1,136 @@
+//! This module is related to server invites
+
+use crate::{
+    messages::Date,
+    server::ServerId,
+    sqlerror::{GenericError, GenericSqlError},
+    user::UserId,
+    utils::IdError,
+};
+use chrono::Duration;
+use fydia_utils::{
+    generate_string,
+    serde::{Deserialize, Serialize},
+};
+use thiserror::Error;
+
+/// Lenght of an invite code
+pub const INVITE_CODE_LENGHT: i32 = 10;
+
+/// `Invite` contains all value of an invite code of a server
+#[allow(missing_docs)]
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct Invite {
+    pub code: String,
+    pub server_id: ServerId,
+    pub creator: UserId,
+    pub uses: u32,
+    pub max_uses: Option<u32>,
+    pub expires_at: Option<Date>,
+    pub created_at: Date,
+}
+
+impl Invite {
+    /// Create a new `Invite` with a random code.
+    ///
+    /// `expires_in` is the lifetime of the invite in seconds.
+    ///
+    /// # Errors
+    /// Return an error if:
+    /// * `max_uses` is 0
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::{invite::Invite, server::ServerId, user::UserId};
+    ///
+    /// let invite = Invite::new(ServerId::new("server"), UserId::new(1), Some(5), Some(3600)).unwrap();
+    /// assert!(invite.is_usable());
+    /// assert!(Invite::new(ServerId::new("server"), UserId::new(1), Some(0), None).is_err());
+    /// ```
+    pub fn new(
+        server_id: ServerId,
+        creator: UserId,
+        max_uses: Option<u32>,
+        expires_in: Option<u32>,
+    ) -> Result<Self, InviteError> {
+        if max_uses == Some(0) {
+            return Err(InviteError::InvalidMaxUses);
+        }
+
+        let created_at = Date::now();
+        let expires_at = expires_in
+            .map(|seconds| Date::new(created_at.0 + Duration::seconds(i64::from(seconds))));
+
+        Ok(Self {
+            code: generate_string(INVITE_CODE_LENGHT),
+            server_id,
+            creator,
+            uses: 0,
+            max_uses,
+            expires_at,
+            created_at,
+        })
+    }
+
+    /// Return true if the invite is expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .as_ref()
+            .is_some_and(|expires_at| expires_at.0 <= Date::now().0)
+    }
+
+    /// Return true if the invite is not expired and can still be used
+    pub fn is_usable(&self) -> bool {
+        !self.is_expired() && self.max_uses.is_none_or(|max_uses| self.uses < max_uses)
+    }
+}
+
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+/// `InviteError` represents all errors of `Invite`
+pub enum InviteError {
+    #[error("No invite with this code")]
+    NoInviteWithCode,
+    #[error("Invite is expired")]
+    Expired,
+    #[error("Invite has reached its maximum uses")]
+    MaxUsesReached,
+    #[error("Max uses should be greater than 0")]
+    InvalidMaxUses,
+    #[error("Cannot insert the invite")]
+    CannotInsert,
+    #[error("Cannot delete the invite")]
+    CannotDelete,
+    #[error("Cannot use the invite")]
+    CannotUse,
+    #[error("User is already a member of the server")]
+    AlreadyMember,
+    #[error("Cannot convert invite in ActiveModel")]
+    CannotIntoActiveModel,
+}
+
+impl From<IdError> for InviteError {
+    fn from(_: IdError) -> Self {
+        Self::CannotIntoActiveModel
+    }
+}
+
+impl From<GenericSqlError> for InviteError {
+    fn from(value: GenericSqlError) -> Self {
+        match value {
+            GenericSqlError::CannotInsert(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotInsert
+            }
+            GenericSqlError::CannotUpdate(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotUse
+            }
+            GenericSqlError::CannotDelete(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotDelete
+            }
+        }
+    }
+}
@@ -8,6 +8,7 @@
 #[macro_use]
 extern crate log;
 
+pub mod ban;
 pub mod channel;
 pub mod directmessage;
 pub mod emoji;
@@ -15,17 +16,22 @@ pub mod event;
 pub mod file;
 pub mod format;
 pub mod instance;
+pub mod invite;
 pub mod manager;
+pub mod member;
 pub mod messages;
 pub mod pathextractor;
 pub mod permission;
+pub mod pin;
 pub mod querystring;
+pub mod reaction;
 pub mod response;
 pub mod roles;
 pub mod server;
 pub mod sqlerror;
 pub mod user;
 pub mod utils;
+pub mod websocket;
 
 #[cfg(test)]
 mod test;
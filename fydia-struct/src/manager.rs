@@ -9,12 +9,19 @@ use tokio::sync::{mpsc::UnboundedSender, oneshot};
 ///
 /// Manager is a thread with Channel to communicate with him.
 #[async_trait::async_trait]
-pub trait ManagerReceiverTrait {
+pub trait ManagerReceiverTrait: Send {
     /// Type of Message
-    type Message;
+    type Message: Send;
 
     /// Handler of a new Message
     async fn on_receiver(&mut self, message: Self::Message);
+
+    /// Wait for a message produced by the manager itself, like an expired timer.
+    ///
+    /// Never resolves by default
+    async fn next_message(&mut self) -> Self::Message {
+        futures::future::pending().await
+    }
 }
 
 /// `Manager` struct with a generic value that implement `ManagerReceiverTrait`
@@ -44,8 +51,14 @@ impl<T: std::default::Default + Debug + ManagerReceiverTrait + std::marker::Send
                 error_exit();
             }
 
-            while let Some(message) = receiver.recv().await {
-                value.on_receiver(message).await;
+            loop {
+                tokio::select! {
+                    message = receiver.recv() => match message {
+                        Some(message) => value.on_receiver(message).await,
+                        None => break,
+                    },
+                    message = value.next_message() => value.on_receiver(message).await,
+                }
             }
         });
         if let Ok(sender) = receiver.await {
@@ -0,0 +1,139 @@
+//! This module is related to members of a server
+
+use crate::{messages::Date, roles::RoleId, server::MembersError, user::UserId};
+use fydia_utils::serde::{Deserialize, Serialize};
+
+/// Default number of members returned by a page
+pub const DEFAULT_MEMBERS_LIMIT: u64 = 100;
+/// Maximum number of members returned by a page
+pub const MAX_MEMBERS_LIMIT: u64 = 1000;
+/// Max lenght of a nickname
+pub const NICKNAME_MAX_LENGHT: usize = 32;
+
+/// `Member` is a user as seen in a server
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct Member {
+    pub id: UserId,
+    pub name: String,
+    pub roles: Vec<RoleId>,
+    #[serde(flatten)]
+    pub profile: MemberProfile,
+}
+
+/// `MemberProfile` contains values of a user that are specific to a server.
+///
+/// `avatar` overrides the avatar of the user in this server.
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct MemberProfile {
+    pub nickname: Option<String>,
+    #[serde(skip)]
+    pub avatar: Option<String>,
+    pub joined_at: Date,
+}
+
+impl MemberProfile {
+    /// Check a new nickname.
+    ///
+    /// Nickname is trimmed and an empty nickname removes it.
+    ///
+    /// # Errors
+    /// Return an error if nickname is longer than `NICKNAME_MAX_LENGHT`
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::member::MemberProfile;
+    ///
+    /// assert_eq!(MemberProfile::check_nickname(Some(" nick ")).unwrap(), Some("nick".to_string()));
+    /// assert_eq!(MemberProfile::check_nickname(Some("")).unwrap(), None);
+    /// assert!(MemberProfile::check_nickname(Some("n".repeat(33).as_str())).is_err());
+    /// ```
+    pub fn check_nickname(nickname: Option<&str>) -> Result<Option<String>, MembersError> {
+        let Some(nickname) = nickname
+            .map(str::trim)
+            .filter(|nickname| !nickname.is_empty())
+        else {
+            return Ok(None);
+        };
+
+        if nickname.chars().count() > NICKNAME_MAX_LENGHT {
+            return Err(MembersError::NicknameTooLong);
+        }
+
+        Ok(Some(nickname.to_string()))
+    }
+}
+
+/// `MemberQuery` contains the cursor, the search and the limit of a page of members
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberQuery {
+    pub after: Option<UserId>,
+    pub search: Option<String>,
+    pub limit: u64,
+}
+
+impl Default for MemberQuery {
+    fn default() -> Self {
+        Self {
+            after: None,
+            search: None,
+            limit: DEFAULT_MEMBERS_LIMIT,
+        }
+    }
+}
+
+impl MemberQuery {
+    /// Create a new `MemberQuery`.
+    ///
+    /// Members are ordered by id and `after` is the id of the last member of the previous page.
+    /// `search` keeps members whose name starts with it, an empty search is ignored.
+    /// Limit is clamped to `MAX_MEMBERS_LIMIT`.
+    ///
+    /// # Errors
+    /// Return an error if limit is 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fydia_struct::member::MemberQuery;
+    ///
+    /// let query = MemberQuery::new(Some(4), Some(String::new()), Some(5000)).unwrap();
+    ///
+    /// assert_eq!(query.search, None);
+    /// assert_eq!(query.limit, 1000);
+    /// assert!(MemberQuery::new(None, None, Some(0)).is_err());
+    /// ```
+    pub fn new(
+        after: Option<u32>,
+        search: Option<String>,
+        limit: Option<u64>,
+    ) -> Result<Self, MembersError> {
+        let limit = limit.unwrap_or(DEFAULT_MEMBERS_LIMIT);
+
+        if limit == 0 {
+            return Err(MembersError::InvalidLimit);
+        }
+
+        Ok(Self {
+            after: after.map(UserId::new),
+            search: search.filter(|search| !search.is_empty()),
+            limit: limit.min(MAX_MEMBERS_LIMIT),
+        })
+    }
+}
+
+/// `MembersPage` is a page of members ordered by id.
+///
+/// `next` is the cursor to use as `after` to fetch the following page,
+/// `None` if there is no more members.
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct MembersPage {
+    pub members: Vec<Member>,
+    pub next: Option<UserId>,
+}
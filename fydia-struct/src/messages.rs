@@ -1,8 +1,10 @@
 //! This module related to message
 
 use crate::channel::ChannelId;
+use crate::member::MemberProfile;
+use crate::reaction::Reaction;
 use crate::sqlerror::GenericSqlError;
-use crate::user::User;
+use crate::user::{User, UserId};
 use crate::utils::IdError;
 use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
 use fydia_utils::generate_string;
@@ -95,11 +97,26 @@ pub struct Message {
     pub content: String,
     pub message_type: MessageType,
     pub edited: bool,
+    /// Time of the last edit, `None` if the message has never been edited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edited_at: Option<Date>,
     pub timestamp: Date,
     #[serde(rename = "channel")]
     pub channel_id: ChannelId,
     #[serde(rename = "author")]
     pub author_id: User,
+    /// Profile of the author in the server of the channel
+    #[serde(rename = "member", default, skip_serializing_if = "Option::is_none")]
+    pub author_member: Option<MemberProfile>,
+    /// Reactions counted by emoji, in order of their first use
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reactions: Vec<Reaction>,
+    /// Id of the message this message replies to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    /// Snapshot of the replied message, `None` if it has been deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referenced_message: Option<Box<MessageSnapshot>>,
 }
 
 impl Message {
@@ -136,11 +153,223 @@ impl Message {
             content,
             message_type,
             edited,
+            edited_at: None,
             timestamp,
             author_id,
             channel_id,
+            author_member: None,
+            reactions: Vec::new(),
+            reply_to: None,
+            referenced_message: None,
         })
     }
+
+    /// Replace content of the message and return the revision of the previous content
+    ///
+    /// # Errors
+    /// Return an error if :
+    /// * content is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fydia_struct::{messages::{Message, MessageType, Date}, user::User, channel::ChannelId};
+    ///
+    /// let mut message = Message::new("First", MessageType::TEXT, false, Date::now(), User::default(),
+    /// ChannelId::new(String::from("THISISANIDOF15C"))).unwrap();
+    /// let revision = message.edit("Second").unwrap();
+    ///
+    /// assert_eq!(revision.content, "First");
+    /// assert_eq!(message.content, "Second");
+    /// assert!(message.edited);
+    /// ```
+    pub fn edit<T: Into<String>>(&mut self, content: T) -> Result<MessageRevision, MessageError> {
+        let content = content.into();
+
+        if content.is_empty() {
+            return Err(MessageError::ContentEmpty);
+        }
+
+        let edited_at = Date::now();
+        let previous = std::mem::replace(&mut self.content, content);
+
+        self.edited = true;
+        self.edited_at = Some(edited_at.clone());
+
+        Ok(MessageRevision {
+            content: previous,
+            edited_at,
+        })
+    }
+}
+
+/// `MessageRevision` is the content a message had before one of its edits
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct MessageRevision {
+    pub content: String,
+    /// Time of the edit which replaced this content
+    pub edited_at: Date,
+}
+
+/// Max number of characters of content kept in a `MessageSnapshot`
+pub const SNAPSHOT_CONTENT_MAX_LENGHT: usize = 100;
+
+/// `MessageSnapshot` is a compact copy of a message shown with the replies to it
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct MessageSnapshot {
+    pub id: String,
+    pub content: String,
+    pub message_type: MessageType,
+    #[serde(rename = "author")]
+    pub author_id: User,
+}
+
+impl MessageSnapshot {
+    /// Create a new `MessageSnapshot`, content is cut to `SNAPSHOT_CONTENT_MAX_LENGHT` characters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fydia_struct::{messages::{MessageSnapshot, MessageType}, user::User};
+    ///
+    /// let snapshot = MessageSnapshot::new("ID", &"a".repeat(150), MessageType::TEXT, User::default());
+    /// assert_eq!(snapshot.content.len(), 100);
+    /// ```
+    pub fn new<T: Into<String>>(
+        id: T,
+        content: &str,
+        message_type: MessageType,
+        author_id: User,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            content: content.chars().take(SNAPSHOT_CONTENT_MAX_LENGHT).collect(),
+            message_type,
+            author_id,
+        }
+    }
+}
+
+/// Default number of messages returned by a page
+pub const DEFAULT_MESSAGES_LIMIT: u64 = 50;
+/// Maximum number of messages returned by a page
+pub const MAX_MESSAGES_LIMIT: u64 = 100;
+
+/// `MessageCursor` is the position from which messages are fetched
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MessageCursor {
+    /// Most recent messages of the channel
+    #[default]
+    Latest,
+    /// Messages older than the message with this id
+    Before(String),
+    /// Messages newer than the message with this id
+    After(String),
+    /// Messages around the message with this id, the message included
+    Around(String),
+}
+
+/// `MessageQuery` contains the cursor and the limit of a page of messages
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageQuery {
+    pub cursor: MessageCursor,
+    pub limit: u64,
+}
+
+impl Default for MessageQuery {
+    fn default() -> Self {
+        Self {
+            cursor: MessageCursor::Latest,
+            limit: DEFAULT_MESSAGES_LIMIT,
+        }
+    }
+}
+
+impl MessageQuery {
+    /// Create a new `MessageQuery` from the optional cursors and limit.
+    ///
+    /// Limit is clamped to `MAX_MESSAGES_LIMIT`.
+    ///
+    /// # Errors
+    /// Return an error if :
+    /// * more than one cursor is given
+    /// * limit is 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fydia_struct::messages::{MessageCursor, MessageQuery};
+    ///
+    /// let query = MessageQuery::new(Some("ID".to_string()), None, None, Some(10)).unwrap();
+    ///
+    /// assert_eq!(query.cursor, MessageCursor::Before("ID".to_string()));
+    /// assert!(MessageQuery::new(Some("ID".to_string()), Some("ID".to_string()), None, None).is_err());
+    /// ```
+    pub fn new(
+        before: Option<String>,
+        after: Option<String>,
+        around: Option<String>,
+        limit: Option<u64>,
+    ) -> Result<Self, MessageError> {
+        let cursor = match (before, after, around) {
+            (None, None, None) => MessageCursor::Latest,
+            (Some(before), None, None) => MessageCursor::Before(before),
+            (None, Some(after), None) => MessageCursor::After(after),
+            (None, None, Some(around)) => MessageCursor::Around(around),
+            _ => return Err(MessageError::TooManyCursors),
+        };
+
+        let limit = limit.unwrap_or(DEFAULT_MESSAGES_LIMIT);
+
+        if limit == 0 {
+            return Err(MessageError::InvalidLimit);
+        }
+
+        Ok(Self {
+            cursor,
+            limit: limit.min(MAX_MESSAGES_LIMIT),
+        })
+    }
+}
+
+/// `MessagesPage` is a page of messages ordered from the oldest to the newest.
+///
+/// `next` is the cursor to use to fetch the following page in the same direction,
+/// `None` if there is no more messages.
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct MessagesPage {
+    pub messages: Vec<Message>,
+    pub next: Option<String>,
+}
+
+/// `ReadState` is the last message read by a user in a channel
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct ReadState {
+    pub user_id: UserId,
+    pub channel_id: ChannelId,
+    pub message_id: String,
+    pub timestamp: Date,
+}
+
+impl ReadState {
+    /// Create a new `ReadState` at current time
+    pub fn new(user_id: UserId, channel_id: ChannelId, message_id: String) -> Self {
+        Self {
+            user_id,
+            channel_id,
+            message_id,
+            timestamp: Date::now(),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -155,6 +384,20 @@ pub enum MessageError {
     CannotGetById,
     #[error("Cannot convert the model to the struct")]
     ModelToStruct,
+    #[error("Only one of before, after or around can be used")]
+    TooManyCursors,
+    #[error("Limit should be greater than 0")]
+    InvalidLimit,
+    #[error("Cannot update read state")]
+    CannotUpdateReadState,
+    #[error("Cannot delete the message")]
+    CannotDelete,
+    #[error("Replied message isn't a message of this channel")]
+    InvalidReply,
+    #[error("Cannot update the message")]
+    CannotUpdate,
+    #[error("Cannot get revisions of the message")]
+    CannotGetRevisions,
     #[error("{0}")]
     GenericSqlError(Box<GenericSqlError>),
 }
@@ -1,6 +1,11 @@
 //! This module is related to permission
 
-use fydia_utils::serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+use fydia_utils::{
+    serde::{Deserialize, Serialize},
+    serde_json::Value,
+};
 use thiserror::Error;
 
 use crate::{
@@ -32,77 +37,211 @@ impl Permissions {
         self.0
     }
 
-    /// Return true if user can read
-    pub fn can_read(&self) -> bool {
-        if let Ok(value) = self.calculate(None) {
-            return value.can_read();
+    /// Return value of permissions.
+    ///
+    /// Permissions are resolved in this order:
+    /// * server permissions of roles (role permissions without channel) are merged
+    /// * admins have all permissions
+    /// * role overwrites of the channel are merged, their denied flags are removed
+    ///   then their allowed flags are added
+    /// * denied flags of the user overwrite are removed then its allowed flags are added
+    ///
+    /// `Admin` cannot be given by an overwrite.
+    pub fn value(&self) -> u64 {
+        self.resolve(false)
+    }
+
+    /// Return value of permissions like `value`, owner have all permissions
+    pub fn resolve(&self, is_owner: bool) -> u64 {
+        if is_owner {
+            return PermissionValue::all();
+        }
+
+        let base = self
+            .0
+            .iter()
+            .filter(|i| i.is_role() && i.channelid.is_none())
+            .fold(0, |value, i| value | i.value);
+
+        if PermissionValue::value_can(base, &PermissionValue::Admin) {
+            return PermissionValue::all();
         }
 
-        false
+        let (allow, deny) = self
+            .0
+            .iter()
+            .filter(|i| i.is_role() && i.channelid.is_some())
+            .fold((0, 0), |(allow, deny), i| (allow | i.value, deny | i.deny));
+
+        let mut value = (base & !deny) | allow;
+
+        for i in self.0.iter().filter(|i| i.is_user()) {
+            value = (value & !i.deny) | i.value;
+        }
+
+        value & !PermissionValue::Admin.to_u64()
+    }
+
+    /// Return true if user can read
+    pub fn can_read(&self) -> bool {
+        self.can(&PermissionValue::Read)
     }
 
     /// Return true if user can write
     pub fn can_write(&self) -> bool {
-        if let Ok(value) = self.calculate(None) {
-            return value.can_write();
-        }
-
-        false
+        self.can(&PermissionValue::Write)
     }
 
     /// Return true if user is admin
     pub fn is_admin(&self) -> bool {
-        if let Ok(value) = self.calculate(None) {
-            return value.can_write();
-        }
-
-        false
+        self.can(&PermissionValue::Admin)
     }
 
-    /// Return true if user can do this
+    /// Return true if user can do this.
+    ///
+    /// Admin can do everything.
     pub fn can(&self, pvalue: &PermissionValue) -> bool {
-        for i in &self.0 {
-            if !i.can(pvalue) {
-                return false;
-            }
-        }
+        PermissionValue::value_can(self.value(), pvalue)
+    }
 
-        true
+    /// Return resolved permissions with the reason of each flag.
+    ///
+    /// Flags follow the same resolution as `resolve`.
+    pub fn effective(&self, is_owner: bool) -> EffectivePermission {
+        let value = self.resolve(is_owner);
+
+        let roles_with = |channel: bool, flags: &dyn Fn(&Permission) -> u64, flag: u64| {
+            self.0
+                .iter()
+                .filter(|i| i.is_role() && i.channelid.is_some() == channel)
+                .filter(|i| flags(i) & flag == flag)
+                .filter_map(|i| match &i.permission_type {
+                    PermissionType::Role(role) => Some(role.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<RoleId>>()
+        };
+
+        let admin_roles = roles_with(false, &|i| i.value, PermissionValue::Admin.to_u64());
+        let user = self.0.iter().find(|i| i.is_user());
+
+        let details = PermissionValue::ALL
+            .iter()
+            .map(|permission| {
+                let flag = permission.to_u64();
+                let (allowed, source) = if is_owner {
+                    (true, PermissionSource::Owner)
+                } else if !admin_roles.is_empty() {
+                    (
+                        true,
+                        PermissionSource::Admin {
+                            roles: admin_roles.clone(),
+                        },
+                    )
+                } else {
+                    let overwritable = *permission != PermissionValue::Admin;
+                    let allow_roles = roles_with(true, &|i| i.value, flag);
+                    let deny_roles = roles_with(true, &|i| i.deny, flag);
+                    let base_roles = roles_with(false, &|i| i.value, flag);
+
+                    match user {
+                        Some(user) if overwritable && user.value & flag == flag => {
+                            (true, PermissionSource::UserOverwrite)
+                        }
+                        Some(user) if overwritable && user.deny & flag == flag => {
+                            (false, PermissionSource::UserOverwrite)
+                        }
+                        _ if overwritable && !allow_roles.is_empty() => {
+                            (true, PermissionSource::RoleOverwrite { roles: allow_roles })
+                        }
+                        _ if overwritable && !deny_roles.is_empty() => {
+                            (false, PermissionSource::RoleOverwrite { roles: deny_roles })
+                        }
+                        _ if !base_roles.is_empty() => {
+                            (true, PermissionSource::Role { roles: base_roles })
+                        }
+                        _ => (false, PermissionSource::None),
+                    }
+                };
+
+                PermissionDetail {
+                    permission: *permission,
+                    allowed,
+                    source,
+                }
+            })
+            .collect();
+
+        EffectivePermission {
+            value,
+            permissions: PermissionValue::from_value(value),
+            details,
+        }
     }
 
-    /// Take multiple permissions and return one
+    /// Take multiple permissions and return the resolved permission of a channel.
     ///
-    /// User permisison is privileged to a role permission.
+    /// See `value` for the resolution order.
     ///
     /// # Errors
     /// Return an error if :
-    /// * there is no `Permission` have `PermissionType::User` as type
+    /// * there is no channelid
     pub fn calculate(&self, channelid: Option<ChannelId>) -> Result<Permission, PermissionError> {
-        if let Some(user_perm) = self
-            .0
-            .iter()
-            .find(|i| matches!(i.permission_type, PermissionType::User(_)))
-        {
-            return Ok(user_perm.clone());
-        }
-
-        let mut value = 0;
-        for i in &self.0 {
-            if let PermissionType::Role(_) = i.permission_type {
-                value |= i.value;
-            }
-        }
-
         let channelid = channelid.ok_or_else(|| PermissionError::NoChannelId)?;
 
         Ok(Permission {
             permission_type: PermissionType::Channel(channelid.clone()),
             channelid: Some(channelid),
-            value,
+            value: self.value(),
+            deny: 0,
         })
     }
 }
 
+/// `EffectivePermission` is the resolved permission of a member in a channel
+#[allow(missing_docs)]
+#[derive(Debug, Serialize)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct EffectivePermission {
+    pub value: u64,
+    pub permissions: Vec<PermissionValue>,
+    pub details: Vec<PermissionDetail>,
+}
+
+/// `PermissionDetail` tells if a flag is allowed and why
+#[allow(missing_docs)]
+#[derive(Debug, Serialize)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct PermissionDetail {
+    pub permission: PermissionValue,
+    pub allowed: bool,
+    pub source: PermissionSource,
+}
+
+/// `PermissionSource` is what allowed or denied a flag
+#[allow(missing_docs)]
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde", tag = "type", rename_all = "snake_case")]
+pub enum PermissionSource {
+    /// Member owns the server
+    Owner,
+    /// Member has admin roles
+    Admin { roles: Vec<RoleId> },
+    /// Member overwrite of the channel
+    UserOverwrite,
+    /// Role overwrites of the channel
+    RoleOverwrite { roles: Vec<RoleId> },
+    /// Server permissions of roles
+    Role { roles: Vec<RoleId> },
+    /// Nothing allowed the flag
+    None,
+}
+
+/// `Permission` is a permission of a role or a user.
+///
+/// Without channel, it's the server permission of a role.
+/// With a channel, it's an overwrite: `value` contains allowed flags and
+/// `deny` contains denied flags.
 #[allow(missing_docs)]
 #[derive(Clone, Deserialize, Serialize, Debug)]
 #[serde(crate = "fydia_utils::serde")]
@@ -111,6 +250,8 @@ pub struct Permission {
     pub permission_type: PermissionType,
     pub channelid: Option<ChannelId>,
     pub value: u64,
+    #[serde(default)]
+    pub deny: u64,
 }
 
 impl Permission {
@@ -120,6 +261,7 @@ impl Permission {
             permission_type: PermissionType::Role(role),
             channelid,
             value,
+            deny: 0,
         }
     }
     /// Create a new User permission
@@ -128,9 +270,26 @@ impl Permission {
             permission_type: PermissionType::User(userid),
             channelid,
             value,
+            deny: 0,
         }
     }
 
+    /// Set denied flags of this overwrite
+    pub fn with_deny(mut self, deny: u64) -> Self {
+        self.deny = deny;
+        self
+    }
+
+    /// Return true if this is a role permission
+    pub fn is_role(&self) -> bool {
+        matches!(self.permission_type, PermissionType::Role(_))
+    }
+
+    /// Return true if this is a user permission
+    pub fn is_user(&self) -> bool {
+        matches!(self.permission_type, PermissionType::User(_))
+    }
+
     /// Return true if user can read
     pub fn can_read(&self) -> bool {
         self.can(&PermissionValue::Read)
@@ -146,10 +305,11 @@ impl Permission {
         self.can(&PermissionValue::Admin)
     }
 
-    /// Return true if user can do the `PermissionValue`
-    fn can(&self, pvalue: &PermissionValue) -> bool {
-        let perm = pvalue.to_u64();
-        self.value & perm == perm
+    /// Return true if user can do the `PermissionValue`.
+    ///
+    /// Admin can do everything.
+    pub fn can(&self, pvalue: &PermissionValue) -> bool {
+        PermissionValue::value_can(self.value, pvalue)
     }
 }
 
@@ -159,6 +319,7 @@ impl Default for Permission {
             permission_type: PermissionType::User(UserId::default()),
             channelid: Option::default(),
             value: Default::default(),
+            deny: Default::default(),
         }
     }
 }
@@ -189,6 +350,8 @@ pub enum PermissionError {
     UserError(Box<UserError>),
     #[error("{0}")]
     ChannelError(Box<ChannelError>),
+    #[error("Unknown permission `{0}`")]
+    UnknownPermission(String),
     #[error("{0}")]
     Other(String),
 }
@@ -237,19 +400,168 @@ pub enum PermissionType {
     Channel(ChannelId),
 }
 
-/// `Permission` contains all permission as enum
+/// `PermissionValue` is one flag of a permission value.
+///
+/// A permission value is a `u64` where each flag is a bit. Bits are stored in
+/// database and sent to clients so a flag must never change of bit, a new
+/// flag takes the next free bit.
+///
+/// Flags can also be written with their snake case names
+/// (`["read", "write", "attach_files"]`).
 #[allow(missing_docs)]
-#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
-#[serde(crate = "fydia_utils::serde")]
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde", rename_all = "snake_case")]
 pub enum PermissionValue {
-    Admin = (1 << 2),
-    Write = (1 << 1),
-    Read = (1 << 0),
     None = 0,
+    Read = (1 << 0),
+    Write = (1 << 1),
+    Admin = (1 << 2),
+    ManageServer = (1 << 3),
+    ManageChannels = (1 << 4),
+    ManageRoles = (1 << 5),
+    KickMembers = (1 << 6),
+    BanMembers = (1 << 7),
+    ManageMessages = (1 << 8),
+    AttachFiles = (1 << 9),
+    MentionEveryone = (1 << 10),
+    AddReactions = (1 << 11),
+    ManageInvites = (1 << 12),
+    ViewAuditLog = (1 << 13),
+    CreateInvites = (1 << 14),
+    ManageEmojis = (1 << 15),
+    ChangeNickname = (1 << 16),
+    ManageNicknames = (1 << 17),
 }
 
 impl PermissionValue {
-    fn to_u64(&self) -> u64 {
-        self.clone() as u64
+    /// All flags ordered by bit
+    pub const ALL: [PermissionValue; 18] = [
+        PermissionValue::Read,
+        PermissionValue::Write,
+        PermissionValue::Admin,
+        PermissionValue::ManageServer,
+        PermissionValue::ManageChannels,
+        PermissionValue::ManageRoles,
+        PermissionValue::KickMembers,
+        PermissionValue::BanMembers,
+        PermissionValue::ManageMessages,
+        PermissionValue::AttachFiles,
+        PermissionValue::MentionEveryone,
+        PermissionValue::AddReactions,
+        PermissionValue::ManageInvites,
+        PermissionValue::ViewAuditLog,
+        PermissionValue::CreateInvites,
+        PermissionValue::ManageEmojis,
+        PermissionValue::ChangeNickname,
+        PermissionValue::ManageNicknames,
+    ];
+
+    /// Return the bit of this flag
+    pub fn to_u64(self) -> u64 {
+        self as u64
+    }
+
+    /// Return a value with every known flag
+    pub fn all() -> u64 {
+        Self::ALL
+            .iter()
+            .fold(0, |value, flag| value | flag.to_u64())
+    }
+
+    /// Return true if `value` contains `pvalue` or is admin
+    pub fn value_can(value: u64, pvalue: &PermissionValue) -> bool {
+        let perm = pvalue.to_u64();
+        let admin = PermissionValue::Admin.to_u64();
+
+        value & perm == perm || value & admin == admin
+    }
+
+    /// Return flags of `value`
+    pub fn from_value(value: u64) -> Vec<PermissionValue> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|flag| value & flag.to_u64() != 0)
+            .collect()
+    }
+
+    /// Convert a value to the signed integer stored in database.
+    ///
+    /// Unknown bits are dropped.
+    pub fn to_database(value: u64) -> i64 {
+        i64::try_from(value & Self::all()).unwrap_or_default()
+    }
+
+    /// Convert a value stored in database.
+    ///
+    /// Unknown bits are dropped.
+    pub fn from_database(value: i64) -> u64 {
+        u64::try_from(value).unwrap_or_default() & Self::all()
+    }
+
+    /// Return snake case name of this flag
+    pub fn name(self) -> &'static str {
+        match self {
+            PermissionValue::None => "none",
+            PermissionValue::Read => "read",
+            PermissionValue::Write => "write",
+            PermissionValue::Admin => "admin",
+            PermissionValue::ManageServer => "manage_server",
+            PermissionValue::ManageChannels => "manage_channels",
+            PermissionValue::ManageRoles => "manage_roles",
+            PermissionValue::KickMembers => "kick_members",
+            PermissionValue::BanMembers => "ban_members",
+            PermissionValue::ManageMessages => "manage_messages",
+            PermissionValue::AttachFiles => "attach_files",
+            PermissionValue::MentionEveryone => "mention_everyone",
+            PermissionValue::AddReactions => "add_reactions",
+            PermissionValue::ManageInvites => "manage_invites",
+            PermissionValue::ViewAuditLog => "view_audit_log",
+            PermissionValue::CreateInvites => "create_invites",
+            PermissionValue::ManageEmojis => "manage_emojis",
+            PermissionValue::ChangeNickname => "change_nickname",
+            PermissionValue::ManageNicknames => "manage_nicknames",
+        }
+    }
+
+    /// Return flag with this snake case name
+    pub fn from_name(name: &str) -> Option<PermissionValue> {
+        Self::ALL.iter().copied().find(|flag| flag.name() == name)
+    }
+
+    /// Parse a permission value from json.
+    ///
+    /// Value can be a number, a string of a number or an array of flag names.
+    ///
+    /// # Errors
+    /// Return an error if value has a unknown flag or bit
+    pub fn from_json(json: &Value) -> Result<u64, PermissionError> {
+        let value = match json {
+            Value::Number(number) => number
+                .as_u64()
+                .ok_or_else(|| PermissionError::UnknownPermission(number.to_string()))?,
+            Value::String(string) => string
+                .parse()
+                .map_err(|_error| PermissionError::UnknownPermission(string.clone()))?,
+            Value::Array(names) => {
+                let mut value = 0;
+                for name in names {
+                    let flag = name
+                        .as_str()
+                        .and_then(PermissionValue::from_name)
+                        .ok_or_else(|| PermissionError::UnknownPermission(name.to_string()))?;
+                    value |= flag.to_u64();
+                }
+
+                value
+            }
+            _ => return Err(PermissionError::UnknownPermission(json.to_string())),
+        };
+
+        if value & !Self::all() != 0 {
+            return Err(PermissionError::UnknownPermission(value.to_string()));
+        }
+
+        Ok(value)
     }
 }
@@ -0,0 +1,73 @@
+//! This module is related to pinned messages of channels
+
+use crate::{
+    messages::{Date, Message},
+    sqlerror::{GenericError, GenericSqlError},
+    user::UserId,
+    utils::IdError,
+};
+use fydia_utils::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// `Pin` is a message pinned in its channel with who pinned it and when
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct Pin {
+    pub message: Box<Message>,
+    pub pinned_by: UserId,
+    pub pinned_at: Date,
+}
+
+impl Pin {
+    /// Create a new `Pin` of a message pinned now by `pinned_by`
+    pub fn new(message: Message, pinned_by: UserId) -> Self {
+        Self {
+            message: Box::new(message),
+            pinned_by,
+            pinned_at: Date::now(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+/// `PinError` represents all errors of `Pin`
+pub enum PinError {
+    #[error("Message is already pinned")]
+    AlreadyPinned,
+    #[error("Message isn't pinned")]
+    NotPinned,
+    #[error("Channel has too many pinned messages")]
+    TooManyPins,
+    #[error("Cannot get pins")]
+    CannotGet,
+    #[error("Cannot insert the pin")]
+    CannotInsert,
+    #[error("Cannot delete the pin")]
+    CannotDelete,
+    #[error("Cannot convert pin in ActiveModel")]
+    CannotIntoActiveModel,
+}
+
+impl From<IdError> for PinError {
+    fn from(_: IdError) -> Self {
+        Self::CannotIntoActiveModel
+    }
+}
+
+impl From<GenericSqlError> for PinError {
+    fn from(value: GenericSqlError) -> Self {
+        match value {
+            GenericSqlError::CannotInsert(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotInsert
+            }
+            GenericSqlError::CannotUpdate(GenericError { error, .. })
+            | GenericSqlError::CannotDelete(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotDelete
+            }
+        }
+    }
+}
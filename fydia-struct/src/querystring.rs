@@ -9,3 +9,49 @@ use fydia_utils::serde::Deserialize;
 pub struct QsToken {
     pub token: Option<String>,
 }
+
+/// Get the Url Parameter of messages pagination like ?before=MESSAGEID&limit=50
+#[allow(missing_docs)]
+#[derive(Debug, Deserialize)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct QsMessages {
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub around: Option<String>,
+    pub limit: Option<u64>,
+}
+
+/// Get the Url Parameter of a picture like ?size=64
+#[allow(missing_docs)]
+#[derive(Debug, Deserialize)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct QsPicture {
+    pub size: Option<u32>,
+}
+
+/// Get the Url Parameter of members pagination like ?after=USERID&search=NAME&limit=100
+#[allow(missing_docs)]
+#[derive(Debug, Deserialize)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct QsMembers {
+    pub after: Option<u32>,
+    pub search: Option<String>,
+    pub limit: Option<u64>,
+}
+
+/// Get the Url Parameter of emoji creation like ?name=NAME
+#[allow(missing_docs)]
+#[derive(Debug, Deserialize)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct QsEmoji {
+    pub name: String,
+}
+
+/// Get the Url Parameter of threads of a channel like ?archived=true
+#[allow(missing_docs)]
+#[derive(Debug, Deserialize)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct QsThreads {
+    #[serde(default)]
+    pub archived: bool,
+}
@@ -0,0 +1,114 @@
+//! This module is related to reactions on messages
+
+use crate::{
+    emoji::EmojiId,
+    sqlerror::{GenericError, GenericSqlError},
+    utils::IdError,
+};
+use fydia_utils::serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Max lenght in bytes of an unicode emoji used as reaction
+pub const UNICODE_EMOJI_MAX_LENGHT: usize = 32;
+
+/// `ReactionEmoji` is the emoji of a reaction, an unicode emoji or a custom emoji of the server
+///
+/// Serialized as `{"unicode": "👍"}` or `{"custom": 1}`
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde", rename_all = "snake_case")]
+pub enum ReactionEmoji {
+    Unicode(String),
+    Custom(EmojiId),
+}
+
+impl ReactionEmoji {
+    /// Check an unicode emoji.
+    ///
+    /// An unicode emoji cannot be empty, contain whitespace, be only ascii characters
+    /// or be longer than `UNICODE_EMOJI_MAX_LENGHT`.
+    /// Custom emojis are checked against emojis of the server.
+    ///
+    /// # Errors
+    /// Return an error if unicode emoji isn't valid
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::reaction::ReactionEmoji;
+    ///
+    /// assert!(ReactionEmoji::Unicode("👍".to_string()).check().is_ok());
+    /// assert!(ReactionEmoji::Unicode("ok".to_string()).check().is_err());
+    /// assert!(ReactionEmoji::Unicode("👍 👍".to_string()).check().is_err());
+    /// ```
+    pub fn check(&self) -> Result<(), ReactionError> {
+        let Self::Unicode(emoji) = self else {
+            return Ok(());
+        };
+
+        if emoji.is_empty()
+            || emoji.len() > UNICODE_EMOJI_MAX_LENGHT
+            || emoji.is_ascii()
+            || emoji.chars().any(char::is_whitespace)
+        {
+            return Err(ReactionError::InvalidEmoji);
+        }
+
+        Ok(())
+    }
+}
+
+/// `Reaction` is the count of users that reacted to a message with the same emoji.
+///
+/// `me` is true if the user that requested the message is one of them.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub struct Reaction {
+    pub emoji: ReactionEmoji,
+    pub count: u64,
+    pub me: bool,
+}
+
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+/// `ReactionError` represents all errors of `Reaction`
+pub enum ReactionError {
+    #[error("Emoji isn't a valid unicode emoji")]
+    InvalidEmoji,
+    #[error("Emoji isn't an emoji of this server")]
+    UnknownEmoji,
+    #[error("You already reacted with this emoji")]
+    AlreadyReacted,
+    #[error("You didn't react with this emoji")]
+    NoReaction,
+    #[error("Cannot get reactions")]
+    CannotGet,
+    #[error("Cannot insert the reaction")]
+    CannotInsert,
+    #[error("Cannot delete the reaction")]
+    CannotDelete,
+    #[error("Cannot convert reaction in ActiveModel")]
+    CannotIntoActiveModel,
+}
+
+impl From<IdError> for ReactionError {
+    fn from(_: IdError) -> Self {
+        Self::CannotIntoActiveModel
+    }
+}
+
+impl From<GenericSqlError> for ReactionError {
+    fn from(value: GenericSqlError) -> Self {
+        match value {
+            GenericSqlError::CannotInsert(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotInsert
+            }
+            GenericSqlError::CannotUpdate(GenericError { error, .. })
+            | GenericSqlError::CannotDelete(GenericError { error, .. }) => {
+                error!("{error}");
+                Self::CannotDelete
+            }
+        }
+    }
+}
@@ -216,11 +216,12 @@ impl IntoResponse for FydiaResponse {
             FydiaResponse::StringError(text) => {
                 build_response(FydiaStatus::Error, json!(text.to_string()), response)
             }
-            FydiaResponse::StringWithStatusCode(statuscode, text) => build_response(
-                FydiaStatus::Ok,
-                json!(text.to_string()),
-                response.status(statuscode),
-            ),
+            FydiaResponse::StringWithStatusCode(statuscode, text) => {
+                let mut response =
+                    build_response(FydiaStatus::Error, json!(text.to_string()), response);
+                *response.status_mut() = statuscode;
+                response
+            }
             FydiaResponse::Json(value) => build_response(FydiaStatus::Ok, value, response),
             FydiaResponse::Bytes(bytes) => {
                 response.body(body::boxed(body::Full::from(bytes))).unwrap()
@@ -1,18 +1,27 @@
 //! This module is related to roles
 
 use crate::{
+    permission::PermissionValue,
     server::ServerId,
     sqlerror::{GenericError, GenericSqlError},
     utils::{Id, IdError},
 };
 use fydia_utils::serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 use thiserror::Error;
 
 /// Id of Role
 pub type RoleId = Id<u32>;
-/// `Role` contains all value of roles
+
+/// Name of the role every member of a server has
+pub const EVERYONE_NAME: &str = "@everyone";
+
+/// `Role` contains all value of roles.
+///
+/// Roles are ordered by `position`, a member can only manage roles and members
+/// below their highest role. `@everyone` is always at position 0.
 #[allow(missing_docs)]
-#[derive(Clone, Deserialize, Serialize, Debug)]
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(crate = "fydia_utils::serde")]
 pub struct Role {
     pub id: RoleId,
@@ -20,6 +29,162 @@ pub struct Role {
     pub name: String,
     pub color: String,
     pub server_permission: u64,
+    #[serde(default)]
+    pub position: u32,
+}
+
+impl Role {
+    /// Create a new `Role` in a server.
+    ///
+    /// `Role.id` is unset until the role is inserted in database.
+    /// A new role is placed just above `@everyone`.
+    ///
+    /// # Errors
+    /// Return an error if:
+    /// * name is empty
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::{roles::Role, server::ServerId};
+    ///
+    /// let role = Role::new(ServerId::new("server"), "moderator", "ff0000", 0).unwrap();
+    /// assert!(role.id.is_not_set());
+    /// ```
+    pub fn new<T: Into<String>>(
+        server_id: ServerId,
+        name: T,
+        color: T,
+        server_permission: u64,
+    ) -> Result<Self, RoleError> {
+        let name = name.into();
+
+        if name.is_empty() {
+            return Err(RoleError::EmptyName);
+        }
+
+        Ok(Self {
+            id: Id::Unset,
+            server_id,
+            name,
+            color: color.into(),
+            server_permission,
+            position: 1,
+        })
+    }
+
+    /// Create the `@everyone` role of a server
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::{roles::Role, server::ServerId};
+    ///
+    /// let role = Role::everyone(ServerId::new("server"));
+    /// assert!(role.is_everyone());
+    /// ```
+    pub fn everyone(server_id: ServerId) -> Self {
+        let server_permission = [
+            PermissionValue::Read,
+            PermissionValue::Write,
+            PermissionValue::AttachFiles,
+            PermissionValue::AddReactions,
+            PermissionValue::CreateInvites,
+            PermissionValue::ChangeNickname,
+        ]
+        .iter()
+        .fold(0, |value, flag| value | flag.to_u64());
+
+        Self {
+            id: Id::Unset,
+            server_id,
+            name: EVERYONE_NAME.to_string(),
+            color: String::from("#ffffff"),
+            server_permission,
+            position: 0,
+        }
+    }
+
+    /// Return true if color is formatted as `#RRGGBB`
+    ///
+    /// # Examples
+    /// ```
+    /// use fydia_struct::roles::Role;
+    ///
+    /// assert!(Role::is_valid_color("#ff00AA"));
+    /// assert!(!Role::is_valid_color("ff00aa"));
+    /// assert!(!Role::is_valid_color("#ff00ag"));
+    /// ```
+    pub fn is_valid_color(color: &str) -> bool {
+        color
+            .strip_prefix('#')
+            .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+
+    /// Return true if role is `@everyone`
+    pub fn is_everyone(&self) -> bool {
+        self.position == 0
+    }
+
+    /// Return the position of the highest role or 0 if there is no role
+    pub fn highest_position(roles: &[Role]) -> u32 {
+        roles.iter().map(|role| role.position).max().unwrap_or(0)
+    }
+
+    /// Set positions of `roles` from `order`.
+    ///
+    /// `order` contains ids of every role except `@everyone` from the lowest to the highest.
+    /// Roles at or above `limit` cannot be moved, no limit is given for the owner.
+    ///
+    /// Return roles that have been moved.
+    ///
+    /// # Errors
+    /// Return an error if:
+    /// * `order` doesn't contain every role once
+    /// * a role at or above `limit` is moved
+    pub fn reorder(
+        roles: &mut [Role],
+        order: &[RoleId],
+        limit: Option<u32>,
+    ) -> Result<Vec<Role>, RoleError> {
+        let movable = roles.iter().filter(|role| !role.is_everyone()).count();
+
+        if order.len() != movable {
+            return Err(RoleError::InvalidOrder);
+        }
+
+        let mut positions = Vec::with_capacity(roles.len());
+
+        for role in roles.iter() {
+            if role.is_everyone() {
+                positions.push(0);
+                continue;
+            }
+
+            let Some(index) = order.iter().position(|id| *id == role.id) else {
+                return Err(RoleError::InvalidOrder);
+            };
+
+            let position = u32::try_from(index + 1).map_err(|_error| RoleError::InvalidOrder)?;
+
+            if let Some(limit) = limit {
+                if position != role.position && (position >= limit || role.position >= limit) {
+                    return Err(RoleError::AboveHighestRole);
+                }
+            }
+
+            positions.push(position);
+        }
+
+        let mut moved = Vec::new();
+
+        for (role, position) in roles.iter_mut().zip(positions) {
+            if role.position != position {
+                role.position = position;
+                moved.push(role.clone());
+            }
+        }
+
+        Ok(moved)
+    }
 }
 
 #[derive(Debug, Error)]
@@ -32,12 +197,26 @@ pub enum RoleError {
     CannotUpdateName,
     #[error("Cannot update cole of the role")]
     CannotUpdateColor,
+    #[error("Cannot update permission of the role")]
+    CannotUpdatePermission,
     #[error("Cannot insert the role")]
     CannotInsert,
     #[error("Cannot delete the role")]
     CannotDelete,
     #[error("Cannot add this user to role")]
     CannotAddUser,
+    #[error("Cannot remove this user from role")]
+    CannotRemoveUser,
+    #[error("Role have an empty name")]
+    EmptyName,
+    #[error("Color should be formatted as #RRGGBB")]
+    InvalidColor,
+    #[error("Cannot change the order of roles")]
+    CannotReorder,
+    #[error("Order must contain every role of the server once")]
+    InvalidOrder,
+    #[error("Role isn't below your highest role")]
+    AboveHighestRole,
 }
 
 impl From<IdError> for RoleError {
@@ -53,10 +232,14 @@ impl From<GenericSqlError> for RoleError {
             GenericSqlError::CannotUpdate(GenericError { set_column, error }) => {
                 error!("{error}");
 
-                if set_column.contains(&"color".to_string()) {
+                if set_column.contains(&"Color".to_string()) {
                     return Self::CannotUpdateColor;
                 }
 
+                if set_column.contains(&"ServerPermission".to_string()) {
+                    return Self::CannotUpdatePermission;
+                }
+
                 Self::CannotUpdateName
             }
             GenericSqlError::CannotDelete(_) => Self::CannotDelete,
@@ -24,6 +24,8 @@ pub struct Server {
     pub owner: UserId,
     pub icon: String,
     pub emoji: Vec<Emoji>,
+    /// Members are listed with their own paginated route
+    #[serde(skip)]
     pub members: Members,
     pub roles: Vec<Role>,
     pub channel: Channels,
@@ -84,6 +86,10 @@ pub enum ServerError {
     CannotGetOwner,
     #[error("This server is already join")]
     AlreadyJoin,
+    #[error("Cannot create the @everyone role")]
+    CannotInsertEveryone,
+    #[error("Cannot delete the server")]
+    CannotDelete,
     #[error("{0}")]
     GenericSqlError(Box<GenericSqlError>),
     #[error("{0}")]
@@ -220,6 +226,16 @@ pub enum MembersError {
     CannotGetUsers,
     #[error("Cannot get users with this expression")]
     CannotGetMembersWithThisExpr,
+    #[error("This user isn't a member of this server")]
+    NotMember,
+    #[error("Cannot remove this member")]
+    CannotRemove,
+    #[error("Limit should be greater than 0")]
+    InvalidLimit,
+    #[error("Nickname is too long")]
+    NicknameTooLong,
+    #[error("Cannot update this member")]
+    CannotUpdate,
     #[error("{0}")]
     GenericSqlError(Box<GenericSqlError>),
 }
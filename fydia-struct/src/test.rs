@@ -90,4 +90,638 @@ mod tests {
             }
         }
     }
+
+    mod websocket {
+        use crate::{
+            channel::ChannelId,
+            event::{Event, EventContent},
+            server::ServerId,
+            websocket::{WebsocketCommand, WebsocketErrorCode, WebsocketFrame, WebsocketSession},
+        };
+        use std::time::{Duration, Instant};
+
+        fn event(message_id: &str) -> Event {
+            Event::new(
+                ServerId::new("server"),
+                EventContent::MessageDelete {
+                    message_id: message_id.to_string(),
+                },
+            )
+        }
+
+        #[test]
+        pub fn command_start_typing() {
+            assert_eq!(
+                WebsocketCommand::from_json(
+                    r#"{"type":"StartTyping","server_id":{"id":"server"},"channel_id":{"id":"channel"}}"#
+                )
+                .ok(),
+                Some(WebsocketCommand::StartTyping {
+                    server_id: ServerId::new("server"),
+                    channel_id: ChannelId { id: "channel".into() },
+                })
+            );
+        }
+
+        #[test]
+        pub fn command_ping_without_nonce() {
+            assert_eq!(
+                WebsocketCommand::from_json(r#"{"type":"Ping"}"#).ok(),
+                Some(WebsocketCommand::Ping { nonce: None })
+            );
+        }
+
+        #[test]
+        pub fn command_unknown() {
+            let error = WebsocketCommand::from_json(r#"{"type":"Unknown"}"#)
+                .err()
+                .map(|error| WebsocketFrame::error(&error, None));
+
+            assert!(matches!(
+                error,
+                Some(WebsocketFrame::Error {
+                    code: WebsocketErrorCode::InvalidCommand,
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        pub fn session_replay() {
+            let mut session = WebsocketSession::new(2);
+            assert_eq!(session.push(event("1")), 1);
+            assert_eq!(session.push(event("2")), 2);
+            assert_eq!(session.push(event("3")), 3);
+
+            assert_eq!(session.replay_after(3), Some(Vec::new()));
+            assert_eq!(
+                session.replay_after(1),
+                Some(vec![(2, event("2")), (3, event("3"))])
+            );
+        }
+
+        #[test]
+        pub fn session_replay_too_old() {
+            let mut session = WebsocketSession::new(2);
+            for i in 0..3 {
+                session.push(event(&i.to_string()));
+            }
+
+            assert_eq!(session.replay_after(0), None);
+            assert_eq!(session.replay_after(4), None);
+        }
+
+        #[test]
+        pub fn session_expires_after_disconnect() {
+            let mut session = WebsocketSession::new(2);
+            let now = Instant::now();
+            let window = Duration::from_secs(60);
+            assert!(!session.is_expired(now + window * 2, window));
+
+            session.set_connected(false, now);
+            assert!(!session.is_expired(now + window, window));
+            assert!(session.is_expired(now + window * 2, window));
+
+            session.set_connected(true, now + window);
+            assert!(!session.is_expired(now + window * 2, window));
+        }
+    }
+
+    mod file {
+        use crate::file::{ByteRange, ByteRangeError};
+
+        #[test]
+        pub fn range_start_end() {
+            assert_eq!(
+                ByteRange::parse("bytes=0-9", 100),
+                Ok(ByteRange { start: 0, end: 9 })
+            );
+        }
+
+        #[test]
+        pub fn range_open_end() {
+            let range = ByteRange::parse("bytes=90-", 100);
+            assert_eq!(range, Ok(ByteRange { start: 90, end: 99 }));
+            assert_eq!(range.map(|range| range.length()), Ok(10));
+        }
+
+        #[test]
+        pub fn range_end_after_size() {
+            assert_eq!(
+                ByteRange::parse("bytes=50-1000", 100),
+                Ok(ByteRange { start: 50, end: 99 })
+            );
+        }
+
+        #[test]
+        pub fn range_unsatisfiable() {
+            assert_eq!(
+                ByteRange::parse("bytes=100-", 100),
+                Err(ByteRangeError::Unsatisfiable)
+            );
+        }
+
+        #[test]
+        pub fn range_invalid() {
+            assert_eq!(
+                ByteRange::parse("bytes=0-1,5-6", 100),
+                Err(ByteRangeError::Invalid)
+            );
+            assert_eq!(
+                ByteRange::parse("items=0-1", 100),
+                Err(ByteRangeError::Invalid)
+            );
+            assert_eq!(
+                ByteRange::parse("bytes=9-1", 100),
+                Err(ByteRangeError::Invalid)
+            );
+        }
+    }
+
+    mod permission {
+        use crate::{
+            channel::ChannelId,
+            permission::{Permission, PermissionSource, PermissionValue, Permissions},
+            user::UserId,
+            utils::Id,
+        };
+        use fydia_utils::serde_json::json;
+
+        #[test]
+        pub fn legacy_bits_are_stable() {
+            assert_eq!(PermissionValue::Read.to_u64(), 1);
+            assert_eq!(PermissionValue::Write.to_u64(), 2);
+            assert_eq!(PermissionValue::Admin.to_u64(), 4);
+            assert_eq!(PermissionValue::ManageServer.to_u64(), 8);
+        }
+
+        #[test]
+        pub fn flags_have_unique_bits_and_names() {
+            let mut value = 0;
+            for flag in PermissionValue::ALL {
+                assert_eq!(value & flag.to_u64(), 0);
+                assert_eq!(PermissionValue::from_name(flag.name()), Some(flag));
+                value |= flag.to_u64();
+            }
+
+            assert_eq!(value, PermissionValue::all());
+        }
+
+        #[test]
+        pub fn from_json() {
+            assert_eq!(PermissionValue::from_json(&json!(3)).ok(), Some(3));
+            assert_eq!(PermissionValue::from_json(&json!("3")).ok(), Some(3));
+            assert_eq!(
+                PermissionValue::from_json(&json!(["read", "attach_files"])).ok(),
+                Some(1 | 1 << 9)
+            );
+            assert!(PermissionValue::from_json(&json!(["fly"])).is_err());
+            assert!(PermissionValue::from_json(&json!(1_u64 << 62)).is_err());
+        }
+
+        #[test]
+        pub fn database_value() {
+            let value = PermissionValue::all();
+            assert_eq!(
+                PermissionValue::from_database(PermissionValue::to_database(value)),
+                value
+            );
+            assert_eq!(PermissionValue::from_database(-1), 0);
+        }
+
+        const CHANNEL: &str = "channel_default_id";
+
+        fn base(role: u32, value: u64) -> Permission {
+            Permission::role(Id::Id(role), None, value)
+        }
+
+        fn role_overwrite(role: u32, allow: u64, deny: u64) -> Permission {
+            Permission::role(Id::Id(role), Some(ChannelId::new(CHANNEL)), allow).with_deny(deny)
+        }
+
+        fn user_overwrite(allow: u64, deny: u64) -> Permission {
+            Permission::user(UserId(Id::Id(1)), Some(ChannelId::new(CHANNEL)), allow)
+                .with_deny(deny)
+        }
+
+        const READ: u64 = PermissionValue::Read as u64;
+        const WRITE: u64 = PermissionValue::Write as u64;
+        const ADMIN: u64 = PermissionValue::Admin as u64;
+        const ATTACH: u64 = PermissionValue::AttachFiles as u64;
+
+        #[test]
+        pub fn no_permission() {
+            let perms = Permissions::new(vec![]);
+
+            assert_eq!(perms.value(), 0);
+            assert!(!perms.can_read());
+        }
+
+        #[test]
+        pub fn base_roles_are_merged() {
+            let perms = Permissions::new(vec![base(1, READ), base(2, WRITE)]);
+
+            assert_eq!(perms.value(), READ | WRITE);
+            assert!(!perms.can(&PermissionValue::KickMembers));
+        }
+
+        #[test]
+        pub fn write_is_not_admin() {
+            let perms = Permissions::new(vec![base(1, WRITE)]);
+
+            assert!(perms.can_write());
+            assert!(!perms.is_admin());
+            assert!(!perms.can_read());
+        }
+
+        #[test]
+        pub fn admin_can_everything() {
+            let perms = Permissions::new(vec![base(1, ADMIN), role_overwrite(1, 0, READ)]);
+
+            assert!(perms.is_admin());
+            assert!(perms.can_read());
+            assert!(perms.can(&PermissionValue::BanMembers));
+        }
+
+        #[test]
+        pub fn owner_can_everything() {
+            let perms = Permissions::new(vec![user_overwrite(0, READ)]);
+
+            assert_eq!(perms.resolve(true), PermissionValue::all());
+            assert_eq!(perms.resolve(false), 0);
+        }
+
+        #[test]
+        pub fn overwrite_cannot_give_admin() {
+            let perms = Permissions::new(vec![
+                base(1, READ),
+                role_overwrite(1, ADMIN, 0),
+                user_overwrite(ADMIN, 0),
+            ]);
+
+            assert_eq!(perms.value(), READ);
+            assert!(!perms.is_admin());
+        }
+
+        #[test]
+        pub fn role_overwrite_denies_base() {
+            let perms = Permissions::new(vec![base(1, READ | WRITE), role_overwrite(1, 0, WRITE)]);
+
+            assert_eq!(perms.value(), READ);
+        }
+
+        #[test]
+        pub fn role_overwrite_allows() {
+            let perms = Permissions::new(vec![base(1, READ), role_overwrite(1, WRITE, 0)]);
+
+            assert_eq!(perms.value(), READ | WRITE);
+        }
+
+        #[test]
+        pub fn role_allow_wins_over_role_deny() {
+            let perms = Permissions::new(vec![
+                base(1, READ | WRITE),
+                role_overwrite(1, 0, WRITE),
+                role_overwrite(2, WRITE, 0),
+            ]);
+
+            assert_eq!(perms.value(), READ | WRITE);
+        }
+
+        #[test]
+        pub fn user_deny_wins_over_role_allow() {
+            let perms = Permissions::new(vec![
+                base(1, READ),
+                role_overwrite(1, WRITE | ATTACH, 0),
+                user_overwrite(0, WRITE),
+            ]);
+
+            assert_eq!(perms.value(), READ | ATTACH);
+        }
+
+        #[test]
+        pub fn user_allow_wins_over_role_deny() {
+            let perms = Permissions::new(vec![
+                base(1, READ | WRITE),
+                role_overwrite(1, 0, READ | WRITE),
+                user_overwrite(READ, 0),
+            ]);
+
+            assert_eq!(perms.value(), READ);
+        }
+
+        #[test]
+        pub fn base_is_kept_without_overwrite() {
+            let perms = Permissions::new(vec![base(1, READ), user_overwrite(WRITE, 0)]);
+
+            assert_eq!(perms.value(), READ | WRITE);
+        }
+
+        #[test]
+        pub fn calculate_needs_channel() {
+            let perms = Permissions::new(vec![base(1, READ)]);
+
+            assert!(perms.calculate(None).is_err());
+            assert_eq!(
+                perms
+                    .calculate(Some(ChannelId::new(CHANNEL)))
+                    .map(|perm| perm.value)
+                    .ok(),
+                Some(READ)
+            );
+        }
+
+        #[test]
+        pub fn effective_details_match_value() {
+            let cases = vec![
+                Permissions::new(vec![]),
+                Permissions::new(vec![base(1, ADMIN)]),
+                Permissions::new(vec![
+                    base(1, READ | WRITE),
+                    role_overwrite(1, ATTACH | ADMIN, WRITE),
+                    role_overwrite(2, WRITE, 0),
+                    user_overwrite(ADMIN, READ),
+                ]),
+            ];
+
+            for perms in cases {
+                let effective = perms.effective(false);
+                assert_eq!(effective.value, perms.value());
+
+                for detail in effective.details {
+                    assert_eq!(
+                        detail.allowed,
+                        PermissionValue::value_can(effective.value, &detail.permission)
+                    );
+                }
+            }
+        }
+
+        #[test]
+        pub fn effective_sources() {
+            let perms = Permissions::new(vec![
+                base(1, READ | WRITE),
+                role_overwrite(1, 0, WRITE),
+                user_overwrite(ATTACH, READ),
+            ]);
+            let effective = perms.effective(false);
+            let source = |flag: PermissionValue| {
+                effective
+                    .details
+                    .iter()
+                    .find(|detail| detail.permission == flag)
+                    .map(|detail| (detail.allowed, &detail.source))
+            };
+
+            assert_eq!(
+                source(PermissionValue::Read),
+                Some((false, &PermissionSource::UserOverwrite))
+            );
+            assert_eq!(
+                source(PermissionValue::Write),
+                Some((
+                    false,
+                    &PermissionSource::RoleOverwrite {
+                        roles: vec![Id::Id(1)]
+                    }
+                ))
+            );
+            assert_eq!(
+                source(PermissionValue::AttachFiles),
+                Some((true, &PermissionSource::UserOverwrite))
+            );
+            assert_eq!(
+                source(PermissionValue::BanMembers),
+                Some((false, &PermissionSource::None))
+            );
+            assert_eq!(
+                perms.effective(true).details[0].source,
+                PermissionSource::Owner
+            );
+        }
+    }
+
+    mod roles {
+        use crate::{
+            roles::{Role, RoleError},
+            server::ServerId,
+            utils::Id,
+        };
+
+        fn roles() -> Vec<Role> {
+            let server = ServerId::new("server_default_id");
+            let mut roles = vec![Role::everyone(server.clone())];
+
+            for position in 1..=3 {
+                roles.push(Role {
+                    id: Id::Id(position * 10),
+                    server_id: server.clone(),
+                    name: format!("role {position}"),
+                    color: String::from("#ffffff"),
+                    server_permission: 0,
+                    position,
+                });
+            }
+
+            roles
+        }
+
+        fn positions(roles: &[Role]) -> Vec<u32> {
+            roles.iter().map(|role| role.position).collect()
+        }
+
+        #[test]
+        pub fn highest_position() {
+            assert_eq!(Role::highest_position(&[]), 0);
+            assert_eq!(Role::highest_position(&roles()), 3);
+        }
+
+        #[test]
+        pub fn reorder_as_owner() {
+            let mut roles = roles();
+            let moved = Role::reorder(&mut roles, &[Id::Id(30), Id::Id(10), Id::Id(20)], None);
+
+            assert_eq!(moved.map(|moved| moved.len()).ok(), Some(3));
+            assert_eq!(positions(&roles), vec![0, 2, 3, 1]);
+        }
+
+        #[test]
+        pub fn reorder_below_highest_role() {
+            let mut roles = roles();
+            let moved = Role::reorder(&mut roles, &[Id::Id(20), Id::Id(10), Id::Id(30)], Some(3));
+
+            assert_eq!(moved.map(|moved| moved.len()).ok(), Some(2));
+            assert_eq!(positions(&roles), vec![0, 2, 1, 3]);
+        }
+
+        #[test]
+        pub fn reorder_cannot_move_highest_role() {
+            let mut roles = roles();
+
+            assert!(matches!(
+                Role::reorder(&mut roles, &[Id::Id(10), Id::Id(30), Id::Id(20)], Some(3)),
+                Err(RoleError::AboveHighestRole)
+            ));
+            assert_eq!(positions(&roles), vec![0, 1, 2, 3]);
+        }
+
+        #[test]
+        pub fn reorder_needs_every_role() {
+            let mut roles = roles();
+
+            for order in [
+                vec![Id::Id(10), Id::Id(20)],
+                vec![Id::Id(10), Id::Id(10), Id::Id(20)],
+                vec![Id::Id(10), Id::Id(20), Id::Id(40)],
+            ] {
+                assert!(matches!(
+                    Role::reorder(&mut roles, &order, None),
+                    Err(RoleError::InvalidOrder)
+                ));
+            }
+        }
+    }
+
+    mod member {
+        use crate::{
+            member::{Member, MemberProfile},
+            messages::Date,
+            user::UserId,
+            utils::Id,
+        };
+        use fydia_utils::serde_json;
+
+        #[test]
+        pub fn profile_is_flattened_without_avatar() {
+            let member = Member {
+                id: UserId::new(2),
+                name: "bob".to_string(),
+                roles: vec![Id::Id(3)],
+                profile: MemberProfile {
+                    nickname: Some("bobby".to_string()),
+                    avatar: Some("avatar".to_string()),
+                    joined_at: Date::now(),
+                },
+            };
+
+            let value = serde_json::to_value(&member).unwrap_or_default();
+
+            assert_eq!(value["nickname"], "bobby");
+            assert!(value.get("joined_at").is_some());
+            assert!(value.get("avatar").is_none());
+            assert!(value.get("profile").is_none());
+        }
+    }
+
+    mod reaction {
+        use crate::{reaction::ReactionEmoji, utils::Id};
+        use fydia_utils::serde_json;
+
+        #[test]
+        pub fn emoji_is_tagged_by_kind() {
+            let unicode =
+                serde_json::to_value(ReactionEmoji::Unicode("👍".to_string())).unwrap_or_default();
+            let custom = serde_json::to_value(ReactionEmoji::Custom(Id::Id(4))).unwrap_or_default();
+
+            assert_eq!(unicode["unicode"], "👍");
+            assert_eq!(custom["custom"], 4);
+            assert!(matches!(
+                serde_json::from_str::<ReactionEmoji>(r#"{"custom": 4}"#),
+                Ok(ReactionEmoji::Custom(Id::Id(4)))
+            ));
+        }
+    }
+
+    mod thread {
+        use crate::{
+            channel::{ChannelId, ThreadMetadata},
+            messages::Date,
+        };
+        use chrono::Duration;
+
+        #[test]
+        pub fn inactive_after_auto_archive() {
+            let parent = ChannelId::new("PARENTCHANNELID");
+            let Ok(mut thread) = ThreadMetadata::new(parent, "MESSAGE", Some(60)) else {
+                panic!("60 minutes should be a valid auto archive");
+            };
+            let now = Date::new(thread.last_activity.0 + Duration::minutes(59));
+            let later = Date::new(thread.last_activity.0 + Duration::minutes(60));
+
+            assert!(!thread.is_inactive(&now));
+            assert!(thread.is_inactive(&later));
+
+            thread.archived = true;
+            assert!(!thread.is_inactive(&later));
+        }
+    }
+
+    mod pin {
+        use crate::{
+            channel::ChannelId,
+            messages::{Date, Message, MessageType},
+            pin::Pin,
+            user::{User, UserId},
+        };
+        use fydia_utils::serde_json;
+
+        #[test]
+        pub fn pin_contains_message() {
+            let Ok(message) = Message::new(
+                "pinned",
+                MessageType::TEXT,
+                false,
+                Date::now(),
+                User::default(),
+                ChannelId::new("THISISANIDOF15C"),
+            ) else {
+                panic!("message should be valid");
+            };
+            let pin = Pin::new(message, UserId::new(2));
+            let value = serde_json::to_value(&pin).unwrap_or_default();
+
+            assert_eq!(value["message"]["content"], "pinned");
+            assert_eq!(value["pinned_by"], 2);
+            assert!(value["pinned_at"].is_number());
+        }
+    }
+
+    mod revision {
+        use crate::{
+            channel::ChannelId,
+            messages::{Date, Message, MessageType},
+            user::User,
+        };
+        use fydia_utils::serde_json;
+
+        #[test]
+        pub fn edit_sets_edited_at() {
+            let Ok(mut message) = Message::new(
+                "first",
+                MessageType::TEXT,
+                false,
+                Date::now(),
+                User::default(),
+                ChannelId::new("THISISANIDOF15C"),
+            ) else {
+                panic!("message should be valid");
+            };
+            let value = serde_json::to_value(&message).unwrap_or_default();
+            assert!(value.get("edited_at").is_none());
+
+            assert!(message.edit("").is_err());
+            assert_eq!(message.content, "first");
+
+            let Ok(revision) = message.edit("second") else {
+                panic!("edit should be valid");
+            };
+            let value = serde_json::to_value(&message).unwrap_or_default();
+
+            assert_eq!(revision.content, "first");
+            assert_eq!(message.edited_at, Some(revision.edited_at));
+            assert_eq!(value["content"], "second");
+            assert_eq!(value["edited"], true);
+            assert!(value["edited_at"].is_number());
+        }
+    }
 }
@@ -33,6 +33,8 @@ pub struct User {
     #[serde(skip)]
     pub description: Option<String>,
     #[serde(skip)]
+    pub avatar: Option<String>,
+    #[serde(skip)]
     pub servers: Servers,
 }
 
@@ -80,6 +82,7 @@ impl User {
         self.email = from.email;
         self.password = from.password;
         self.description = from.description;
+        self.avatar = from.avatar;
         self.servers = from.servers;
     }
     /// Use it with precausion
@@ -0,0 +1,266 @@
+//! This module is related to the websocket protocol between client and server
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::{channel::ChannelId, event::Event, server::ServerId, utils::Id};
+use fydia_utils::{
+    generate_string,
+    serde::{Deserialize, Serialize},
+};
+use thiserror::Error;
+
+/// Number of events kept by session to be replayed on resume
+pub const REPLAY_BUFFER_SIZE: usize = 256;
+
+/// Time during which a session can be resumed after its last websocket is closed
+pub const RESUME_WINDOW: Duration = Duration::from_secs(300);
+
+/// `WebsocketCommand` represents all commands that a client can send by websocket.
+///
+///# Examples
+///```
+///use fydia_struct::websocket::WebsocketCommand;
+///
+///let command = WebsocketCommand::from_json(r#"{"type":"Ping","nonce":"1"}"#).unwrap();
+///assert_eq!(command, WebsocketCommand::Ping { nonce: Some("1".to_string()) });
+///```
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+#[serde(tag = "type")]
+pub enum WebsocketCommand {
+    /// Only receive events of these servers, all servers if empty
+    Subscribe {
+        server_ids: Vec<ServerId>,
+    },
+    StartTyping {
+        server_id: ServerId,
+        channel_id: ChannelId,
+    },
+    StopTyping {
+        server_id: ServerId,
+        channel_id: ChannelId,
+    },
+    StartDirectMessageTyping {
+        directmessage_id: Id<u32>,
+    },
+    StopDirectMessageTyping {
+        directmessage_id: Id<u32>,
+    },
+    /// Mark all messages of a channel as read until this message
+    MarkRead {
+        server_id: ServerId,
+        channel_id: ChannelId,
+        message_id: String,
+    },
+    Ping {
+        #[serde(default)]
+        nonce: Option<String>,
+    },
+    /// Receive all events sent after `sequence` in the session `session_id`
+    Resume {
+        session_id: String,
+        sequence: u64,
+    },
+}
+
+impl WebsocketCommand {
+    /// Parse a command from a text frame
+    ///
+    /// # Errors
+    /// Return an error if the frame isn't a valid command
+    pub fn from_json(frame: &str) -> Result<Self, WebsocketError> {
+        fydia_utils::serde_json::from_str(frame)
+            .map_err(|error| WebsocketError::InvalidCommand(error.to_string()))
+    }
+
+    /// Return the name of the command
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebsocketCommand::Subscribe { .. } => "Subscribe",
+            WebsocketCommand::StartTyping { .. } => "StartTyping",
+            WebsocketCommand::StopTyping { .. } => "StopTyping",
+            WebsocketCommand::StartDirectMessageTyping { .. } => "StartDirectMessageTyping",
+            WebsocketCommand::StopDirectMessageTyping { .. } => "StopDirectMessageTyping",
+            WebsocketCommand::MarkRead { .. } => "MarkRead",
+            WebsocketCommand::Ping { .. } => "Ping",
+            WebsocketCommand::Resume { .. } => "Resume",
+        }
+    }
+}
+
+/// `WebsocketFrame` represents all replies of the server to a `WebsocketCommand`
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+#[serde(tag = "type")]
+pub enum WebsocketFrame {
+    /// First frame sent on a new connection
+    Hello {
+        session_id: String,
+        sequence: u64,
+    },
+    /// An event with its sequence number.
+    /// Events can be received twice just after a resume, clients should
+    /// ignore a sequence lower or equal than the last received one.
+    Dispatch {
+        sequence: u64,
+        event: Box<Event>,
+    },
+    /// Missed events were sent, `sequence` is the last sequence of the session
+    Resumed {
+        sequence: u64,
+    },
+    /// Session can't be resumed, client should fetch its state again
+    InvalidSession,
+    Pong {
+        nonce: Option<String>,
+    },
+    Error {
+        code: WebsocketErrorCode,
+        message: String,
+        command: Option<String>,
+    },
+}
+
+impl WebsocketFrame {
+    /// Create an error frame from a `WebsocketError` and the name of the command
+    pub fn error(error: &WebsocketError, command: Option<&str>) -> Self {
+        Self::Error {
+            code: error.code(),
+            message: error.to_string(),
+            command: command.map(ToString::to_string),
+        }
+    }
+}
+
+/// Code of an error frame
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "fydia_utils::serde")]
+pub enum WebsocketErrorCode {
+    InvalidCommand,
+    UnsupportedFrame,
+    NotMember,
+    UnknownChannel,
+    UnknownMessage,
+    MissingPermission,
+    Internal,
+}
+
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+/// `WebsocketError` represents all errors of a `WebsocketCommand`
+pub enum WebsocketError {
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
+    #[error("Only text frames are supported")]
+    UnsupportedFrame,
+    #[error("You aren't a member of this server")]
+    NotMember,
+    #[error("You aren't a member of this direct message")]
+    NotDirectMessageMember,
+    #[error("No channel with this id in this server")]
+    UnknownChannel,
+    #[error("No message with this id in this channel")]
+    UnknownMessage,
+    #[error("You don't have the permission to do this in this channel")]
+    MissingPermission,
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl WebsocketError {
+    /// Return the code sent in error frame
+    pub fn code(&self) -> WebsocketErrorCode {
+        match self {
+            WebsocketError::InvalidCommand(_) => WebsocketErrorCode::InvalidCommand,
+            WebsocketError::UnsupportedFrame => WebsocketErrorCode::UnsupportedFrame,
+            WebsocketError::NotMember | WebsocketError::NotDirectMessageMember => {
+                WebsocketErrorCode::NotMember
+            }
+            WebsocketError::UnknownChannel => WebsocketErrorCode::UnknownChannel,
+            WebsocketError::UnknownMessage => WebsocketErrorCode::UnknownMessage,
+            WebsocketError::MissingPermission => WebsocketErrorCode::MissingPermission,
+            WebsocketError::Internal(_) => WebsocketErrorCode::Internal,
+        }
+    }
+}
+
+/// `WebsocketSession` contains the sequence and the last events sent to an user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebsocketSession {
+    id: String,
+    sequence: u64,
+    buffer: VecDeque<(u64, Event)>,
+    capacity: usize,
+    disconnected_at: Option<Instant>,
+}
+
+impl WebsocketSession {
+    /// Create a new session that keeps `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            id: generate_string(32),
+            sequence: 0,
+            buffer: VecDeque::with_capacity(capacity),
+            capacity,
+            disconnected_at: None,
+        }
+    }
+
+    /// Mark the session as connected or disconnected at `now`
+    pub fn set_connected(&mut self, connected: bool, now: Instant) {
+        self.disconnected_at = if connected { None } else { Some(now) };
+    }
+
+    /// Return true if session has been disconnected for longer than `window`
+    pub fn is_expired(&self, now: Instant, window: Duration) -> bool {
+        self.disconnected_at
+            .is_some_and(|disconnected_at| now.duration_since(disconnected_at) > window)
+    }
+
+    /// Return the id of session
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Return the sequence of the last event
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Add an event in session and return its sequence
+    pub fn push(&mut self, event: Event) -> u64 {
+        self.sequence += 1;
+
+        if self.buffer.len() == self.capacity {
+            self.buffer.pop_front();
+        }
+
+        if self.capacity > 0 {
+            self.buffer.push_back((self.sequence, event));
+        }
+
+        self.sequence
+    }
+
+    /// Return all events sent after `sequence`.
+    ///
+    /// Return `None` if some events aren't in buffer anymore
+    /// or if `sequence` is greater than the sequence of session
+    pub fn replay_after(&self, sequence: u64) -> Option<Vec<(u64, Event)>> {
+        if sequence > self.sequence || self.sequence - sequence > self.buffer.len() as u64 {
+            return None;
+        }
+
+        Some(
+            self.buffer
+                .iter()
+                .filter(|(event_sequence, _)| *event_sequence > sequence)
+                .cloned()
+                .collect(),
+        )
+    }
+}